@@ -1,4 +1,5 @@
 pub mod slack;
+#[allow(dead_code)]
 pub mod watchdog;
 
 #[cfg(test)]
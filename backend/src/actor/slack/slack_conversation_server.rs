@@ -41,11 +41,12 @@ where
     pub fn new(factory: Factory) -> SlackConversationServer<Msg, Factory> {
         SlackConversationServer::<Msg, Factory> {
             factory,
-            _phantom_data: PhantomData::default(),
+            _phantom_data: PhantomData,
         }
     }
 }
 
+#[allow(clippy::large_enum_variant)]
 pub enum SlackConversationServerMsg<Msg>
 where
     Msg: ractor::Message,
@@ -55,11 +56,13 @@ where
         channel: SlackChannelId,
         event: SlackPushEventCallback,
     },
+    #[allow(dead_code)]
     Get {
         team: SlackTeamId,
         channel: SlackChannelId,
         reply: RpcReplyPort<ActorRef<Msg>>,
     },
+    #[allow(dead_code)]
     Stop {
         team: SlackTeamId,
         channel: SlackChannelId,
@@ -144,7 +147,10 @@ where
                 channel,
                 reason,
             } => match state.conversations.remove(&(team, channel)) {
-                Some(e) => Ok(e.stop(reason)),
+                Some(e) => {
+                    e.stop(reason);
+                    Ok(())
+                }
                 None => Ok(()),
             },
             SlackConversationServerMsg::OnPushEvent {
@@ -87,23 +87,20 @@ impl Actor for Watchdog {
                 }
             },
             WatchdogMsg::Timeout(actor) => {
-                match state.subjects.remove(&actor) {
-                    Some(Registration { actor, .. }) => {
-                        info!(
-                            actor_id = actor.get_id().to_string(),
-                            actor_name = actor.get_name(),
-                            "watchdog timeout, killing",
-                        );
-                        actor.kill();
-                        state.kills += 1;
-                    }
-                    _ => (),
-                };
+                if let Some(Registration { actor, .. }) = state.subjects.remove(&actor) {
+                    info!(
+                        actor_id = actor.get_id().to_string(),
+                        actor_name = actor.get_name(),
+                        "watchdog timeout, killing",
+                    );
+                    actor.kill();
+                    state.kills += 1;
+                }
                 Ok(())
             }
             WatchdogMsg::Stats(reply) => reply
                 .send(WatchdogStats { kills: state.kills })
-                .map_err(|e| ActorProcessingErr::from(e)),
+                .map_err(ActorProcessingErr::from),
         }
     }
 
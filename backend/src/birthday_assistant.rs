@@ -1,12 +1,35 @@
-use crate::model::Employee;
+use crate::model::{Employee, FieldVisibility};
 use anyhow::{anyhow, Result};
 use async_openai::config::OpenAIConfig;
 use async_openai::types::*;
-use time::OffsetDateTime;
+use time::{Date, OffsetDateTime};
 use tracing::{info, info_span, instrument};
 
 type Client = async_openai::Client<OpenAIConfig>;
 
+/// The input to the assistant. The message is posted in public channels, so the age is only
+/// included when everyone can see the date of birth.
+pub(crate) fn birthday_prompt(e: &Employee, today: Date) -> String {
+    let dob = e
+        .dob
+        .filter(|_| e.dob_visibility == FieldVisibility::Everyone);
+
+    let Some(dob) = dob else {
+        return format!("Det er {} som har bursdag i dag!", e.name);
+    };
+
+    let mut age = today.year() - dob.year();
+
+    if (today.month() as u8, today.day()) < (dob.month() as u8, dob.day()) {
+        age -= 1;
+    }
+
+    format!(
+        "Det er {} som har bursdag i dag! Vedkommende blir {} år",
+        e.name, age
+    )
+}
+
 /// An OpenAI-based birthday message assistant.
 #[derive(Clone)]
 pub struct BirthdayAssistant {
@@ -23,24 +46,8 @@ impl BirthdayAssistant {
     }
 
     #[instrument(skip(self))]
-    pub(crate) async fn create_message(self: &Self, e: &Employee) -> Result<String> {
-        let dob = e
-            .dob
-            .ok_or(anyhow!("Employee doesn't have an date of birth set"))?;
-
-        let (now_year, now_day) = OffsetDateTime::now_utc().to_ordinal_date();
-        let (dob_year, dob_day) = dob.to_ordinal_date();
-
-        let mut age = now_year - dob_year;
-
-        if now_day < dob_day {
-            age -= 1;
-        }
-
-        let input = format!(
-            "Det er {} som har bursdag i dag! Vedkommende blir {} år",
-            e.name, age
-        );
+    pub(crate) async fn create_message(&self, e: &Employee) -> Result<String> {
+        let input = birthday_prompt(e, OffsetDateTime::now_utc().date());
 
         let (run, message) = self.run_message(input).await?;
 
@@ -50,7 +57,7 @@ impl BirthdayAssistant {
     }
 
     #[instrument(skip(self))]
-    async fn run_message(self: &Self, input: String) -> Result<(RunObject, String)> {
+    async fn run_message(&self, input: String) -> Result<(RunObject, String)> {
         let thread = {
             let _span = info_span!("thread.create");
 
@@ -1,20 +1,29 @@
 use crate::birthday_assistant::BirthdayAssistant;
-use crate::model::{Dao, Employee, EmployeeDao, SomeAccount, SLACK};
-use crate::slack_interaction_server::SlackInteractionServerMsg::AddInteraction;
+use crate::model::{
+    BirthdayConversation, BirthdayConversationId, BirthdayDao, Dao, Employee, EmployeeDao,
+    EmployeeId, SomeAccount, SomeNetwork,
+};
+use crate::slack_interaction_server::SlackInteractionServerMsg::{
+    AddInteraction, RemoveInteraction, RestoreInteraction,
+};
 use crate::slack_interaction_server::{
     map_err, InteractionSubscriber, SlackInteractionId, SlackInteractionServer,
 };
 use anyhow::anyhow;
 use ractor::concurrency::JoinHandle;
-use ractor::{call, Actor, ActorProcessingErr, ActorRef, MessagingErr};
+use ractor::{call, cast, Actor, ActorProcessingErr, ActorRef, MessagingErr};
 use slack_morphism::prelude::*;
-use std::ops::Deref;
 use std::sync::Arc;
 use std::time::Duration;
+use time::{Date, OffsetDateTime};
 use tracing::*;
 use BirthdayActorMsg::*;
 use BirthdayActorState::*;
 
+/// How long a conversation waits for its buttons to be clicked. The conversation is stored with
+/// its expiry, so it has to outlive a restart.
+pub(crate) const CONVERSATION_TIMEOUT: Duration = Duration::from_secs(12 * 60 * 60);
+
 pub(crate) struct BirthdayActor {
     dao: Dao,
     birthday_assistant: BirthdayAssistant,
@@ -31,21 +40,106 @@ impl BirthdayActor {
         birthday_assistant: BirthdayAssistant,
         slack_interaction_actor: ActorRef<<SlackInteractionServer as Actor>::Msg>,
         slack_client: Arc<crate::bot::SlackClient>,
+        timeout_duration: Duration,
     ) -> Self {
         Self {
             dao,
             birthday_assistant,
             slack_interaction_actor,
             slack_client,
-            timeout_duration: Duration::from_secs(10),
+            timeout_duration,
         }
     }
 
+    fn expires_at(&self) -> OffsetDateTime {
+        OffsetDateTime::now_utc() + self.timeout_duration
+    }
+
     pub(crate) async fn on_init(
         &self,
         myself: ActorRef<BirthdayActorMsg>,
-        New { team, channel, who }: &New,
+        new: &New,
     ) -> anyhow::Result<BirthdayActorState> {
+        let New {
+            team,
+            channel,
+            employee,
+            announcement,
+        } = new;
+
+        let posted = self.post_initial_message(myself.clone(), new).await;
+
+        // The announcement was claimed by the scheduler, give it back so that it is tried again.
+        if let (Err(_), Some(date)) = (&posted, announcement) {
+            if let Err(e) = self
+                .dao
+                .delete_birthday_announcement(*employee, *date)
+                .await
+            {
+                warn!("unable to delete birthday announcement: {}", e);
+            }
+        }
+
+        let (interaction_id, employee, some_account, res) = posted?;
+        let who = employee.name.clone();
+
+        info!(
+            "Posted slack message: channel={}, ts={}",
+            res.channel, res.ts
+        );
+
+        let conversation = self
+            .dao
+            .insert_birthday_conversation(
+                team.to_string(),
+                channel.to_string(),
+                res.ts.to_string(),
+                who.clone(),
+                Some(employee.id),
+                interaction_id.0,
+                self.expires_at(),
+            )
+            .await?;
+
+        let timer = myself.send_after(self.timeout_duration, || Timeout);
+
+        Ok(AwaitingInteraction(AwaitingInteraction {
+            id: conversation.id,
+            timer,
+            channel: channel.clone(),
+            who,
+            employee: Some(employee),
+            some_account,
+            ts: res.ts,
+            birthday_message: None,
+            generate_interaction: Some(interaction_id),
+            send_interaction: None,
+            send_dm_interaction: None,
+        }))
+    }
+
+    /// Posts the message with the button that generates a greeting.
+    async fn post_initial_message(
+        &self,
+        myself: ActorRef<BirthdayActorMsg>,
+        New {
+            team,
+            channel,
+            employee,
+            ..
+        }: &New,
+    ) -> anyhow::Result<(
+        SlackInteractionId,
+        Employee,
+        Option<SomeAccount>,
+        SlackApiChatPostMessageResponse,
+    )> {
+        let employee = self
+            .dao
+            .employee_by_id(*employee)
+            .await?
+            .ok_or_else(|| anyhow!("employee not found: {}", employee))?;
+
         let interaction_id = call!(
             self.slack_interaction_actor,
             AddInteraction,
@@ -56,55 +150,109 @@ impl BirthdayActor {
 
         info!("interaction_id: {:?}", interaction_id);
 
-        info!("got message: {:?}", who);
+        let some_account = self
+            .dao
+            .some_account_for_network(employee.id, SomeNetwork::Slack, Some(team.clone().0))
+            .await?;
 
-        let who = who.clone();
+        let message =
+            BirthdayMessage::initial(&employee.name, &some_account, interaction_id.clone());
 
-        let employee = self.dao.employee_by_name(who.clone()).await?;
+        let req = SlackApiChatPostMessageRequest::new(channel.clone(), message.render_template());
+
+        let session = self
+            .slack_client
+            .client
+            .open_session(&self.slack_client.token);
+
+        let res = match session.chat_post_message(&req).await {
+            Ok(res) => res,
+            Err(e) => {
+                self.remove_interactions(vec![interaction_id]);
+                return Err(e.into());
+            }
+        };
+
+        Ok((interaction_id, employee, some_account, res))
+    }
+
+    /// Picks up a conversation that was started before a restart. The interactions are registered
+    /// again so that the buttons on the existing Slack message reach this actor.
+    pub(crate) async fn on_restore(
+        &self,
+        myself: ActorRef<BirthdayActorMsg>,
+        conversation: &BirthdayConversation,
+    ) -> anyhow::Result<BirthdayActorState> {
+        info!(id = conversation.id.0, "Restoring birthday conversation");
+
+        let employee = match conversation.employee {
+            Some(id) => self.dao.employee_by_id(id).await?,
+            None => None,
+        };
 
         let some_account = match employee.clone() {
             Some(e) => {
                 self.dao
-                    .some_account_for_network(e.id, SLACK.0.clone(), Some(team.clone().0))
+                    .some_account_for_network(
+                        e.id,
+                        SomeNetwork::Slack,
+                        Some(conversation.team.clone()),
+                    )
                     .await?
             }
             _ => None,
         };
 
-        let message = BirthdayMessage::initial(&who, &some_account, interaction_id);
-
-        let req = SlackApiChatPostMessageRequest::new(channel.clone(), message.render_template());
-
-        let session = self
-            .slack_client
-            .client
-            .open_session(&self.slack_client.token);
-
-        let res = session.chat_post_message(&req).await?;
+        for interaction_id in [
+            conversation.generate_interaction,
+            conversation.send_interaction,
+            conversation.send_dm_interaction,
+        ]
+        .into_iter()
+        .flatten()
+        {
+            cast!(
+                self.slack_interaction_actor,
+                RestoreInteraction(
+                    SlackInteractionId(interaction_id),
+                    Box::new(BirthdayActorInteractionSubscriber {
+                        actor: myself.clone()
+                    })
+                )
+            )?;
+        }
 
-        info!(
-            "Posted slack message: channel={}, ts={}",
-            res.channel, res.ts
-        );
+        // An expired conversation times out right away
+        let remaining = (conversation.expires_at - OffsetDateTime::now_utc())
+            .try_into()
+            .unwrap_or(Duration::ZERO);
 
-        let timer = myself.send_after(self.timeout_duration, || Timeout);
+        let timer = myself.send_after(remaining, || Timeout);
 
         Ok(AwaitingInteraction(AwaitingInteraction {
+            id: conversation.id,
             timer,
-            channel: channel.clone(),
-            who,
+            channel: SlackChannelId::new(conversation.channel.clone()),
+            who: conversation.who.clone(),
             employee,
             some_account,
-            ts: res.ts,
-            birthday_message: None,
+            ts: SlackTs::new(conversation.ts.clone()),
+            birthday_message: conversation.birthday_message.clone(),
+            generate_interaction: conversation.generate_interaction.map(SlackInteractionId),
+            send_interaction: conversation.send_interaction.map(SlackInteractionId),
+            send_dm_interaction: conversation.send_dm_interaction.map(SlackInteractionId),
         }))
     }
 
-    pub(crate) async fn create_suggestion(
+    pub(crate) async fn on_interaction(
         &self,
         myself: ActorRef<BirthdayActorMsg>,
+        user: Option<SlackUserId>,
         event: SlackInteractionActionInfo,
-        AwaitingInteraction {
+        state: &AwaitingInteraction,
+    ) -> anyhow::Result<BirthdayActorState> {
+        let AwaitingInteraction {
+            id,
             timer,
             channel,
             who,
@@ -112,8 +260,9 @@ impl BirthdayActor {
             some_account,
             ts,
             birthday_message,
-        }: &AwaitingInteraction,
-    ) -> anyhow::Result<BirthdayActorState> {
+            ..
+        } = state;
+
         info!("got interaction block action: {:?}", event.clone());
 
         timer.abort();
@@ -127,68 +276,242 @@ impl BirthdayActor {
             Some(s) if s == "generate-message" => {
                 info!("generating message");
 
-                let message = BirthdayMessage::busy(&who, some_account, &birthday_message);
+                let message = BirthdayMessage::busy(who, some_account, birthday_message);
 
                 self.update_message(&message, channel, ts).await;
 
-                match self.birthday_assistant.create_message(&employee).await {
-                    Ok(birthday_message) => {
-                        info!("New birthday message: {}", birthday_message);
-
-                        let generate_interaction_id = call!(
-                            self.slack_interaction_actor.clone(),
-                            AddInteraction,
-                            Box::new(BirthdayActorInteractionSubscriber {
-                                actor: myself.clone()
-                            })
-                        )?;
-
-                        let send_interaction_id = call!(
-                            self.slack_interaction_actor.clone(),
-                            AddInteraction,
-                            Box::new(BirthdayActorInteractionSubscriber {
-                                actor: myself.clone()
-                            })
-                        )?;
-
-                        let message = BirthdayMessage::suggestion(
-                            &who,
-                            some_account,
-                            generate_interaction_id,
-                            &birthday_message,
-                            Some(send_interaction_id),
-                        );
-
-                        self.update_message(&message, channel, ts).await;
-
-                        AwaitingInteraction(AwaitingInteraction {
-                            timer: myself.send_after(self.timeout_duration, || Timeout),
-                            channel: channel.clone(),
-                            who: who.clone(),
-                            employee: Some(employee.clone()),
-                            some_account: some_account.clone(),
-                            ts: ts.clone(),
-                            birthday_message: Some(birthday_message),
-                        })
-                    }
-                    Err(e) => {
-                        warn!("unable to create message: {}", e);
-                        BirthdayActorState::Fail(Fail {})
-                    }
-                }
+                let birthday_message = self
+                    .birthday_assistant
+                    .create_message(employee)
+                    .await
+                    .map_err(|e| anyhow!("unable to create message: {}", e))?;
+
+                info!("New birthday message: {}", birthday_message);
+
+                let generate_interaction_id = call!(
+                    self.slack_interaction_actor.clone(),
+                    AddInteraction,
+                    Box::new(BirthdayActorInteractionSubscriber {
+                        actor: myself.clone()
+                    })
+                )?;
+
+                let send_interaction_id = call!(
+                    self.slack_interaction_actor.clone(),
+                    AddInteraction,
+                    Box::new(BirthdayActorInteractionSubscriber {
+                        actor: myself.clone()
+                    })
+                )?;
+
+                let send_dm_interaction_id = call!(
+                    self.slack_interaction_actor.clone(),
+                    AddInteraction,
+                    Box::new(BirthdayActorInteractionSubscriber {
+                        actor: myself.clone()
+                    })
+                )?;
+
+                self.dao
+                    .update_birthday_conversation(
+                        *id,
+                        Some(birthday_message.clone()),
+                        Some(generate_interaction_id.0),
+                        Some(send_interaction_id.0),
+                        Some(send_dm_interaction_id.0),
+                        self.expires_at(),
+                    )
+                    .await?;
+
+                let message = BirthdayMessage::suggestion(
+                    who,
+                    some_account,
+                    generate_interaction_id.clone(),
+                    &birthday_message,
+                    Some(send_interaction_id.clone()),
+                    Some(send_dm_interaction_id.clone()),
+                );
+
+                self.update_message(&message, channel, ts).await;
+
+                // The buttons of the previous suggestion are gone
+                self.remove_interactions(state.interactions());
+
+                AwaitingInteraction(AwaitingInteraction {
+                    id: *id,
+                    timer: myself.send_after(self.timeout_duration, || Timeout),
+                    channel: channel.clone(),
+                    who: who.clone(),
+                    employee: Some(employee.clone()),
+                    some_account: some_account.clone(),
+                    ts: ts.clone(),
+                    birthday_message: Some(birthday_message),
+                    generate_interaction: Some(generate_interaction_id),
+                    send_interaction: Some(send_interaction_id),
+                    send_dm_interaction: Some(send_dm_interaction_id),
+                })
             }
-            _ => BirthdayActorState::Fail(Fail {}),
+            Some(s) if s == "send-message" => self.send_greeting(state, user, false).await?,
+            Some(s) if s == "send-direct-message" => self.send_greeting(state, user, true).await?,
+            value => return Err(anyhow!("unknown action: {:?}", value)),
         };
 
         Ok(updated)
     }
 
+    /// Posts the generated greeting, either in the channel or as a direct message to the employee,
+    /// and marks the interactive message as completed.
+    async fn send_greeting(
+        &self,
+        state: &AwaitingInteraction,
+        sent_by: Option<SlackUserId>,
+        direct: bool,
+    ) -> anyhow::Result<BirthdayActorState> {
+        let AwaitingInteraction {
+            id,
+            channel,
+            who,
+            employee,
+            some_account,
+            ts,
+            birthday_message,
+            ..
+        } = state;
+
+        let birthday_message = birthday_message
+            .clone()
+            .ok_or_else(|| anyhow!("no birthday message has been generated"))?;
+
+        let user_id = some_account
+            .clone()
+            .and_then(|sa| sa.subject)
+            .map(SlackUserId);
+
+        let target = if direct {
+            user_id
+                .clone()
+                .map(|u| SlackChannelId(u.0))
+                .ok_or_else(|| anyhow!("{} doesn't have a Slack account", who))?
+        } else {
+            channel.clone()
+        };
+
+        info!(direct, "Sending birthday greeting, channel={}", target);
+
+        let greeting = BirthdayGreetingMessage {
+            who: who.clone(),
+            user_id,
+            birthday_message: birthday_message.clone(),
+        };
+
+        let req = SlackApiChatPostMessageRequest::new(target, greeting.render_template());
+
+        let session = self
+            .slack_client
+            .client
+            .open_session(&self.slack_client.token);
+
+        let res = session.chat_post_message(&req).await?;
+
+        let greeting_id = self
+            .dao
+            .insert_birthday_greeting(
+                employee.as_ref().map(|e| e.id),
+                who.clone(),
+                res.channel.to_string(),
+                res.ts.to_string(),
+                birthday_message.clone(),
+                sent_by.as_ref().map(|u| u.to_string()),
+            )
+            .await?;
+
+        info!(greeting = greeting_id.0, "Birthday greeting sent");
+
+        let message = BirthdayMessage::sent(who, some_account, &birthday_message, sent_by, direct);
+
+        self.update_message(&message, channel, ts).await;
+
+        self.dao.delete_birthday_conversation(*id).await?;
+
+        Ok(BirthdayActorState::Done(Done {
+            interactions: state.interactions(),
+        }))
+    }
+
+    /// Marks the Slack message as expired and forgets about the conversation.
+    async fn on_timeout(
+        &self,
+        AwaitingInteraction {
+            id,
+            channel,
+            who,
+            some_account,
+            ts,
+            birthday_message,
+            ..
+        }: &AwaitingInteraction,
+    ) {
+        info!("Expiring conversation, ts={}", ts);
+
+        let message =
+            BirthdayMessage::deleted(who.clone(), some_account.clone(), birthday_message.clone());
+
+        self.update_message(&message, channel, ts).await;
+
+        if let Err(e) = self.dao.delete_birthday_conversation(*id).await {
+            warn!("unable to delete birthday conversation: {}", e);
+        }
+    }
+
+    /// Shows the error below the buttons, so that they can be clicked again, and restarts the
+    /// timer.
+    async fn on_failure(
+        &self,
+        myself: &ActorRef<BirthdayActorMsg>,
+        state: &mut AwaitingInteraction,
+        error: &anyhow::Error,
+    ) {
+        let message = BirthdayMessage::failed(state, format!("{}", error));
+
+        self.update_message(&message, &state.channel, &state.ts)
+            .await;
+
+        if let Err(e) = self
+            .dao
+            .update_birthday_conversation(
+                state.id,
+                state.birthday_message.clone(),
+                state.generate_interaction.as_ref().map(|i| i.0),
+                state.send_interaction.as_ref().map(|i| i.0),
+                state.send_dm_interaction.as_ref().map(|i| i.0),
+                self.expires_at(),
+            )
+            .await
+        {
+            warn!("unable to update birthday conversation: {}", e);
+        }
+
+        state.timer = myself.send_after(self.timeout_duration, || Timeout);
+    }
+
+    /// Unregisters interactions that this actor won't handle anymore.
+    fn remove_interactions(&self, interactions: Vec<SlackInteractionId>) {
+        for interaction_id in interactions {
+            if let Err(e) = cast!(
+                self.slack_interaction_actor,
+                RemoveInteraction(interaction_id)
+            ) {
+                warn!("unable to remove interaction: {}", e);
+            }
+        }
+    }
+
     async fn update_message(
         &self,
         message: &BirthdayMessage,
         channel: &SlackChannelId,
         ts: &SlackTs,
-    ) -> () {
+    ) {
         let req =
             SlackApiChatUpdateRequest::new(channel.clone(), message.render_template(), ts.clone());
 
@@ -208,15 +531,24 @@ impl BirthdayActor {
 #[derive(Debug)]
 pub(crate) struct Fail;
 
+#[derive(Debug)]
+pub(crate) struct Done {
+    /// Removed from the interaction server when the actor stops.
+    interactions: Vec<SlackInteractionId>,
+}
+
 #[derive(Debug)]
 pub(crate) struct New {
     team: SlackTeamId,
     channel: SlackChannelId,
-    who: String,
+    employee: EmployeeId,
+    /// The day of a scheduled announcement.
+    announcement: Option<Date>,
 }
 
 #[derive(Debug)]
 pub(crate) struct AwaitingInteraction {
+    id: BirthdayConversationId,
     timer: TimerT,
     channel: SlackChannelId,
     who: String,
@@ -224,65 +556,74 @@ pub(crate) struct AwaitingInteraction {
     some_account: Option<SomeAccount>,
     ts: SlackTs,
     birthday_message: Option<String>,
+    generate_interaction: Option<SlackInteractionId>,
+    send_interaction: Option<SlackInteractionId>,
+    send_dm_interaction: Option<SlackInteractionId>,
+}
+
+impl AwaitingInteraction {
+    fn interactions(&self) -> Vec<SlackInteractionId> {
+        [
+            &self.generate_interaction,
+            &self.send_interaction,
+            &self.send_dm_interaction,
+        ]
+        .into_iter()
+        .flatten()
+        .cloned()
+        .collect()
+    }
 }
 
 #[derive(Debug)]
+#[allow(clippy::large_enum_variant)]
 pub enum BirthdayActorState {
     Fail(Fail),
+    Done(Done),
     New(New),
+    Restoring(BirthdayConversation),
     AwaitingInteraction(AwaitingInteraction),
 }
 
-impl BirthdayActorState {
-    fn ts(&self) -> Option<(SlackChannelId, SlackTs)> {
-        match self {
-            BirthdayActorState::Fail(..) => None,
-            New(..) => None,
-            AwaitingInteraction(AwaitingInteraction { channel, ts, .. }) => {
-                Some((channel.clone(), ts.clone()))
-            }
-        }
-    }
-
-    fn birthday_message(&self) -> Option<String> {
-        match self {
-            AwaitingInteraction(AwaitingInteraction {
-                birthday_message, ..
-            }) => birthday_message.clone(),
-            _ => None,
-        }
-    }
-
-    fn some_account(&self) -> Option<SomeAccount> {
-        match self {
-            AwaitingInteraction(AwaitingInteraction { some_account, .. }) => some_account.clone(),
-            _ => None,
-        }
-    }
-}
-
+#[allow(clippy::large_enum_variant)]
 pub enum BirthdayActorMsg {
     Init,
-    OnInteraction(SlackInteractionActionInfo),
+    OnInteraction(Option<SlackUserId>, SlackInteractionActionInfo),
     Timeout,
 }
 
+pub enum BirthdayActorArguments {
+    /// The date is set when the scheduler has claimed the announcement of the day, it is released
+    /// again if the message can't be posted.
+    New(SlackTeamId, SlackChannelId, EmployeeId, Option<Date>),
+    Restore(BirthdayConversation),
+}
+
 #[ractor::async_trait]
 impl Actor for BirthdayActor {
     type Msg = BirthdayActorMsg;
     type State = BirthdayActorState;
-    type Arguments = (SlackTeamId, SlackChannelId, String);
+    type Arguments = BirthdayActorArguments;
 
     async fn pre_start(
         &self,
         _myself: ActorRef<Self::Msg>,
-        (team, channel, who): Self::Arguments,
+        args: Self::Arguments,
     ) -> Result<Self::State, ActorProcessingErr> {
+        let state = match args {
+            BirthdayActorArguments::New(team, channel, employee, announcement) => New(New {
+                team,
+                channel,
+                employee,
+                announcement,
+            }),
+            BirthdayActorArguments::Restore(conversation) => Restoring(conversation),
+        };
+
         _myself
             .send_message(Init)
-            .map(|_| New(New { team, channel, who }))
+            .map(|_| state)
             .map_err(ActorProcessingErr::from)
-        // Ok(New(New { team, channel, who }))
     }
 
     async fn post_stop(
@@ -290,18 +631,12 @@ impl Actor for BirthdayActor {
         _: ActorRef<Self::Msg>,
         state: &mut Self::State,
     ) -> Result<(), ActorProcessingErr> {
-        if let Some((channel, ts)) = state.ts() {
-            info!("Stopping, ts={}", ts);
+        info!("Stopping");
 
-            let message = BirthdayMessage::deleted(
-                "unknown".to_string(),
-                state.some_account(),
-                state.birthday_message(),
-            );
-
-            self.update_message(&message, &channel, &ts).await;
-        } else {
-            info!("Stopping")
+        match state {
+            AwaitingInteraction(s) => self.remove_interactions(s.interactions()),
+            BirthdayActorState::Done(done) => self.remove_interactions(done.interactions.clone()),
+            _ => {}
         }
 
         Ok(())
@@ -313,16 +648,28 @@ impl Actor for BirthdayActor {
         message: Self::Msg,
         state: &mut Self::State,
     ) -> Result<(), ActorProcessingErr> {
-        let internal = match (message, state.deref()) {
-            (Init, New(new)) => self.on_init(myself, new).await,
-            (OnInteraction(event), AwaitingInteraction(s)) => {
-                self.create_suggestion(myself, event, s).await
+        let internal = match (message, &mut *state) {
+            (Init, New(new)) => self.on_init(myself.clone(), new).await,
+            (Init, Restoring(conversation)) => self.on_restore(myself.clone(), conversation).await,
+            (OnInteraction(user, event), AwaitingInteraction(s)) => {
+                match self.on_interaction(myself.clone(), user, event, s).await {
+                    Err(e) => {
+                        // The conversation stays registered, so the user gets to try again
+                        warn!("Unable to handle interaction: {}", e);
+                        self.on_failure(&myself, s, &e).await;
+                        return Ok(());
+                    }
+                    internal => internal,
+                }
             }
-            (Timeout, _) => {
+            (Timeout, s) => {
                 info!("User interaction timed out");
 
+                if let AwaitingInteraction(s) = s {
+                    self.on_timeout(s).await;
+                }
+
                 myself.stop(None);
-                // We don't want to lose the internal state, so just return here.
                 return Ok(());
             }
             _ => {
@@ -333,6 +680,10 @@ impl Actor for BirthdayActor {
         };
 
         match internal {
+            Ok(BirthdayActorState::Done(done)) => {
+                *state = BirthdayActorState::Done(done);
+                myself.stop(None);
+            }
             Ok(internal) => *state = internal,
             Err(e) => {
                 warn!("Internal error: {}", e);
@@ -350,9 +701,13 @@ struct BirthdayActorInteractionSubscriber {
 }
 
 impl InteractionSubscriber for BirthdayActorInteractionSubscriber {
-    fn on_interaction(&self, event: SlackInteractionActionInfo) -> Result<(), MessagingErr<()>> {
+    fn on_interaction(
+        &self,
+        user: Option<SlackUserId>,
+        event: SlackInteractionActionInfo,
+    ) -> Result<(), MessagingErr<()>> {
         self.actor
-            .send_message(OnInteraction(event))
+            .send_message(OnInteraction(user, event))
             .map_err(map_err)
     }
 }
@@ -364,72 +719,101 @@ pub struct BirthdayMessage {
     pub user_id: Option<SlackUserId>,
     pub generate_message_id: Option<SlackInteractionId>,
     pub send_id: Option<SlackInteractionId>,
+    pub send_dm_id: Option<SlackInteractionId>,
 
     pub birthday_message: Option<String>,
     pub busy: bool,
     pub deleted: bool,
+    pub sent: Option<(Option<SlackUserId>, bool)>,
+    pub error: Option<String>,
 }
 
 impl BirthdayMessage {
+    fn failed(state: &AwaitingInteraction, error: String) -> BirthdayMessage {
+        BirthdayMessage {
+            who: state.who.clone(),
+            user_id: state
+                .some_account
+                .clone()
+                .and_then(|sa| sa.subject)
+                .map(SlackUserId),
+            generate_message_id: state.generate_interaction.clone(),
+            send_id: state.send_interaction.clone(),
+            send_dm_id: state.send_dm_interaction.clone(),
+            birthday_message: state.birthday_message.clone(),
+            busy: false,
+            deleted: false,
+            sent: None,
+            error: Some(error),
+        }
+    }
+
     fn initial(
-        who: &String,
+        who: &str,
         some_account: &Option<SomeAccount>,
         generate_message_id: SlackInteractionId,
     ) -> BirthdayMessage {
         BirthdayMessage {
-            who: who.clone(),
+            who: who.to_string(),
             user_id: some_account
                 .clone()
-                .map(|sa| sa.subject)
-                .flatten()
+                .and_then(|sa| sa.subject)
                 .map(SlackUserId),
             generate_message_id: Some(generate_message_id),
             send_id: None,
+            send_dm_id: None,
             birthday_message: None,
             busy: false,
             deleted: false,
+            sent: None,
+            error: None,
         }
     }
 
     fn suggestion(
-        who: &String,
+        who: &str,
         some_account: &Option<SomeAccount>,
         generate_message_id: SlackInteractionId,
-        birthday_message: &String,
+        birthday_message: &str,
         send_id: Option<SlackInteractionId>,
+        send_dm_id: Option<SlackInteractionId>,
     ) -> BirthdayMessage {
         BirthdayMessage {
-            who: who.clone(),
+            who: who.to_string(),
             user_id: some_account
                 .clone()
-                .map(|sa| sa.subject)
-                .flatten()
+                .and_then(|sa| sa.subject)
                 .map(SlackUserId),
             generate_message_id: Some(generate_message_id),
             send_id,
-            birthday_message: Some(birthday_message.clone()),
+            send_dm_id,
+            birthday_message: Some(birthday_message.to_string()),
             busy: false,
             deleted: false,
+            sent: None,
+            error: None,
         }
     }
 
     fn busy(
-        who: &String,
+        who: &str,
         some_account: &Option<SomeAccount>,
         birthday_message: &Option<String>,
     ) -> BirthdayMessage {
         BirthdayMessage {
-            who: who.clone(),
+            who: who.to_string(),
             user_id: some_account
                 .clone()
-                .map(|sa| sa.subject)
-                .flatten()
+                .and_then(|sa| sa.subject)
                 .map(SlackUserId),
             generate_message_id: None,
             send_id: None,
+            send_dm_id: None,
             birthday_message: birthday_message.clone(),
             busy: true,
             deleted: false,
+            sent: None,
+            error: None,
         }
     }
 
@@ -440,12 +824,39 @@ impl BirthdayMessage {
     ) -> BirthdayMessage {
         BirthdayMessage {
             who: who.clone(),
-            user_id: some_account.map(|sa| sa.subject).flatten().map(SlackUserId),
+            user_id: some_account.and_then(|sa| sa.subject).map(SlackUserId),
             generate_message_id: None,
             send_id: None,
+            send_dm_id: None,
             birthday_message,
             busy: false,
             deleted: true,
+            sent: None,
+            error: None,
+        }
+    }
+
+    fn sent(
+        who: &str,
+        some_account: &Option<SomeAccount>,
+        birthday_message: &str,
+        sent_by: Option<SlackUserId>,
+        direct: bool,
+    ) -> BirthdayMessage {
+        BirthdayMessage {
+            who: who.to_string(),
+            user_id: some_account
+                .clone()
+                .and_then(|sa| sa.subject)
+                .map(SlackUserId),
+            generate_message_id: None,
+            send_id: None,
+            send_dm_id: None,
+            birthday_message: Some(birthday_message.to_string()),
+            busy: false,
+            deleted: false,
+            sent: Some((sent_by, direct)),
+            error: None,
         }
     }
 }
@@ -479,11 +890,50 @@ impl SlackMessageTemplate for BirthdayMessage {
                     self.send_id.clone().unwrap().into(),
                     pt!("Send")).
                     with_value("send-message".to_string())
+                ),
+                optionally_into(self.user_id.is_some() && self.send_dm_id.is_some() => SlackBlockButtonElement::new(
+                    self.send_dm_id.clone().unwrap().into(),
+                    pt!("Send as DM")).
+                    with_value("send-direct-message".to_string())
                 )
             ])),
+            optionally_into(self.sent.is_some() => SlackSectionBlock::new().with_text(md!(
+                "{} by {} :white_check_mark:",
+                if self.sent.clone().unwrap().1 { "Sent as a direct message" } else { "Sent" },
+                self.sent.clone().unwrap().0.map(|u| u.to_slack_format()).unwrap_or_else(|| "someone".to_string())
+            ))),
+            optionally_into(self.error.is_some() => SlackSectionBlock::new().with_text(md!(
+                ":warning: Something went wrong: {}",
+                self.error.clone().unwrap()
+            ))),
             optionally_into(self.deleted => SlackSectionBlock::new().with_text(md!(
                 "You snooze, you loose! :alarm_clock:"
             )))
         ])
     }
 }
+
+/// The greeting itself, as posted when someone clicks "Send".
+#[derive(Debug, Clone)]
+pub struct BirthdayGreetingMessage {
+    pub who: String,
+    pub user_id: Option<SlackUserId>,
+    pub birthday_message: String,
+}
+
+impl SlackMessageTemplate for BirthdayGreetingMessage {
+    fn render_template(&self) -> SlackMessageContent {
+        let who = self
+            .user_id
+            .clone()
+            .map(|u| u.to_slack_format())
+            .unwrap_or_else(|| self.who.clone());
+
+        SlackMessageContent::new()
+            .with_text(format!("Happy birthday {}!", who))
+            .with_blocks(slack_blocks![
+                some_into(SlackSectionBlock::new().with_text(md!("{} :birthday:", who))),
+                some_into(SlackSectionBlock::new().with_text(md!("{}", self.birthday_message)))
+            ])
+    }
+}
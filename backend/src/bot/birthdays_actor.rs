@@ -1,21 +1,76 @@
 use crate::birthday_assistant::BirthdayAssistant;
-use crate::bot::birthday_actor::{BirthdayActor, BirthdayActorMsg};
+use crate::bot::birthday_actor::{
+    BirthdayActor, BirthdayActorArguments, BirthdayActorMsg, CONVERSATION_TIMEOUT,
+};
 use crate::bot::SlackClient;
-use crate::model::Dao;
+use crate::model::{BirthdayDao, Dao, EmployeeDao};
 use crate::slack_interaction_server::SlackInteractionServer;
 use ractor::{Actor, ActorProcessingErr, ActorRef, RpcReplyPort, SupervisionEvent};
 use slack_morphism::prelude::*;
 use std::sync::Arc;
+use std::time::Duration;
+use time::{Date, OffsetDateTime, PrimitiveDateTime, Time};
+use time_tz::{OffsetDateTimeExt, PrimitiveDateTimeExt, Tz};
 use tracing::*;
 use uuid::Uuid;
 
 pub enum BirthdaysActorMsg {
+    /// Starts a birthday conversation for the active employee with the given name, replies with
+    /// None if there is no such employee.
     CreateBirthdayActor(
         SlackTeamId,
         SlackChannelId,
         String,
-        RpcReplyPort<ActorRef<BirthdayActorMsg>>,
+        RpcReplyPort<Option<ActorRef<BirthdayActorMsg>>>,
     ),
+    CheckBirthdays,
+    RestoreConversations,
+}
+
+/// When and where the daily birthday announcements are posted.
+#[derive(Clone, Debug)]
+pub(crate) struct BirthdaySchedule {
+    pub(crate) channel: SlackChannelId,
+    pub(crate) timezone: &'static Tz,
+    pub(crate) time: Time,
+}
+
+impl BirthdaySchedule {
+    /// The delay until the next check should happen, which is the configured time today if it
+    /// hasn't passed yet, otherwise the configured time tomorrow.
+    pub(crate) fn next_check(&self, now: OffsetDateTime) -> Duration {
+        let now = now.to_timezone(self.timezone);
+
+        let date = if now.time() < self.time {
+            now.date()
+        } else {
+            now.date().next_day().unwrap_or(now.date())
+        };
+
+        PrimitiveDateTime::new(date, self.time)
+            .assume_timezone(self.timezone)
+            .take_first()
+            .and_then(|next| (next - now).try_into().ok())
+            .unwrap_or(Duration::from_secs(60 * 60))
+    }
+
+    /// How long an announcement waits for its buttons to be clicked, the rest of the day but no
+    /// shorter than other conversations.
+    pub(crate) fn conversation_timeout(&self, now: OffsetDateTime) -> Duration {
+        let now = now.to_timezone(self.timezone);
+
+        now.date()
+            .next_day()
+            .and_then(|tomorrow| {
+                PrimitiveDateTime::new(tomorrow, Time::MIDNIGHT)
+                    .assume_timezone(self.timezone)
+                    .take_first()
+            })
+            .and_then(|end_of_day| (end_of_day - now).try_into().ok())
+            .map_or(CONVERSATION_TIMEOUT, |rest_of_day: Duration| {
+                rest_of_day.max(CONVERSATION_TIMEOUT)
+            })
+    }
 }
 
 pub(crate) struct BirthdaysActor {
@@ -23,6 +78,7 @@ pub(crate) struct BirthdaysActor {
     birthday_assistant: BirthdayAssistant,
     slack_interaction_actor: ActorRef<<SlackInteractionServer as Actor>::Msg>,
     slack_client: Arc<SlackClient>,
+    schedule: Option<BirthdaySchedule>,
 }
 
 impl BirthdaysActor {
@@ -31,13 +87,115 @@ impl BirthdaysActor {
         birthday_assistant: BirthdayAssistant,
         slack_interaction_actor: ActorRef<<SlackInteractionServer as Actor>::Msg>,
         slack_client: Arc<SlackClient>,
+        schedule: Option<BirthdaySchedule>,
     ) -> Self {
         Self {
             dao,
             birthday_assistant,
             slack_interaction_actor,
             slack_client,
+            schedule,
+        }
+    }
+
+    async fn spawn_birthday_actor(
+        &self,
+        myself: &ActorRef<BirthdaysActorMsg>,
+        args: BirthdayActorArguments,
+        timeout: Duration,
+    ) -> Result<ActorRef<BirthdayActorMsg>, ActorProcessingErr> {
+        info!("Creating new BirthdayActor");
+        let name = format!("birthday/{}", Uuid::now_v7());
+
+        let (actor, _) = myself
+            .spawn_linked(
+                Some(name),
+                BirthdayActor::new(
+                    self.dao.clone(),
+                    self.birthday_assistant.clone(),
+                    self.slack_interaction_actor.clone(),
+                    self.slack_client.clone(),
+                    timeout,
+                ),
+                args,
+            )
+            .await?;
+
+        Ok(actor)
+    }
+
+    #[instrument(skip(self, myself, schedule))]
+    async fn announce_birthdays(
+        &self,
+        myself: &ActorRef<BirthdaysActorMsg>,
+        schedule: &BirthdaySchedule,
+        today: Date,
+    ) -> Result<(), ActorProcessingErr> {
+        let employees = self.dao.employees_with_birthday(today).await?;
+
+        if employees.is_empty() {
+            info!("No birthdays today");
+            return Ok(());
+        }
+
+        let session = self
+            .slack_client
+            .client
+            .open_session(&self.slack_client.token);
+
+        let team = session.auth_test().await?.team_id;
+        let timeout = schedule.conversation_timeout(OffsetDateTime::now_utc());
+
+        for employee in employees {
+            // This makes sure that a restart doesn't cause a second announcement.
+            if !self
+                .dao
+                .insert_birthday_announcement(employee.id, today)
+                .await?
+            {
+                info!(employee = employee.id.0, "Birthday already announced");
+                continue;
+            }
+
+            info!(employee = employee.id.0, "Announcing birthday");
+
+            let args = BirthdayActorArguments::New(
+                team.clone(),
+                schedule.channel.clone(),
+                employee.id,
+                Some(today),
+            );
+
+            if let Err(e) = self.spawn_birthday_actor(myself, args, timeout).await {
+                self.dao
+                    .delete_birthday_announcement(employee.id, today)
+                    .await?;
+                return Err(e);
+            }
         }
+
+        Ok(())
+    }
+
+    #[instrument(skip(self, myself))]
+    async fn restore_conversations(
+        &self,
+        myself: &ActorRef<BirthdaysActorMsg>,
+    ) -> Result<(), ActorProcessingErr> {
+        let conversations = self.dao.birthday_conversations().await?;
+
+        info!("Restoring {} birthday conversations", conversations.len());
+
+        for conversation in conversations {
+            self.spawn_birthday_actor(
+                myself,
+                BirthdayActorArguments::Restore(conversation),
+                CONVERSATION_TIMEOUT,
+            )
+            .await?;
+        }
+
+        Ok(())
     }
 }
 
@@ -51,9 +209,15 @@ impl Actor for BirthdaysActor {
 
     async fn pre_start(
         &self,
-        _: ActorRef<Self::Msg>,
+        myself: ActorRef<Self::Msg>,
         _: Self::Arguments,
     ) -> Result<Self::State, ActorProcessingErr> {
+        myself.send_message(BirthdaysActorMsg::RestoreConversations)?;
+
+        if self.schedule.is_some() {
+            myself.send_message(BirthdaysActorMsg::CheckBirthdays)?;
+        }
+
         Ok(Self::State {})
     }
 
@@ -65,24 +229,54 @@ impl Actor for BirthdaysActor {
     ) -> Result<(), ActorProcessingErr> {
         match message {
             BirthdaysActorMsg::CreateBirthdayActor(team, channel, who, reply) => {
-                info!("Creating new BirthdayActor");
-                let name = format!("birthday/{}", Uuid::now_v7().to_string());
-
-                let (actor, _) = myself
-                    .spawn_linked(
-                        Some(name),
-                        BirthdayActor::new(
-                            self.dao.clone(),
-                            self.birthday_assistant.clone(),
-                            self.slack_interaction_actor.clone(),
-                            self.slack_client.clone(),
-                        ),
-                        (team, channel, who),
-                    )
-                    .await?;
+                let employee = self
+                    .dao
+                    .employee_by_name(who.clone())
+                    .await?
+                    .filter(|e| e.active);
+
+                let actor = match employee {
+                    Some(employee) => {
+                        let args = BirthdayActorArguments::New(team, channel, employee.id, None);
+                        Some(
+                            self.spawn_birthday_actor(&myself, args, CONVERSATION_TIMEOUT)
+                                .await?,
+                        )
+                    }
+                    None => {
+                        warn!("No active employee named {}", who);
+                        None
+                    }
+                };
 
                 reply.send(actor)?
             }
+            BirthdaysActorMsg::RestoreConversations => {
+                if let Err(e) = self.restore_conversations(&myself).await {
+                    warn!("Could not restore birthday conversations: {}", e);
+                }
+            }
+            BirthdaysActorMsg::CheckBirthdays => {
+                let Some(schedule) = &self.schedule else {
+                    return Ok(());
+                };
+
+                let now = OffsetDateTime::now_utc();
+                let local = now.to_timezone(schedule.timezone);
+
+                if local.time() >= schedule.time {
+                    if let Err(e) = self
+                        .announce_birthdays(&myself, schedule, local.date())
+                        .await
+                    {
+                        warn!("Could not announce birthdays: {}", e);
+                    }
+                }
+
+                let next = schedule.next_check(now);
+                info!("Next birthday check in {:?}", next);
+                myself.send_after(next, || BirthdaysActorMsg::CheckBirthdays);
+            }
         }
 
         Ok(())
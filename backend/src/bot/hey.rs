@@ -9,7 +9,7 @@ pub(crate) struct HeyHandler {
 
 impl HeyHandler {
     pub(crate) async fn on_message(
-        self: &Self,
+        &self,
         sender: &SlackUserId,
         channel: &SlackChannelId,
         content: &String,
@@ -3,6 +3,10 @@ pub mod birthdays_actor;
 pub mod hey;
 pub mod skjera_slack_conversation;
 pub mod skjera_slack_conversations;
+pub mod slack_profile_sync_actor;
+
+#[cfg(test)]
+mod tests;
 
 use crate::actor::slack::slack_conversation_server::SlackConversationServerMsg;
 use crate::bot::skjera_slack_conversation::*;
@@ -69,7 +73,7 @@ where
     }
 
     #[instrument(skip(self, event))]
-    pub(crate) async fn on_event<'a>(self: &Self, event: SlackPushEventCallback) -> Response {
+    pub(crate) async fn on_event<'a>(&self, event: SlackPushEventCallback) -> Response {
         trace!("Received slack push event");
 
         match &event.event {
@@ -94,7 +98,7 @@ where
 
     #[instrument(skip(self, event))]
     pub(crate) async fn on_block_action<'a>(
-        self: &Self,
+        &self,
         event: SlackInteractionBlockActionsEvent,
     ) -> Response {
         info!("Received slack interaction event");
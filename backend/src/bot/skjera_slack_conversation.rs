@@ -55,9 +55,9 @@ impl DefaultSlackHandler for SkjeraConversation {
     ) -> Result<(), ActorProcessingErr> {
         let content = event.content.and_then(|s| s.text).unwrap_or("".to_string());
 
-        let words: Vec<&str> = content.trim().split_whitespace().collect();
+        let words: Vec<&str> = content.split_whitespace().collect();
 
-        let first = words.get(0);
+        let first = words.first();
         let second = words.get(1);
 
         match (first, second, event.sender.user, event.origin.channel) {
@@ -82,7 +82,10 @@ impl DefaultSlackHandler for SkjeraConversation {
                 )
                 .expect("could not start birthday actor");
 
-                info!("new birthday created: {:?}", addr);
+                match addr {
+                    Some(addr) => info!("new birthday created: {:?}", addr),
+                    None => warn!("no birthday created for {}", content),
+                }
 
                 Ok(())
             }
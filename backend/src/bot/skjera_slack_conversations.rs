@@ -50,6 +50,8 @@ impl OnPush<SkjeraConversationMsg> for SkjeraConversations {
         actor: ActorRef<SkjeraConversationMsg>,
         event: slack_morphism::prelude::SlackPushEventCallback,
     ) -> Result<(), ActorProcessingErr> {
-        actor.cast(SlackPushEventCallback(event)).map_err(Into::into)
+        actor
+            .cast(SlackPushEventCallback(event))
+            .map_err(Into::into)
     }
 }
@@ -0,0 +1,208 @@
+use crate::model::{Dao, EmployeeDao, SomeAccount, SomeNetwork};
+use crate::slack_client;
+use crate::slack_client::{RateLimited, SlackUser};
+use ractor::{Actor, ActorProcessingErr, ActorRef};
+use reqwest::Client;
+use std::time::Duration;
+use tracing::*;
+
+/// users.info allows about 100 requests a minute.
+const USERS_INFO_PAUSE: Duration = Duration::from_millis(600);
+
+/// How many times a lookup is retried when Slack asks to slow down.
+const RATE_LIMIT_RETRIES: usize = 3;
+
+pub enum SlackProfileSyncActorMsg {
+    Sync,
+}
+
+/// The changes to a Slack account after looking up its user.
+#[derive(Debug, PartialEq)]
+pub(crate) struct SlackAccountUpdate {
+    pub(crate) name: Option<String>,
+    pub(crate) nick: Option<String>,
+    pub(crate) avatar: Option<String>,
+    pub(crate) deactivated: bool,
+}
+
+impl SlackAccountUpdate {
+    /// Returns None if the account is up to date. `user` is None if the user doesn't exist in the
+    /// workspace anymore, which is handled like a deactivated user.
+    pub(crate) fn new(account: &SomeAccount, user: Option<&SlackUser>) -> Option<Self> {
+        let update = match user {
+            Some(user) => SlackAccountUpdate {
+                name: Some(user.profile.real_name.clone()).filter(|s| !s.trim().is_empty()),
+                nick: Some(user.profile.display_name.clone()).filter(|s| !s.trim().is_empty()),
+                avatar: user
+                    .profile
+                    .image_512
+                    .clone()
+                    .or(user.profile.image_192.clone()),
+                deactivated: user.deleted,
+            },
+            None => SlackAccountUpdate {
+                name: account.name.clone(),
+                nick: account.nick.clone(),
+                avatar: account.avatar.clone(),
+                deactivated: true,
+            },
+        };
+
+        let unchanged = update.name == account.name
+            && update.nick == account.nick
+            && update.avatar == account.avatar
+            && update.deactivated == account.deactivated;
+
+        (!unchanged).then_some(update)
+    }
+}
+
+/// Looks up a user like [slack_client::users_info], but waits and tries again as long as Slack
+/// asks to.
+pub(crate) async fn users_info_retrying(
+    http_client: &Client,
+    api_url: &str,
+    token: &str,
+    user: &str,
+) -> anyhow::Result<Option<SlackUser>> {
+    let mut retries = 0;
+
+    loop {
+        match slack_client::users_info(http_client, api_url, token, user).await {
+            Err(e) if retries < RATE_LIMIT_RETRIES => {
+                let Some(RateLimited { retry_after }) = e.downcast_ref::<RateLimited>() else {
+                    return Err(e);
+                };
+
+                info!("Rate limited by Slack, retrying in {:?}", retry_after);
+                tokio::time::sleep(*retry_after).await;
+                retries += 1;
+            }
+            result => return result,
+        }
+    }
+}
+
+/// Keeps the name, nick and avatar of the linked Slack accounts up to date, they are only
+/// captured when the account is connected.
+///
+/// Only the accounts in the bot's workspace can be looked up.
+pub(crate) struct SlackProfileSyncActor {
+    dao: Dao,
+    http_client: reqwest::Client,
+    api_url: String,
+    token: String,
+    interval: Duration,
+}
+
+impl SlackProfileSyncActor {
+    pub fn new(
+        dao: Dao,
+        http_client: reqwest::Client,
+        api_url: String,
+        token: String,
+        interval: Duration,
+    ) -> Self {
+        Self {
+            dao,
+            http_client,
+            api_url,
+            token,
+            interval,
+        }
+    }
+
+    #[instrument(skip(self))]
+    async fn sync_profiles(&self) -> anyhow::Result<()> {
+        let team = slack_client::auth_test(&self.http_client, &self.api_url, &self.token).await?;
+
+        let accounts = self
+            .dao
+            .some_accounts_by_network(SomeNetwork::Slack, Some(team))
+            .await?;
+
+        info!("Syncing {} Slack accounts", accounts.len());
+
+        for (i, account) in accounts.into_iter().enumerate() {
+            let Some(subject) = &account.subject else {
+                continue;
+            };
+
+            if i > 0 {
+                tokio::time::sleep(USERS_INFO_PAUSE).await;
+            }
+
+            // Give up on any other error, it is most likely an invalid token
+            let user =
+                users_info_retrying(&self.http_client, &self.api_url, &self.token, subject).await?;
+
+            let Some(update) = SlackAccountUpdate::new(&account, user.as_ref()) else {
+                continue;
+            };
+
+            info!(
+                some_account = account.id.0,
+                ?update,
+                "Updating Slack account"
+            );
+
+            if update.deactivated != account.deactivated {
+                self.dao
+                    .set_some_account_deactivated(account.id, update.deactivated)
+                    .await?;
+            }
+
+            self.dao
+                .update_some_account(
+                    account.id,
+                    account.authenticated,
+                    account.network_avatar,
+                    account.subject,
+                    update.name,
+                    update.nick,
+                    account.url,
+                    update.avatar,
+                )
+                .await?;
+        }
+
+        Ok(())
+    }
+}
+
+#[ractor::async_trait]
+impl Actor for SlackProfileSyncActor {
+    type Msg = SlackProfileSyncActorMsg;
+    type State = ();
+    type Arguments = ();
+
+    async fn pre_start(
+        &self,
+        myself: ActorRef<Self::Msg>,
+        _: Self::Arguments,
+    ) -> Result<Self::State, ActorProcessingErr> {
+        myself.send_message(SlackProfileSyncActorMsg::Sync)?;
+
+        Ok(())
+    }
+
+    async fn handle(
+        &self,
+        myself: ActorRef<Self::Msg>,
+        message: Self::Msg,
+        _: &mut Self::State,
+    ) -> Result<(), ActorProcessingErr> {
+        match message {
+            SlackProfileSyncActorMsg::Sync => {
+                if let Err(e) = self.sync_profiles().await {
+                    warn!("Could not sync Slack profiles: {}", e);
+                }
+
+                info!("Next Slack profile sync in {:?}", self.interval);
+                myself.send_after(self.interval, || SlackProfileSyncActorMsg::Sync);
+            }
+        }
+
+        Ok(())
+    }
+}
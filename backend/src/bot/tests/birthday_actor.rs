@@ -0,0 +1,455 @@
+//! Runs birthday conversations against a local stand-in for the Slack Web API.
+
+use crate::birthday_assistant::BirthdayAssistant;
+use crate::bot::birthday_actor::{
+    BirthdayActor, BirthdayActorArguments, BirthdayActorMsg, CONVERSATION_TIMEOUT,
+};
+use crate::bot::SlackClient;
+use crate::model::tests::{
+    birthday_greetings, delete_test_employee, insert_test_employee, test_dao,
+};
+use crate::model::*;
+use crate::slack_interaction_server::SlackInteractionServerMsg::{
+    AddInteraction, OnInteractionActions, RemoveInteraction, RestoreInteraction,
+};
+use crate::slack_interaction_server::{
+    InteractionSubscriber, SlackInteractionId, SlackInteractionServer, SlackInteractionServerMsg,
+};
+use crate::web::tests::fake_slack::{FakeSlackApi, TEAM, TOKEN};
+use async_openai::config::OpenAIConfig;
+use hyper_rustls::HttpsConnectorBuilder;
+use ractor::concurrency::JoinHandle;
+use ractor::{call, cast, Actor, ActorRef, ActorStatus, MessagingErr};
+use serde_json::json;
+use slack_morphism::prelude::*;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+const CHANNEL: &str = "C0001";
+
+fn slack_client(slack: &FakeSlackApi) -> Arc<SlackClient> {
+    let _ = rustls::crypto::aws_lc_rs::default_provider().install_default();
+
+    let connector = HttpsConnectorBuilder::new()
+        .with_native_roots()
+        .unwrap()
+        .https_or_http()
+        .enable_http1()
+        .build();
+
+    let client = slack_morphism::SlackClient::new(
+        SlackClientHyperConnector::with_connector(connector).with_slack_api_url(&slack.api_url),
+    );
+
+    Arc::new(SlackClient {
+        client,
+        token: SlackApiToken::new(TOKEN.into()),
+    })
+}
+
+/// Never called, the tests start out with a generated greeting.
+fn birthday_assistant() -> BirthdayAssistant {
+    let config = OpenAIConfig::new().with_api_key("sk-test");
+
+    BirthdayAssistant::new(async_openai::Client::with_config(config), "asst".into())
+}
+
+async fn spawn_birthday_actor(
+    dao: &Dao,
+    slack: &FakeSlackApi,
+    slack_interaction_actor: &ActorRef<SlackInteractionServerMsg>,
+    args: BirthdayActorArguments,
+) -> (ActorRef<BirthdayActorMsg>, JoinHandle<()>) {
+    let actor = BirthdayActor::new(
+        dao.clone(),
+        birthday_assistant(),
+        slack_interaction_actor.clone(),
+        slack_client(slack),
+        CONVERSATION_TIMEOUT,
+    );
+
+    Actor::spawn(None, actor, args).await.unwrap()
+}
+
+fn action(interaction: Uuid, value: &str) -> SlackInteractionActionInfo {
+    SlackInteractionActionInfo::new(
+        SlackActionType("button".into()),
+        SlackActionId(interaction.to_string()),
+    )
+    .with_value(value.into())
+}
+
+fn click_send(send_interaction: Uuid) -> BirthdayActorMsg {
+    BirthdayActorMsg::OnInteraction(
+        Some(SlackUserId("U0002".into())),
+        action(send_interaction, "send-message"),
+    )
+}
+
+/// A click on a button, as delivered by Slack.
+fn block_actions(action: SlackInteractionActionInfo) -> SlackInteractionBlockActionsEvent {
+    serde_json::from_value(json!({
+        "team": {"id": TEAM},
+        "user": {"id": "U0002"},
+        "api_app_id": "A0001",
+        "container": {"type": "message", "message_ts": "1700000000.000001"},
+        "trigger_id": "1",
+        "actions": [action],
+    }))
+    .unwrap()
+}
+
+async fn wait_until<F: Fn() -> bool>(condition: F) {
+    for _ in 0..100 {
+        if condition() {
+            return;
+        }
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+
+    panic!("timed out");
+}
+
+/// Polls for the conversation about the employee, it is saved once the message has been posted.
+async fn wait_for_conversation(dao: &Dao, employee: EmployeeId) -> BirthdayConversation {
+    for _ in 0..100 {
+        let conversations = dao.birthday_conversations().await.unwrap();
+
+        if let Some(c) = conversations
+            .into_iter()
+            .find(|c| c.employee == Some(employee))
+        {
+            return c;
+        }
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+
+    panic!("timed out");
+}
+
+async fn conversation_exists(dao: &Dao, id: BirthdayConversationId) -> bool {
+    dao.birthday_conversations()
+        .await
+        .unwrap()
+        .iter()
+        .any(|c| c.id == id)
+}
+
+#[tokio::test]
+async fn test_send_greeting() {
+    let Some(dao) = test_dao().await else {
+        return;
+    };
+    let employee = insert_test_employee(&dao, "Ola Nordmann").await;
+    let slack = FakeSlackApi::start().await;
+
+    let conversation = dao
+        .insert_birthday_conversation(
+            TEAM.to_string(),
+            CHANNEL.to_string(),
+            "1700000000.000000".to_string(),
+            employee.name.clone(),
+            Some(employee.id),
+            Uuid::now_v7(),
+            OffsetDateTime::now_utc() + Duration::from_secs(60),
+        )
+        .await
+        .unwrap();
+
+    let send_interaction = Uuid::now_v7();
+    let conversation = dao
+        .update_birthday_conversation(
+            conversation.id,
+            Some("Gratulerer med dagen!".to_string()),
+            conversation.generate_interaction,
+            Some(send_interaction),
+            None,
+            conversation.expires_at,
+        )
+        .await
+        .unwrap();
+
+    let (slack_interaction_actor, _) = Actor::spawn(None, SlackInteractionServer, ())
+        .await
+        .unwrap();
+
+    let (actor, handle) = spawn_birthday_actor(
+        &dao,
+        &slack,
+        &slack_interaction_actor,
+        BirthdayActorArguments::Restore(conversation.clone()),
+    )
+    .await;
+
+    // The greeting can't be posted, the error is shown and the buttons are kept
+    slack.set_post_message_error(Some("channel_not_found"));
+    actor.send_message(click_send(send_interaction)).unwrap();
+
+    wait_until(|| !slack.updated().is_empty()).await;
+
+    let update = slack.updated()[0].to_string();
+    assert!(update.contains("Something went wrong"), "{}", update);
+    assert!(update.contains(&send_interaction.to_string()), "{}", update);
+
+    assert_eq!(ActorStatus::Running, actor.get_status());
+    assert!(conversation_exists(&dao, conversation.id).await);
+    assert_eq!(0, birthday_greetings(&dao, employee.id).await);
+
+    // Clicking again sends the greeting and ends the conversation
+    slack.set_post_message_error(None);
+    actor.send_message(click_send(send_interaction)).unwrap();
+
+    handle.await.unwrap();
+
+    let posted = slack.posted();
+    assert_eq!(1, posted.len());
+    assert_eq!(CHANNEL, posted[0]["channel"]);
+    assert!(posted[0].to_string().contains("Gratulerer med dagen!"));
+
+    let update = slack.updated().last().unwrap().to_string();
+    assert!(update.contains("Sent by <@U0002>"), "{}", update);
+
+    assert!(!conversation_exists(&dao, conversation.id).await);
+    assert_eq!(1, birthday_greetings(&dao, employee.id).await);
+
+    slack_interaction_actor.stop(None);
+    delete_test_employee(&dao, employee.id).await;
+}
+
+/// A conversation survives a restart of the actor, the buttons on the message keep working.
+#[tokio::test]
+async fn test_restore_conversation() {
+    let Some(dao) = test_dao().await else {
+        return;
+    };
+    let employee = insert_test_employee(&dao, "Kari Nordmann").await;
+    let slack = FakeSlackApi::start().await;
+
+    let (slack_interaction_actor, _) = Actor::spawn(None, SlackInteractionServer, ())
+        .await
+        .unwrap();
+
+    let (actor, handle) = spawn_birthday_actor(
+        &dao,
+        &slack,
+        &slack_interaction_actor,
+        BirthdayActorArguments::New(
+            SlackTeamId(TEAM.into()),
+            SlackChannelId(CHANNEL.into()),
+            employee.id,
+            None,
+        ),
+    )
+    .await;
+
+    let conversation = wait_for_conversation(&dao, employee.id).await;
+
+    // Stored to outlive a restart
+    assert!(conversation.expires_at > OffsetDateTime::now_utc() + Duration::from_secs(60 * 60));
+
+    let posted = slack.posted();
+    assert_eq!(1, posted.len());
+    assert_eq!(posted[0]["channel"], conversation.channel);
+    assert_eq!("1700000000.000001", conversation.ts);
+    let generate_interaction = conversation.generate_interaction.unwrap();
+    assert!(posted[0]
+        .to_string()
+        .contains(&generate_interaction.to_string()));
+
+    actor.stop(None);
+    handle.await.unwrap();
+
+    // As if a greeting had been generated before the restart
+    let send_interaction = Uuid::now_v7();
+    let conversation = dao
+        .update_birthday_conversation(
+            conversation.id,
+            Some("Gratulerer med dagen!".to_string()),
+            Some(generate_interaction),
+            Some(send_interaction),
+            None,
+            conversation.expires_at,
+        )
+        .await
+        .unwrap();
+
+    let (_, handle) = spawn_birthday_actor(
+        &dao,
+        &slack,
+        &slack_interaction_actor,
+        BirthdayActorArguments::Restore(conversation.clone()),
+    )
+    .await;
+
+    // Give the actor time to register its interactions again
+    tokio::time::sleep(Duration::from_millis(200)).await;
+
+    cast!(
+        slack_interaction_actor,
+        OnInteractionActions(block_actions(action(send_interaction, "send-message")))
+    )
+    .unwrap();
+
+    tokio::time::timeout(Duration::from_secs(5), handle)
+        .await
+        .unwrap()
+        .unwrap();
+
+    let posted = slack.posted();
+    assert_eq!(2, posted.len());
+    assert!(posted[1].to_string().contains("Gratulerer med dagen!"));
+
+    assert!(!conversation_exists(&dao, conversation.id).await);
+    assert_eq!(1, birthday_greetings(&dao, employee.id).await);
+
+    slack_interaction_actor.stop(None);
+    delete_test_employee(&dao, employee.id).await;
+}
+
+/// A conversation that was started a while before the restart still gets the clicks.
+#[tokio::test]
+async fn test_restore_old_conversation() {
+    let Some(dao) = test_dao().await else {
+        return;
+    };
+    let employee = insert_test_employee(&dao, "Per Nordmann").await;
+    let slack = FakeSlackApi::start().await;
+
+    // Started ten minutes ago
+    let started = OffsetDateTime::now_utc() - Duration::from_secs(10 * 60);
+    let conversation = dao
+        .insert_birthday_conversation(
+            TEAM.to_string(),
+            CHANNEL.to_string(),
+            "1700000000.000000".to_string(),
+            employee.name.clone(),
+            Some(employee.id),
+            Uuid::now_v7(),
+            started + CONVERSATION_TIMEOUT,
+        )
+        .await
+        .unwrap();
+
+    let send_interaction = Uuid::now_v7();
+    let conversation = dao
+        .update_birthday_conversation(
+            conversation.id,
+            Some("Gratulerer med dagen!".to_string()),
+            conversation.generate_interaction,
+            Some(send_interaction),
+            None,
+            conversation.expires_at,
+        )
+        .await
+        .unwrap();
+
+    let (slack_interaction_actor, _) = Actor::spawn(None, SlackInteractionServer, ())
+        .await
+        .unwrap();
+
+    let (actor, handle) = spawn_birthday_actor(
+        &dao,
+        &slack,
+        &slack_interaction_actor,
+        BirthdayActorArguments::Restore(conversation.clone()),
+    )
+    .await;
+
+    // Give the actor time to register its interactions again
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    assert_eq!(ActorStatus::Running, actor.get_status());
+
+    cast!(
+        slack_interaction_actor,
+        OnInteractionActions(block_actions(action(send_interaction, "send-message")))
+    )
+    .unwrap();
+
+    tokio::time::timeout(Duration::from_secs(5), handle)
+        .await
+        .unwrap()
+        .unwrap();
+
+    let posted = slack.posted();
+    assert_eq!(1, posted.len());
+    assert!(posted[0].to_string().contains("Gratulerer med dagen!"));
+    assert_eq!(1, birthday_greetings(&dao, employee.id).await);
+
+    slack_interaction_actor.stop(None);
+    delete_test_employee(&dao, employee.id).await;
+}
+
+struct RecordingSubscriber(Arc<Mutex<Vec<SlackInteractionActionInfo>>>);
+
+impl InteractionSubscriber for RecordingSubscriber {
+    fn on_interaction(
+        &self,
+        _: Option<SlackUserId>,
+        event: SlackInteractionActionInfo,
+    ) -> Result<(), MessagingErr<()>> {
+        self.0.lock().unwrap().push(event);
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn test_remove_interaction() {
+    let (server, _) = Actor::spawn(None, SlackInteractionServer, ())
+        .await
+        .unwrap();
+
+    let received = Arc::new(Mutex::new(Vec::new()));
+    let click = |id: &SlackInteractionId| {
+        cast!(
+            server,
+            OnInteractionActions(block_actions(action(id.0, "send-message")))
+        )
+        .unwrap()
+    };
+
+    let added = call!(
+        server,
+        AddInteraction,
+        Box::new(RecordingSubscriber(received.clone()))
+    )
+    .unwrap();
+
+    let restored = SlackInteractionId(Uuid::now_v7());
+    cast!(
+        server,
+        RestoreInteraction(
+            restored.clone(),
+            Box::new(RecordingSubscriber(received.clone()))
+        )
+    )
+    .unwrap();
+
+    click(&added);
+    click(&restored);
+
+    cast!(server, RemoveInteraction(added.clone())).unwrap();
+    cast!(server, RemoveInteraction(restored.clone())).unwrap();
+
+    click(&added);
+    click(&restored);
+
+    // The messages are handled in order, so this waits for the clicks
+    call!(
+        server,
+        AddInteraction,
+        Box::new(RecordingSubscriber(Arc::default()))
+    )
+    .unwrap();
+
+    let received: Vec<_> = received
+        .lock()
+        .unwrap()
+        .iter()
+        .map(|a| a.action_id.to_string())
+        .collect();
+    assert_eq!(vec![added.to_string(), restored.to_string()], received);
+
+    server.stop(None);
+}
@@ -0,0 +1,85 @@
+use crate::birthday_assistant::birthday_prompt;
+use crate::bot::birthday_actor::CONVERSATION_TIMEOUT;
+use crate::bot::birthdays_actor::BirthdaySchedule;
+use crate::model::tests::test_employee;
+use crate::model::*;
+use slack_morphism::prelude::SlackChannelId;
+use std::time::Duration;
+use time::macros::{date, datetime, time};
+
+#[test]
+fn test_is_birthday() {
+    assert!(is_birthday(date!(1980 - 12 - 09), date!(2025 - 12 - 09)));
+    assert!(!is_birthday(date!(1980 - 12 - 09), date!(2025 - 12 - 10)));
+
+    // Leap day birthdays are celebrated on the 28th in non-leap years
+    assert!(is_birthday(date!(1996 - 02 - 29), date!(2025 - 02 - 28)));
+    assert!(!is_birthday(date!(1996 - 02 - 29), date!(2025 - 03 - 01)));
+    assert!(is_birthday(date!(1996 - 02 - 29), date!(2024 - 02 - 29)));
+    assert!(!is_birthday(date!(1996 - 02 - 29), date!(2024 - 02 - 28)));
+}
+
+#[test]
+fn test_next_check() {
+    let schedule = BirthdaySchedule {
+        channel: SlackChannelId::new("C123".to_string()),
+        timezone: time_tz::timezones::db::europe::OSLO,
+        time: time!(09:00),
+    };
+
+    // 07:00 UTC is 08:00 in Oslo during the winter
+    assert_eq!(
+        Duration::from_secs(60 * 60),
+        schedule.next_check(datetime!(2025-01-10 07:00 UTC))
+    );
+
+    // Already passed, wait until tomorrow
+    assert_eq!(
+        Duration::from_secs(23 * 60 * 60),
+        schedule.next_check(datetime!(2025-01-10 09:00 UTC))
+    );
+}
+
+#[test]
+fn test_conversation_timeout() {
+    let schedule = BirthdaySchedule {
+        channel: SlackChannelId::new("C123".to_string()),
+        timezone: time_tz::timezones::db::europe::OSLO,
+        time: time!(09:00),
+    };
+
+    // Announced at 09:00 in Oslo, open until midnight there
+    assert_eq!(
+        Duration::from_secs(15 * 60 * 60),
+        schedule.conversation_timeout(datetime!(2025-01-10 08:00 UTC))
+    );
+
+    // Late in the day, open as long as any other conversation
+    assert_eq!(
+        CONVERSATION_TIMEOUT,
+        schedule.conversation_timeout(datetime!(2025-01-10 20:00 UTC))
+    );
+}
+
+#[test]
+fn test_birthday_prompt() {
+    let today = date!(2025 - 12 - 09);
+
+    assert_eq!(
+        "Det er Ola Nordmann som har bursdag i dag! Vedkommende blir 45 år",
+        birthday_prompt(&test_employee(1), today)
+    );
+
+    // The message is posted in public channels, the age would give away the year of birth
+    for dob_visibility in [FieldVisibility::Admins, FieldVisibility::Hidden] {
+        let employee = Employee {
+            dob_visibility,
+            ..test_employee(1)
+        };
+
+        assert_eq!(
+            "Det er Ola Nordmann som har bursdag i dag!",
+            birthday_prompt(&employee, today)
+        );
+    }
+}
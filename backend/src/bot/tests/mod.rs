@@ -0,0 +1,3 @@
+mod birthday_actor;
+mod birthdays;
+mod slack_profile_sync;
@@ -0,0 +1,185 @@
+//! Runs the Slack calls against a local stand-in for the Slack Web API.
+
+use crate::bot::slack_profile_sync_actor::{users_info_retrying, SlackAccountUpdate};
+use crate::model::*;
+use crate::slack_client::{auth_test, users_info, RateLimited, SlackUser, SlackUserProfile};
+use crate::web::tests::fake_slack::{FakeSlackApi, TEAM, TOKEN};
+use std::time::Duration;
+
+fn slack_account(name: &str, nick: &str, avatar: &str, deactivated: bool) -> SomeAccount {
+    SomeAccount {
+        id: SomeAccountId(1),
+        employee: EmployeeId(1),
+        network: SomeNetwork::Slack,
+        authenticated: true,
+        network_instance: Some(TEAM.to_string()),
+        network_avatar: None,
+        subject: Some("U0001".to_string()),
+        name: Some(name.to_string()),
+        nick: Some(nick.to_string()),
+        url: None,
+        avatar: Some(avatar.to_string()),
+        deactivated,
+    }
+}
+
+fn slack_user(deleted: bool, real_name: &str, display_name: &str) -> SlackUser {
+    SlackUser {
+        id: "U0001".to_string(),
+        is_bot: false,
+        deleted,
+        is_restricted: false,
+        is_ultra_restricted: false,
+        team_id: Some(TEAM.to_string()),
+        profile: SlackUserProfile {
+            display_name: display_name.to_string(),
+            real_name: real_name.to_string(),
+            image_192: Some("https://avatars.example.com/192.png".to_string()),
+            image_512: Some("https://avatars.example.com/512.png".to_string()),
+            email: None,
+        },
+    }
+}
+
+#[tokio::test]
+async fn test_auth_test() {
+    let slack = FakeSlackApi::start().await;
+    let client = reqwest::Client::new();
+
+    assert_eq!(
+        TEAM,
+        auth_test(&client, &slack.api_url, TOKEN).await.unwrap()
+    );
+    assert!(auth_test(&client, &slack.api_url, "xoxb-wrong")
+        .await
+        .is_err());
+}
+
+#[tokio::test]
+async fn test_users_info() {
+    let slack = FakeSlackApi::start().await;
+    slack.add_user("U0001", false, "Ola Nordmann", "ola");
+    slack.add_user("U0002", true, "Kari Nordmann", "");
+
+    let client = reqwest::Client::new();
+
+    let user = users_info(&client, &slack.api_url, TOKEN, "U0001")
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(!user.deleted);
+    assert_eq!("Ola Nordmann", user.profile.real_name);
+    assert_eq!("ola", user.profile.display_name);
+    assert_eq!(
+        Some("https://avatars.example.com/U0001_512.png"),
+        user.profile.image_512.as_deref()
+    );
+
+    let user = users_info(&client, &slack.api_url, TOKEN, "U0002")
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(user.deleted);
+
+    assert!(users_info(&client, &slack.api_url, TOKEN, "U9999")
+        .await
+        .unwrap()
+        .is_none());
+
+    assert!(users_info(&client, &slack.api_url, "xoxb-wrong", "U0001")
+        .await
+        .is_err());
+
+    slack.set_rate_limited();
+    let error = users_info(&client, &slack.api_url, TOKEN, "U0001")
+        .await
+        .unwrap_err();
+    assert_eq!(
+        Some(Duration::from_secs(30)),
+        error.downcast_ref::<RateLimited>().map(|e| e.retry_after)
+    );
+}
+
+#[tokio::test]
+async fn test_users_info_retrying() {
+    let slack = FakeSlackApi::start().await;
+    slack.add_user("U0001", false, "Ola Nordmann", "ola");
+
+    let client = reqwest::Client::new();
+
+    slack.rate_limit_next(3);
+    let user = users_info_retrying(&client, &slack.api_url, TOKEN, "U0001")
+        .await
+        .unwrap();
+    assert_eq!("U0001", user.unwrap().id);
+
+    // Gives up after three retries
+    slack.rate_limit_next(4);
+    assert!(users_info_retrying(&client, &slack.api_url, TOKEN, "U0001")
+        .await
+        .unwrap_err()
+        .is::<RateLimited>());
+
+    // Other errors aren't retried
+    assert!(
+        users_info_retrying(&client, &slack.api_url, "xoxb-wrong", "U0001")
+            .await
+            .is_err()
+    );
+}
+
+#[test]
+fn test_slack_account_update() {
+    let account = slack_account(
+        "Ola Nordmann",
+        "ola",
+        "https://avatars.example.com/512.png",
+        false,
+    );
+
+    // Nothing has changed
+    assert_eq!(
+        None,
+        SlackAccountUpdate::new(&account, Some(&slack_user(false, "Ola Nordmann", "ola")))
+    );
+
+    // A cleared display name clears the nick
+    assert_eq!(
+        Some(SlackAccountUpdate {
+            name: Some("Ola Normann".to_string()),
+            nick: None,
+            avatar: Some("https://avatars.example.com/512.png".to_string()),
+            deactivated: false,
+        }),
+        SlackAccountUpdate::new(&account, Some(&slack_user(false, "Ola Normann", " ")))
+    );
+
+    assert_eq!(
+        Some(SlackAccountUpdate {
+            name: Some("Ola Nordmann".to_string()),
+            nick: Some("ola".to_string()),
+            avatar: Some("https://avatars.example.com/512.png".to_string()),
+            deactivated: true,
+        }),
+        SlackAccountUpdate::new(&account, Some(&slack_user(true, "Ola Nordmann", "ola")))
+    );
+}
+
+#[test]
+fn test_slack_account_update_missing_user() {
+    let account = slack_account("Ola Nordmann", "ola", "https://example.com/a.png", false);
+
+    // The profile is kept as it was
+    assert_eq!(
+        Some(SlackAccountUpdate {
+            name: Some("Ola Nordmann".to_string()),
+            nick: Some("ola".to_string()),
+            avatar: Some("https://example.com/a.png".to_string()),
+            deactivated: true,
+        }),
+        SlackAccountUpdate::new(&account, None)
+    );
+
+    let account = slack_account("Ola Nordmann", "ola", "https://example.com/a.png", true);
+    assert_eq!(None, SlackAccountUpdate::new(&account, None));
+}
@@ -9,7 +9,6 @@ use std::env;
 use tokio::task::JoinHandle;
 #[cfg(feature = "loki")]
 use tracing_loki::BackgroundTaskController;
-use tracing_opentelemetry;
 use tracing_subscriber::layer::SubscriberExt;
 use tracing_subscriber::util::SubscriberInitExt;
 use tracing_subscriber::EnvFilter;
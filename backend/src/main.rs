@@ -3,23 +3,22 @@ mod birthday_assistant;
 mod bot;
 mod logging;
 mod macros;
-#[cfg(any())]
 mod meta;
 mod model;
 mod session;
-#[cfg(any())]
 mod skjera;
 mod slack_client;
 mod slack_interaction_server;
 mod web;
 
 use crate::birthday_assistant::BirthdayAssistant;
-use crate::bot::birthdays_actor::{BirthdaysActor, BirthdaysActorMsg};
+use crate::bot::birthdays_actor::{BirthdaySchedule, BirthdaysActor, BirthdaysActorMsg};
 use crate::bot::skjera_slack_conversation::SkjeraConversationMsg;
 use crate::bot::skjera_slack_conversations::SkjeraConversations;
+use crate::bot::slack_profile_sync_actor::SlackProfileSyncActor;
 use crate::bot::SlackClient;
 use crate::model::*;
-use crate::session::SkjeraSessionData;
+use crate::session::{PgSessionStore, SkjeraSessionData, AUTH_DATA_KEY};
 use crate::slack_interaction_server::SlackInteractionServer;
 use crate::web::web::create_router;
 use actor::slack::slack_conversation_server::{
@@ -30,13 +29,13 @@ use axum::http::StatusCode;
 use axum::response::{IntoResponse, Redirect, Response};
 use axum::Router;
 use axum_login::{login_required, AuthManagerLayerBuilder};
-use oauth2::basic::BasicClient;
+use clap::{Parser, Subcommand};
 use ractor::{Actor, ActorRef};
 use reqwest::Client as ReqwestClient;
 use sqlx::postgres::PgConnectOptions;
 use sqlx::{Pool, Postgres};
 use std::env;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::process::exit;
 use std::string::ToString;
 use std::sync::Arc;
@@ -46,18 +45,49 @@ use tokio::task::JoinHandle;
 use tower_http::services::ServeDir;
 use tower_http::trace::TraceLayer;
 use tower_sessions::cookie::SameSite::Lax;
-use tower_sessions::{MemoryStore, SessionManagerLayer, SessionStore};
+use tower_sessions::{ExpiredDeletion, Expiry, SessionManagerLayer, SessionStore};
 use tracing::{debug, info, warn};
+use web::bluesky::BlueskyVerifier;
+use web::forge::ForgeConnect;
 use web::oauth;
 use web::slack::SlackConnect;
 
 const VERSION_INFO: &str = env!("VERSION_INFO");
 
 pub(crate) type AuthSession = axum_login::AuthSession<ServerImpl>;
-const LOGIN_PATH: &'static str = "/login";
+const LOGIN_PATH: &str = "/login";
+
+#[derive(Parser)]
+#[command(version = VERSION_INFO)]
+struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Runs the web server, the default.
+    Serve,
+    /// Writes the employees and their SoMe accounts to a file.
+    Export {
+        /// csv or json, the default is based on the file extension.
+        #[arg(long)]
+        format: Option<DirectoryFormat>,
+        file: PathBuf,
+    },
+    /// Creates or updates the employees and their SoMe accounts in a file, matched by email.
+    Import {
+        /// csv or json, the default is based on the file extension.
+        #[arg(long)]
+        format: Option<DirectoryFormat>,
+        file: PathBuf,
+    },
+}
 
 #[tokio::main]
 async fn main() {
+    let cli = Cli::parse();
+
     println!("Starting skjera. version={}", VERSION_INFO);
 
     // We don't care if there is a problem here
@@ -100,6 +130,25 @@ async fn main() {
     let pool = sqlx::postgres::PgPool::connect_lazy_with(options);
     let dao = Dao::new(pool.clone());
 
+    let command = match cli.command {
+        Some(Command::Export { format, file }) => Some(export_command(&dao, format, file).await),
+        Some(Command::Import { format, file }) => Some(import_command(&dao, format, file).await),
+        Some(Command::Serve) | None => None,
+    };
+
+    if let Some(result) = command {
+        logging_subsystem.shutdown().await;
+
+        match result {
+            Ok(true) => exit(0),
+            Ok(false) => exit(2),
+            Err(e) => {
+                eprintln!("error: {:#}", e);
+                exit(1)
+            }
+        }
+    }
+
     let assets_path = if is_local { "backend/assets" } else { "assets" }.to_string();
     let ctx = ReqwestClient::new();
     let cfg = match Config::new() {
@@ -110,15 +159,26 @@ async fn main() {
         }
     };
 
-    let basic_client = oauth::build_oauth_client(
-        cfg.redirect_url.clone(),
+    let google_login = match oauth::GoogleLogin::new(
+        cfg.issuer_url.clone(),
         cfg.client_id.clone(),
         cfg.client_secret.clone(),
-    );
+        cfg.redirect_url.clone(),
+    )
+    .await
+    {
+        Ok(google_login) => google_login,
+        Err(e) => {
+            eprintln!("could not configure Google login: {}", e);
+            exit(1)
+        }
+    };
 
     let slack_connect = match &cfg.slack_config {
         Some(sc) => SlackConnect::new(
             ctx.clone(),
+            web::slack::SLACK_ISSUER_URL.to_string(),
+            web::slack::SLACK_API_URL.to_string(),
             sc.client_id.clone(),
             sc.client_secret.clone(),
             sc.redirect_url.clone(),
@@ -144,6 +204,7 @@ async fn main() {
         birthday_bot.clone(),
         slack_interaction_server.clone(),
         &cfg.slack_config,
+        cfg.birthday_schedule.clone(),
     )
     .await
     {
@@ -151,17 +212,76 @@ async fn main() {
         Err(e) => return println!("could not configure slack: {}", e),
     };
 
+    let slack_profile_sync = match &cfg.slack_config {
+        Some(sc) => Some(
+            Actor::spawn(
+                None,
+                SlackProfileSyncActor::new(
+                    dao.clone(),
+                    ctx.clone(),
+                    web::slack::SLACK_API_URL.to_string(),
+                    sc.bot_token.token_value.0.clone(),
+                    cfg.slack_profile_sync_interval,
+                ),
+                (),
+            )
+            .await
+            .expect("Actor failed to start"),
+        ),
+        None => None,
+    };
+
+    let bluesky = BlueskyVerifier::new(ctx.clone(), cfg.bluesky_appview_url.clone());
+
+    let github_connect = match &cfg.github_config {
+        Some(gc) => match ForgeConnect::github(
+            ctx.clone(),
+            &gc.url,
+            web::forge::github_api_url(&gc.url),
+            gc.client_id.clone(),
+            gc.client_secret.clone(),
+            gc.redirect_url.clone(),
+        ) {
+            Ok(github_connect) => Some(github_connect),
+            Err(e) => {
+                eprintln!("could not configure GitHub connect: {}", e);
+                exit(1)
+            }
+        },
+        None => None,
+    };
+
+    let gitlab_connect = match &cfg.gitlab_config {
+        Some(gc) => match ForgeConnect::gitlab(
+            ctx.clone(),
+            &gc.url,
+            gc.client_id.clone(),
+            gc.client_secret.clone(),
+            gc.redirect_url.clone(),
+        ) {
+            Ok(gitlab_connect) => Some(gitlab_connect),
+            Err(e) => {
+                eprintln!("could not configure GitLab connect: {}", e);
+                exit(1)
+            }
+        },
+        None => None,
+    };
+
     let server_impl = ServerImpl {
         pool: pool.clone(),
         assets_path,
-        ctx,
         cfg,
-        basic_client,
+        google_login,
         bot,
         slack_client,
         employee_dao: dao,
         slack_connect,
+        github_connect,
+        gitlab_connect,
         birthday_bot,
+        bluesky,
+        http_client: ctx,
         // slack_interaction_actor,
     };
 
@@ -171,19 +291,35 @@ async fn main() {
     //     info!(name: "my-event-name", target: "my-system", event_id = 20, user_name = "otel", user_email = "otel@opentelemetry.io", message = "This is an example message");
     // });
 
-    let session_store = MemoryStore::default();
+    let session_store = PgSessionStore::new(server_impl.employee_dao.clone());
+
+    // Every replica runs this, deleting expired rows is idempotent.
+    let session_deletion = tokio::task::spawn(
+        session_store
+            .clone()
+            .continuously_delete_expired(std::time::Duration::from_secs(60 * 60)),
+    );
+
     let session_layer = SessionManagerLayer::new(session_store)
         .with_secure(true)
         .with_http_only(true)
-        .with_same_site(Lax);
+        .with_same_site(Lax)
+        .with_expiry(Expiry::OnInactivity(time::Duration::days(14)));
 
     let r = start_server(server_impl, session_layer, "0.0.0.0:8080").await;
 
+    session_deletion.abort();
+
     if let Some((birthdays, birthdays_actor)) = birthdays {
         birthdays.stop(None);
         birthdays_actor.await.unwrap();
     }
 
+    if let Some((slack_profile_sync, slack_profile_sync_actor)) = slack_profile_sync {
+        slack_profile_sync.stop(None);
+        slack_profile_sync_actor.await.unwrap();
+    }
+
     slack_interaction_server.stop(None);
     slack_interaction_server_actor.await.unwrap();
 
@@ -203,12 +339,68 @@ async fn main() {
     }
 }
 
+fn directory_format(
+    format: Option<DirectoryFormat>,
+    file: &Path,
+) -> anyhow::Result<DirectoryFormat> {
+    format
+        .or_else(|| DirectoryFormat::from_path(file))
+        .ok_or_else(|| anyhow!("unknown file type, use --format"))
+}
+
+async fn export_command(
+    dao: &Dao,
+    format: Option<DirectoryFormat>,
+    file: PathBuf,
+) -> anyhow::Result<bool> {
+    let format = directory_format(format, &file)?;
+
+    let employees = dao.all_employees().await?;
+    let ids: Vec<_> = employees.iter().map(|e| e.id).collect();
+    let accounts = dao.some_accounts_by_employees(&ids).await?;
+
+    let directory = directory_employees(employees, &accounts);
+    std::fs::write(&file, write_directory(format, &directory)?)?;
+
+    println!(
+        "Exported {} employees to {}",
+        directory.len(),
+        file.display()
+    );
+
+    Ok(true)
+}
+
+/// Returns false if any of the rows failed.
+async fn import_command(
+    dao: &Dao,
+    format: Option<DirectoryFormat>,
+    file: PathBuf,
+) -> anyhow::Result<bool> {
+    let format = directory_format(format, &file)?;
+
+    let data = std::fs::read(&file)?;
+    let (employees, errors) = read_directory(format, &data)?;
+    let report = import_directory(dao, employees, errors).await?;
+
+    println!(
+        "Created {}, updated {} and left {} employees unchanged",
+        report.created, report.updated, report.unchanged
+    );
+    for error in &report.errors {
+        println!("Row {}: {}", error.row, error.message);
+    }
+
+    Ok(report.errors.is_empty())
+}
+
 async fn configure_slack(
     pool: Pool<Postgres>,
     dao: Dao,
     birthday_assistant: Option<BirthdayAssistant>,
     slack_interaction_actor: ActorRef<<SlackInteractionServer as Actor>::Msg>,
     slack_config: &Option<SlackConfig>,
+    birthday_schedule: Option<BirthdaySchedule>,
 ) -> anyhow::Result<(
     Option<Arc<SlackClient>>,
     Option<bot::SkjeraBot<Postgres>>,
@@ -237,6 +429,7 @@ async fn configure_slack(
                 birthday_assistant,
                 slack_interaction_actor.clone(),
                 slack_client.clone(),
+                birthday_schedule,
             ),
             (),
         )
@@ -279,17 +472,20 @@ struct ServerImpl {
     /// EmployeeDao, but not anywhere else. I'm not sure if cloning the Pool is ok or not.
     /// Perhaps the EmployeeDao shouldn't use the pool at all and everything should just use this
     /// single reference.
-    #[allow(dead_code)]
     pool: Pool<Postgres>,
     assets_path: String,
-    ctx: ReqwestClient,
     cfg: Config,
-    basic_client: BasicClient,
+    google_login: oauth::GoogleLogin,
     bot: Option<bot::SkjeraBot<Postgres>>,
     pub slack_client: Option<Arc<SlackClient>>,
     pub employee_dao: Dao,
     pub slack_connect: Option<SlackConnect>,
+    pub github_connect: Option<ForgeConnect>,
+    pub gitlab_connect: Option<ForgeConnect>,
     pub birthday_bot: Option<BirthdayAssistant>,
+    pub bluesky: BlueskyVerifier,
+    /// For fetching resources from other sites, like avatars.
+    pub http_client: ReqwestClient,
 }
 
 impl ServerImpl {
@@ -299,11 +495,23 @@ impl ServerImpl {
             session_hash: Box::new(e.id.0.to_be_bytes()),
             email: e.email,
             name: e.name,
-            slack_connect: None,
+            role: e.role,
+            api_token_scope: None,
         }
     }
 }
 
+/// The same span as the default one, but without the query. The calendar feed is authenticated
+/// with a token in the query, and it must not end up in the logs.
+fn request_span(request: &axum::extract::Request) -> tracing::Span {
+    tracing::debug_span!(
+        "request",
+        method = %request.method(),
+        uri = %request.uri().path(),
+        version = ?request.version(),
+    )
+}
+
 async fn start_server<SS>(
     server_impl: ServerImpl,
     session_layer: SessionManagerLayer<SS>,
@@ -315,18 +523,21 @@ where
     let assets_path = Path::new(&server_impl.assets_path);
     let assets = Router::new().nest_service("/assets", ServeDir::new(assets_path));
 
-    let (public, private) = create_router(&server_impl)?;
+    let (public, private, api) = create_router(&server_impl)?;
     let private = private.route_layer(login_required!(ServerImpl, login_url = LOGIN_PATH));
 
-    let auth_layer = AuthManagerLayerBuilder::new(server_impl.clone(), session_layer).build();
+    let auth_layer = AuthManagerLayerBuilder::new(server_impl.clone(), session_layer)
+        .with_data_key(AUTH_DATA_KEY)
+        .build();
 
     let app = Router::new()
         .merge(private)
         .merge(public)
+        .with_state(server_impl)
+        .merge(api)
         .layer(auth_layer)
-        .layer(TraceLayer::new_for_http())
-        .fallback_service(assets.clone())
-        .with_state(server_impl);
+        .layer(TraceLayer::new_for_http().make_span_with(request_span))
+        .fallback_service(assets.clone());
 
     // Run the server with graceful shutdown
     let listener = TcpListener::bind(addr)
@@ -369,14 +580,28 @@ async fn shutdown_signal() {
 
 #[derive(Clone, Debug)]
 struct Config {
+    pub issuer_url: String,
     pub client_id: String,
     pub client_secret: String,
     pub redirect_url: String,
+    /// Google Workspace domains (the `hd` claim) whose users can sign up without approval.
+    pub allowed_domains: Vec<String>,
+    /// Can always log in, and are made admins when they do.
+    pub admin_emails: Vec<String>,
     pub slack_config: Option<SlackConfig>,
+    /// How often the linked Slack accounts are updated from Slack.
+    pub slack_profile_sync_interval: std::time::Duration,
+    pub github_config: Option<ForgeConfig>,
+    pub gitlab_config: Option<ForgeConfig>,
+    pub bluesky_appview_url: String,
+    pub birthday_schedule: Option<BirthdaySchedule>,
 }
 
 impl Config {
     fn new() -> Result<Self, String> {
+        let issuer_url =
+            env::var("OAUTH_ISSUER_URL").unwrap_or_else(|_| oauth::GOOGLE_ISSUER_URL.to_string());
+
         let client_id =
             std::env::var("OAUTH_CLIENT_ID").map_err(|_| "OAUTH_CLIENT_ID not set".to_string())?;
 
@@ -386,6 +611,9 @@ impl Config {
         let redirect_url = std::env::var("OAUTH_REDIRECT_URL")
             .map_err(|_| "OAUTH_REDIRECT_URL not set".to_string())?;
 
+        let allowed_domains = env_list("ALLOWED_DOMAINS");
+        let admin_emails = env_list("ADMIN_EMAILS");
+
         let slack_config = match (
             env::var("SLACK_CLIENT_ID"),
             env::var("SLACK_CLIENT_SECRET"),
@@ -409,13 +637,87 @@ impl Config {
             _ => None,
         };
 
+        let slack_profile_sync_interval = match env::var("SLACK_PROFILE_SYNC_HOURS") {
+            Ok(hours) => hours
+                .parse::<u64>()
+                .ok()
+                .filter(|hours| *hours > 0)
+                .ok_or_else(|| {
+                    format!("SLACK_PROFILE_SYNC_HOURS is not a valid number: {}", hours)
+                })?,
+            Err(_) => 6,
+        };
+        let slack_profile_sync_interval =
+            std::time::Duration::from_secs(slack_profile_sync_interval * 60 * 60);
+
+        let github_config = ForgeConfig::from_env("GITHUB", web::forge::GITHUB_URL);
+        let gitlab_config = ForgeConfig::from_env("GITLAB", web::forge::GITLAB_URL);
+
+        let bluesky_appview_url = env::var("BLUESKY_APPVIEW_URL")
+            .unwrap_or_else(|_| web::bluesky::BLUESKY_APPVIEW_URL.to_string());
+
+        let birthday_schedule = match env::var("BIRTHDAY_CHANNEL") {
+            Ok(channel) => {
+                let timezone =
+                    env::var("BIRTHDAY_TIMEZONE").unwrap_or_else(|_| "Europe/Oslo".to_string());
+                let timezone = time_tz::timezones::get_by_name(&timezone).ok_or_else(|| {
+                    format!("BIRTHDAY_TIMEZONE is not a valid timezone: {}", timezone)
+                })?;
+
+                let time = env::var("BIRTHDAY_TIME").unwrap_or_else(|_| "09:00".to_string());
+                let format = time::format_description::parse("[hour]:[minute]")
+                    .map_err(|e| e.to_string())?;
+                let time = time::Time::parse(&time, &format)
+                    .map_err(|_| format!("BIRTHDAY_TIME is not a valid time (HH:MM): {}", time))?;
+
+                Some(BirthdaySchedule {
+                    channel: slack_morphism::prelude::SlackChannelId::new(channel),
+                    timezone,
+                    time,
+                })
+            }
+            Err(_) => None,
+        };
+
         Ok(Config {
+            issuer_url,
             client_id,
             client_secret,
             redirect_url,
+            allowed_domains,
+            admin_emails,
             slack_config,
+            slack_profile_sync_interval,
+            github_config,
+            gitlab_config,
+            bluesky_appview_url,
+            birthday_schedule,
         })
     }
+
+    fn is_allowed_domain(&self, domain: Option<&str>) -> bool {
+        domain.is_some_and(|domain| {
+            self.allowed_domains
+                .iter()
+                .any(|d| d.eq_ignore_ascii_case(domain))
+        })
+    }
+
+    fn is_admin(&self, email: &str) -> bool {
+        self.admin_emails
+            .iter()
+            .any(|e| e.eq_ignore_ascii_case(email))
+    }
+}
+
+/// Reads a comma separated list from the environment.
+fn env_list(key: &str) -> Vec<String> {
+    env::var(key)
+        .unwrap_or_default()
+        .split(',')
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .collect()
 }
 
 #[derive(Clone, Debug)]
@@ -445,6 +747,35 @@ impl SlackConfig {
     }
 }
 
+/// The OAuth app on GitHub or GitLab that is used for connecting accounts.
+#[derive(Clone, Debug)]
+struct ForgeConfig {
+    url: String,
+    client_id: String,
+    client_secret: String,
+    redirect_url: String,
+}
+
+impl ForgeConfig {
+    /// Reads `<prefix>_CLIENT_ID`, `<prefix>_CLIENT_SECRET` and `<prefix>_REDIRECT_URL`, and
+    /// `<prefix>_URL` for self-hosted installations.
+    fn from_env(prefix: &str, default_url: &str) -> Option<Self> {
+        let var = |key: &str| env::var(format!("{}_{}", prefix, key));
+
+        match (var("CLIENT_ID"), var("CLIENT_SECRET"), var("REDIRECT_URL")) {
+            (Ok(client_id), Ok(client_secret), Ok(redirect_url)) => Some(ForgeConfig {
+                url: var("URL")
+                    .map(|url| url.trim_end_matches('/').to_string())
+                    .unwrap_or_else(|_| default_url.to_string()),
+                client_id,
+                client_secret,
+                redirect_url,
+            }),
+            _ => None,
+        }
+    }
+}
+
 #[derive(Debug, thiserror::Error)]
 pub enum AppError {
     #[error(transparent)]
@@ -465,6 +796,12 @@ pub enum AppError {
 
 impl IntoResponse for AppError {
     fn into_response(self) -> Response {
+        if let AppError::Anyhow(e) = &self {
+            if e.is::<web::oauth::InvalidOauthState>() {
+                return (StatusCode::BAD_REQUEST, "Invalid oauth state").into_response();
+            }
+        }
+
         tracing::error!("Application error: {:#}", self);
 
         (StatusCode::INTERNAL_SERVER_ERROR, "Something went wrong").into_response()
@@ -1,5 +1,49 @@
+mod api_token;
+mod assignment;
+mod avatar;
+mod birthday;
+mod customer;
+mod directory;
 pub(crate) mod employee;
+mod login_access;
+mod news;
 mod some_account;
+mod some_account_verification;
+mod web_session;
 
+#[cfg(test)]
+pub(crate) mod tests;
+
+pub use crate::model::api_token::*;
+pub use crate::model::assignment::*;
+pub use crate::model::avatar::*;
+pub use crate::model::birthday::*;
+pub use crate::model::customer::*;
+pub use crate::model::directory::*;
 pub use crate::model::employee::*;
+pub use crate::model::login_access::*;
+pub use crate::model::news::*;
 pub use crate::model::some_account::*;
+pub use crate::model::some_account_verification::*;
+pub use crate::model::web_session::*;
+
+use sqlx::{Pool, Postgres};
+
+#[derive(Debug)]
+pub struct Dao {
+    pool: Pool<Postgres>,
+}
+
+impl Dao {
+    pub fn new(pool: Pool<Postgres>) -> Self {
+        Self { pool }
+    }
+}
+
+impl Clone for Dao {
+    fn clone(&self) -> Self {
+        Self {
+            pool: self.pool.clone(),
+        }
+    }
+}
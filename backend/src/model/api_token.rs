@@ -0,0 +1,146 @@
+use crate::id_type;
+use crate::model::*;
+use async_trait::async_trait;
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use sqlx::types::time::OffsetDateTime;
+use sqlx::*;
+
+id_type!(ApiTokenId);
+
+const API_TOKEN_PREFIX: &str = "skjera_";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, sqlx::Type, Serialize, Deserialize)]
+#[sqlx(type_name = "varchar", rename_all = "kebab-case")]
+#[serde(rename_all = "kebab-case")]
+pub enum ApiTokenScope {
+    /// Only allows safe requests, like GET.
+    ReadOnly,
+    ReadWrite,
+    /// Can only be used for the birthday calendar feed, not the API. Calendar apps put the token
+    /// in the URL, where it is easily leaked.
+    Calendar,
+}
+
+impl ApiTokenScope {
+    pub fn is_read_only(&self) -> bool {
+        *self != ApiTokenScope::ReadWrite
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ApiTokenScope::ReadOnly => "Read-only",
+            ApiTokenScope::ReadWrite => "Read-write",
+            ApiTokenScope::Calendar => "Birthday calendar",
+        }
+    }
+}
+
+/// A personal access token. Only the hash of the token is stored, the token itself is only shown
+/// to the employee when it is created.
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct ApiToken {
+    pub id: ApiTokenId,
+    pub employee: EmployeeId,
+    pub name: String,
+    pub scope: ApiTokenScope,
+    pub created_at: OffsetDateTime,
+    pub last_used_at: Option<OffsetDateTime>,
+}
+
+/// Creates a new random token.
+pub fn generate_api_token() -> String {
+    let mut bytes = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut bytes);
+
+    format!("{}{}", API_TOKEN_PREFIX, hex::encode(bytes))
+}
+
+/// The tokens are random enough that a plain SHA-256 is sufficient, there is nothing to brute
+/// force.
+pub fn hash_api_token(token: &str) -> Vec<u8> {
+    Sha256::digest(token.as_bytes()).to_vec()
+}
+
+#[async_trait]
+pub(crate) trait ApiTokenDao {
+    async fn api_tokens_by_employee(&self, employee: EmployeeId) -> Result<Vec<ApiToken>, Error>;
+
+    async fn insert_api_token(
+        &self,
+        employee: EmployeeId,
+        name: String,
+        scope: ApiTokenScope,
+        token_hash: Vec<u8>,
+    ) -> Result<ApiToken, Error>;
+
+    /// Looks up the token with the given hash and marks it as used.
+    async fn use_api_token(&self, token_hash: Vec<u8>) -> Result<Option<ApiToken>, Error>;
+
+    async fn delete_api_token(&self, id: ApiTokenId, employee: EmployeeId) -> Result<u64, Error>;
+}
+
+#[async_trait]
+impl ApiTokenDao for Dao {
+    #[tracing::instrument]
+    async fn api_tokens_by_employee(&self, employee: EmployeeId) -> Result<Vec<ApiToken>, Error> {
+        sqlx::query_as!(
+            ApiToken,
+            r#"SELECT id, employee, name, scope AS "scope: ApiTokenScope", created_at, last_used_at
+            FROM skjera.api_token
+            WHERE employee=$1
+            ORDER BY created_at"#,
+            employee.0
+        )
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    #[tracing::instrument(skip(token_hash))]
+    async fn insert_api_token(
+        &self,
+        employee: EmployeeId,
+        name: String,
+        scope: ApiTokenScope,
+        token_hash: Vec<u8>,
+    ) -> Result<ApiToken, Error> {
+        sqlx::query_as!(
+            ApiToken,
+            r#"INSERT INTO skjera.api_token(employee, name, scope, token_hash)
+            VALUES ($1, $2, $3, $4)
+            RETURNING id, employee, name, scope AS "scope: ApiTokenScope", created_at, last_used_at"#,
+            employee.0,
+            name,
+            scope as ApiTokenScope,
+            token_hash,
+        )
+        .fetch_one(&self.pool)
+        .await
+    }
+
+    #[tracing::instrument(skip(token_hash))]
+    async fn use_api_token(&self, token_hash: Vec<u8>) -> Result<Option<ApiToken>, Error> {
+        sqlx::query_as!(
+            ApiToken,
+            r#"UPDATE skjera.api_token SET last_used_at=NOW()
+            WHERE token_hash=$1
+            RETURNING id, employee, name, scope AS "scope: ApiTokenScope", created_at, last_used_at"#,
+            token_hash,
+        )
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    #[tracing::instrument]
+    async fn delete_api_token(&self, id: ApiTokenId, employee: EmployeeId) -> Result<u64, Error> {
+        sqlx::query!(
+            "DELETE FROM skjera.api_token WHERE id=$1 AND employee=$2",
+            id.0,
+            employee.0,
+        )
+        .execute(&self.pool)
+        .await
+        .map(|r| r.rows_affected())
+    }
+}
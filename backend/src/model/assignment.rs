@@ -0,0 +1,197 @@
+use crate::id_type;
+use crate::model::*;
+use async_trait::async_trait;
+use sqlx::types::time::Date;
+use sqlx::*;
+
+id_type!(AssignmentId);
+
+/// An employee working for a customer for a period of time. An assignment without an end date is
+/// open-ended.
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct Assignment {
+    pub id: AssignmentId,
+    pub customer: CustomerId,
+    pub employee: EmployeeId,
+    pub description: String,
+    pub start_date: Date,
+    pub end_date: Option<Date>,
+}
+
+#[async_trait]
+pub(crate) trait AssignmentDao {
+    async fn assignment_by_id(&self, id: AssignmentId) -> Result<Option<Assignment>, Error>;
+
+    async fn assignments_by_customer(&self, customer: CustomerId)
+        -> Result<Vec<Assignment>, Error>;
+
+    async fn assignments_by_employee(&self, employee: EmployeeId)
+        -> Result<Vec<Assignment>, Error>;
+
+    /// The assignment the employee is on at `today`. If there are overlapping assignments, the
+    /// one that started last wins.
+    async fn current_assignment(
+        &self,
+        employee: EmployeeId,
+        today: Date,
+    ) -> Result<Option<Assignment>, Error>;
+
+    async fn insert_assignment(
+        &self,
+        customer: CustomerId,
+        employee: EmployeeId,
+        description: String,
+        start_date: Date,
+        end_date: Option<Date>,
+    ) -> Result<Assignment, Error>;
+
+    async fn update_assignment(&self, assignment: &Assignment) -> Result<Assignment, Error>;
+
+    async fn delete_assignment(&self, id: AssignmentId) -> Result<u64, Error>;
+}
+
+#[async_trait]
+impl AssignmentDao for Dao {
+    #[tracing::instrument]
+    async fn assignment_by_id(&self, id: AssignmentId) -> Result<Option<Assignment>, Error> {
+        sqlx::query_as!(
+            Assignment,
+            "SELECT * FROM skjera.assignment WHERE id=$1",
+            id.0
+        )
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    #[tracing::instrument]
+    async fn assignments_by_customer(
+        &self,
+        customer: CustomerId,
+    ) -> Result<Vec<Assignment>, Error> {
+        sqlx::query_as!(
+            Assignment,
+            "SELECT * FROM skjera.assignment WHERE customer=$1 ORDER BY start_date DESC",
+            customer.0
+        )
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    #[tracing::instrument]
+    async fn assignments_by_employee(
+        &self,
+        employee: EmployeeId,
+    ) -> Result<Vec<Assignment>, Error> {
+        sqlx::query_as!(
+            Assignment,
+            "SELECT * FROM skjera.assignment WHERE employee=$1 ORDER BY start_date DESC",
+            employee.0
+        )
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    #[tracing::instrument]
+    async fn current_assignment(
+        &self,
+        employee: EmployeeId,
+        today: Date,
+    ) -> Result<Option<Assignment>, Error> {
+        sqlx::query_as!(
+            Assignment,
+            "SELECT * FROM skjera.assignment
+            WHERE employee=$1 AND start_date <= $2 AND (end_date IS NULL OR end_date >= $2)
+            ORDER BY start_date DESC
+            LIMIT 1",
+            employee.0,
+            today
+        )
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    #[tracing::instrument]
+    async fn insert_assignment(
+        &self,
+        customer: CustomerId,
+        employee: EmployeeId,
+        description: String,
+        start_date: Date,
+        end_date: Option<Date>,
+    ) -> Result<Assignment, Error> {
+        let mut tx = self.pool.begin().await?;
+
+        let assignment = sqlx::query_as!(
+            Assignment,
+            "INSERT INTO skjera.assignment (customer, employee, description, start_date, end_date)
+            VALUES($1, $2, $3, $4, $5)
+            RETURNING *",
+            customer.0,
+            employee.0,
+            description,
+            start_date,
+            end_date
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+
+        let (employee_name, customer_name) = sqlx::query!(
+            "SELECT e.name AS employee, c.name AS customer
+            FROM skjera.employee e, skjera.customer c
+            WHERE e.id=$1 AND c.id=$2",
+            employee.0,
+            customer.0
+        )
+        .fetch_one(&mut *tx)
+        .await
+        .map(|r| (r.employee, r.customer))?;
+
+        let mut body = format!(
+            "{} starts working for {} on {}.",
+            employee_name, customer_name, assignment.start_date
+        );
+        if !assignment.description.is_empty() {
+            body = format!("{} {}", body, assignment.description);
+        }
+
+        news::insert_news_item(
+            &mut tx,
+            NewsKind::AssignmentStarted,
+            format!("{} starts at {}", employee_name, customer_name),
+            body,
+            Some(employee),
+            Some(customer),
+            Some(assignment.id),
+        )
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(assignment)
+    }
+
+    #[tracing::instrument]
+    async fn update_assignment(&self, assignment: &Assignment) -> Result<Assignment, Error> {
+        sqlx::query_as!(
+            Assignment,
+            "UPDATE skjera.assignment SET employee=$1, description=$2, start_date=$3, end_date=$4
+            WHERE id=$5
+            RETURNING *",
+            assignment.employee.0,
+            assignment.description,
+            assignment.start_date,
+            assignment.end_date,
+            assignment.id.0
+        )
+        .fetch_one(&self.pool)
+        .await
+    }
+
+    #[tracing::instrument]
+    async fn delete_assignment(&self, id: AssignmentId) -> Result<u64, Error> {
+        sqlx::query!("DELETE FROM skjera.assignment WHERE id=$1", id.0)
+            .execute(&self.pool)
+            .await
+            .map(|r| r.rows_affected())
+    }
+}
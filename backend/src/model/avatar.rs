@@ -0,0 +1,110 @@
+use crate::id_type;
+use crate::model::*;
+use async_trait::async_trait;
+use sqlx::types::time::OffsetDateTime;
+use sqlx::*;
+
+id_type!(AvatarId);
+
+/// An avatar that has been fetched from a SoMe network. The image itself is stored per size in
+/// `avatar_image`.
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct Avatar {
+    pub id: AvatarId,
+    /// The ETag from the upstream server.
+    pub etag: Option<String>,
+    pub fetched_at: OffsetDateTime,
+}
+
+#[async_trait]
+pub(crate) trait AvatarDao {
+    async fn avatar_by_url(&self, url: &str) -> Result<Option<Avatar>, Error>;
+
+    async fn avatar_image(&self, avatar: AvatarId, size: i32) -> Result<Option<Vec<u8>>, Error>;
+
+    /// Stores a freshly fetched avatar, replacing any images that were stored for the same URL.
+    async fn save_avatar(
+        &self,
+        url: &str,
+        etag: Option<String>,
+        images: Vec<(i32, Vec<u8>)>,
+    ) -> Result<AvatarId, Error>;
+
+    /// Marks the avatar as fetched now, for when the upstream server says it hasn't changed.
+    async fn touch_avatar(&self, id: AvatarId) -> Result<u64, Error>;
+}
+
+#[async_trait]
+impl AvatarDao for Dao {
+    #[tracing::instrument]
+    async fn avatar_by_url(&self, url: &str) -> Result<Option<Avatar>, Error> {
+        sqlx::query_as!(
+            Avatar,
+            "SELECT id, etag, fetched_at FROM skjera.avatar WHERE url=$1",
+            url
+        )
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    #[tracing::instrument]
+    async fn avatar_image(&self, avatar: AvatarId, size: i32) -> Result<Option<Vec<u8>>, Error> {
+        sqlx::query_scalar!(
+            "SELECT data FROM skjera.avatar_image WHERE avatar=$1 AND size=$2",
+            avatar.0,
+            size
+        )
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    #[tracing::instrument(skip(images))]
+    async fn save_avatar(
+        &self,
+        url: &str,
+        etag: Option<String>,
+        images: Vec<(i32, Vec<u8>)>,
+    ) -> Result<AvatarId, Error> {
+        let mut tx = self.pool.begin().await?;
+
+        let id = sqlx::query_scalar!(
+            r#"INSERT INTO skjera.avatar(url, etag, fetched_at) VALUES ($1, $2, NOW())
+            ON CONFLICT (url) DO UPDATE SET etag=excluded.etag, fetched_at=excluded.fetched_at
+            RETURNING id AS "id: AvatarId""#,
+            url,
+            etag,
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+
+        sqlx::query!("DELETE FROM skjera.avatar_image WHERE avatar=$1", id.0)
+            .execute(&mut *tx)
+            .await?;
+
+        for (size, data) in images {
+            sqlx::query!(
+                "INSERT INTO skjera.avatar_image(avatar, size, data) VALUES ($1, $2, $3)",
+                id.0,
+                size,
+                data,
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+
+        Ok(id)
+    }
+
+    #[tracing::instrument]
+    async fn touch_avatar(&self, id: AvatarId) -> Result<u64, Error> {
+        sqlx::query!(
+            "UPDATE skjera.avatar SET fetched_at=NOW() WHERE id=$1",
+            id.0
+        )
+        .execute(&self.pool)
+        .await
+        .map(|r| r.rows_affected())
+    }
+}
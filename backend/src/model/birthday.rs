@@ -0,0 +1,262 @@
+use crate::id_type;
+use crate::model::*;
+use async_trait::async_trait;
+use sqlx::types::time::{Date, OffsetDateTime};
+use sqlx::types::Uuid;
+use sqlx::*;
+use time::Month;
+
+id_type!(BirthdayConversationId);
+id_type!(BirthdayGreetingId);
+
+/// The persisted state of a birthday message in Slack that is waiting for someone to interact with
+/// it.
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct BirthdayConversation {
+    pub id: BirthdayConversationId,
+    pub team: String,
+    pub channel: String,
+    pub ts: String,
+    pub who: String,
+    pub employee: Option<EmployeeId>,
+    pub birthday_message: Option<String>,
+    pub generate_interaction: Option<Uuid>,
+    pub send_interaction: Option<Uuid>,
+    pub send_dm_interaction: Option<Uuid>,
+    pub expires_at: OffsetDateTime,
+}
+
+/// Returns true if someone born on `dob` celebrates their birthday on `today`.
+///
+/// People born on February 29th celebrate on February 28th in non-leap years.
+pub fn is_birthday(dob: Date, today: Date) -> bool {
+    if dob.month() == Month::February && dob.day() == 29 && !time::util::is_leap_year(today.year())
+    {
+        return today.month() == Month::February && today.day() == 28;
+    }
+
+    dob.month() == today.month() && dob.day() == today.day()
+}
+
+#[async_trait]
+pub(crate) trait BirthdayDao {
+    /// The employees to congratulate today, those that opted out of announcements are excluded.
+    async fn employees_with_birthday(&self, today: Date) -> Result<Vec<Employee>, Error>;
+
+    /// Records that the birthday of `employee` has been announced on `date`. Returns false if it
+    /// already had been announced.
+    async fn insert_birthday_announcement(
+        &self,
+        employee: EmployeeId,
+        date: Date,
+    ) -> Result<bool, Error>;
+
+    /// Undoes [BirthdayDao::insert_birthday_announcement] when the announcement couldn't be posted,
+    /// so that it is tried again.
+    async fn delete_birthday_announcement(
+        &self,
+        employee: EmployeeId,
+        date: Date,
+    ) -> Result<u64, Error>;
+
+    async fn birthday_conversations(&self) -> Result<Vec<BirthdayConversation>, Error>;
+
+    #[allow(clippy::too_many_arguments)]
+    async fn insert_birthday_conversation(
+        &self,
+        team: String,
+        channel: String,
+        ts: String,
+        who: String,
+        employee: Option<EmployeeId>,
+        generate_interaction: Uuid,
+        expires_at: OffsetDateTime,
+    ) -> Result<BirthdayConversation, Error>;
+
+    async fn update_birthday_conversation(
+        &self,
+        id: BirthdayConversationId,
+        birthday_message: Option<String>,
+        generate_interaction: Option<Uuid>,
+        send_interaction: Option<Uuid>,
+        send_dm_interaction: Option<Uuid>,
+        expires_at: OffsetDateTime,
+    ) -> Result<BirthdayConversation, Error>;
+
+    async fn delete_birthday_conversation(&self, id: BirthdayConversationId) -> Result<u64, Error>;
+
+    #[allow(clippy::too_many_arguments)]
+    async fn insert_birthday_greeting(
+        &self,
+        employee: Option<EmployeeId>,
+        who: String,
+        channel: String,
+        ts: String,
+        message: String,
+        sent_by: Option<String>,
+    ) -> Result<BirthdayGreetingId, Error>;
+}
+
+#[async_trait]
+impl BirthdayDao for Dao {
+    #[tracing::instrument]
+    async fn employees_with_birthday(&self, today: Date) -> Result<Vec<Employee>, Error> {
+        let employees = sqlx::query_as!(
+            Employee,
+            r#"SELECT id, email, name, dob, role AS "role: EmployeeRole", active,
+                personal_email, phone_number, scienta_slack_name, noa_slack_name,
+                dob_visibility AS "dob_visibility: FieldVisibility",
+                personal_email_visibility AS "personal_email_visibility: FieldVisibility",
+                phone_number_visibility AS "phone_number_visibility: FieldVisibility",
+                birthday_announcement, birthday_calendar_year
+            FROM skjera.employee
+            WHERE active AND birthday_announcement AND dob IS NOT NULL"#
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(employees
+            .into_iter()
+            .filter(|e| e.dob.is_some_and(|dob| is_birthday(dob, today)))
+            .collect())
+    }
+
+    #[tracing::instrument]
+    async fn insert_birthday_announcement(
+        &self,
+        employee: EmployeeId,
+        date: Date,
+    ) -> Result<bool, Error> {
+        sqlx::query!(
+            "INSERT INTO skjera.birthday_announcement(employee, date) VALUES ($1, $2)
+             ON CONFLICT DO NOTHING",
+            employee.0,
+            date,
+        )
+        .execute(&self.pool)
+        .await
+        .map(|r| r.rows_affected() > 0)
+    }
+
+    #[tracing::instrument]
+    async fn delete_birthday_announcement(
+        &self,
+        employee: EmployeeId,
+        date: Date,
+    ) -> Result<u64, Error> {
+        sqlx::query!(
+            "DELETE FROM skjera.birthday_announcement WHERE employee=$1 AND date=$2",
+            employee.0,
+            date,
+        )
+        .execute(&self.pool)
+        .await
+        .map(|r| r.rows_affected())
+    }
+
+    #[tracing::instrument]
+    async fn birthday_conversations(&self) -> Result<Vec<BirthdayConversation>, Error> {
+        sqlx::query_as!(
+            BirthdayConversation,
+            r#"SELECT id, team, channel, ts, who, employee AS "employee: EmployeeId",
+                birthday_message, generate_interaction, send_interaction, send_dm_interaction, expires_at
+            FROM skjera.birthday_conversation"#
+        )
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    #[tracing::instrument]
+    async fn insert_birthday_conversation(
+        &self,
+        team: String,
+        channel: String,
+        ts: String,
+        who: String,
+        employee: Option<EmployeeId>,
+        generate_interaction: Uuid,
+        expires_at: OffsetDateTime,
+    ) -> Result<BirthdayConversation, Error> {
+        sqlx::query_as!(
+            BirthdayConversation,
+            r#"INSERT INTO skjera.birthday_conversation(team, channel, ts, who, employee, generate_interaction, expires_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            RETURNING id, team, channel, ts, who, employee AS "employee: EmployeeId",
+                birthday_message, generate_interaction, send_interaction, send_dm_interaction, expires_at"#,
+            team,
+            channel,
+            ts,
+            who,
+            employee.map(|e| e.0),
+            generate_interaction,
+            expires_at,
+        )
+        .fetch_one(&self.pool)
+        .await
+    }
+
+    #[tracing::instrument]
+    async fn update_birthday_conversation(
+        &self,
+        id: BirthdayConversationId,
+        birthday_message: Option<String>,
+        generate_interaction: Option<Uuid>,
+        send_interaction: Option<Uuid>,
+        send_dm_interaction: Option<Uuid>,
+        expires_at: OffsetDateTime,
+    ) -> Result<BirthdayConversation, Error> {
+        sqlx::query_as!(
+            BirthdayConversation,
+            r#"UPDATE skjera.birthday_conversation
+            SET birthday_message=$1,
+                generate_interaction=$2,
+                send_interaction=$3,
+                send_dm_interaction=$4,
+                expires_at=$5
+            WHERE id=$6
+            RETURNING id, team, channel, ts, who, employee AS "employee: EmployeeId",
+                birthday_message, generate_interaction, send_interaction, send_dm_interaction, expires_at"#,
+            birthday_message,
+            generate_interaction,
+            send_interaction,
+            send_dm_interaction,
+            expires_at,
+            id.0,
+        )
+        .fetch_one(&self.pool)
+        .await
+    }
+
+    #[tracing::instrument]
+    async fn delete_birthday_conversation(&self, id: BirthdayConversationId) -> Result<u64, Error> {
+        sqlx::query!("DELETE FROM skjera.birthday_conversation WHERE id=$1", id.0,)
+            .execute(&self.pool)
+            .await
+            .map(|r| r.rows_affected())
+    }
+
+    #[tracing::instrument]
+    async fn insert_birthday_greeting(
+        &self,
+        employee: Option<EmployeeId>,
+        who: String,
+        channel: String,
+        ts: String,
+        message: String,
+        sent_by: Option<String>,
+    ) -> Result<BirthdayGreetingId, Error> {
+        sqlx::query_scalar!(
+            r#"INSERT INTO skjera.birthday_greeting(employee, who, channel, ts, message, sent_by)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            RETURNING id AS "id: BirthdayGreetingId""#,
+            employee.map(|e| e.0),
+            who,
+            channel,
+            ts,
+            message,
+            sent_by,
+        )
+        .fetch_one(&self.pool)
+        .await
+    }
+}
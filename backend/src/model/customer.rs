@@ -0,0 +1,99 @@
+use crate::id_type;
+use crate::model::*;
+use async_trait::async_trait;
+use sqlx::*;
+
+id_type!(CustomerId);
+
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct Customer {
+    pub id: CustomerId,
+    pub name: String,
+}
+
+#[async_trait]
+pub(crate) trait CustomerDao {
+    async fn customers(&self) -> Result<Vec<Customer>, Error>;
+    async fn customer_by_id(&self, id: CustomerId) -> Result<Option<Customer>, Error>;
+    async fn customer_by_name(&self, name: &str) -> Result<Option<Customer>, Error>;
+    async fn insert_customer(&self, name: String) -> Result<Customer, Error>;
+    async fn update_customer(&self, customer: &Customer) -> Result<Customer, Error>;
+    async fn delete_customer(&self, id: CustomerId) -> Result<u64, Error>;
+}
+
+#[async_trait]
+impl CustomerDao for Dao {
+    #[tracing::instrument]
+    async fn customers(&self) -> Result<Vec<Customer>, Error> {
+        sqlx::query_as!(Customer, "SELECT * FROM skjera.customer ORDER BY name")
+            .fetch_all(&self.pool)
+            .await
+    }
+
+    #[tracing::instrument]
+    async fn customer_by_id(&self, id: CustomerId) -> Result<Option<Customer>, Error> {
+        sqlx::query_as!(Customer, "SELECT * FROM skjera.customer WHERE id=$1", id.0)
+            .fetch_optional(&self.pool)
+            .await
+    }
+
+    #[tracing::instrument]
+    async fn customer_by_name(&self, name: &str) -> Result<Option<Customer>, Error> {
+        sqlx::query_as!(
+            Customer,
+            "SELECT * FROM skjera.customer WHERE name=$1",
+            name
+        )
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    #[tracing::instrument]
+    async fn insert_customer(&self, name: String) -> Result<Customer, Error> {
+        let mut tx = self.pool.begin().await?;
+
+        let customer = sqlx::query_as!(
+            Customer,
+            "INSERT INTO skjera.customer (name) VALUES($1) RETURNING *",
+            name
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+
+        news::insert_news_item(
+            &mut tx,
+            NewsKind::CustomerAdded,
+            format!("New customer: {}", customer.name),
+            format!("{} is our newest customer.", customer.name),
+            None,
+            Some(customer.id),
+            None,
+        )
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(customer)
+    }
+
+    #[tracing::instrument]
+    async fn update_customer(&self, customer: &Customer) -> Result<Customer, Error> {
+        sqlx::query_as!(
+            Customer,
+            "UPDATE skjera.customer SET name=$1 WHERE id=$2 RETURNING *",
+            customer.name,
+            customer.id.0
+        )
+        .fetch_one(&self.pool)
+        .await
+    }
+
+    /// Deletes the customer. This fails if the customer still has assignments.
+    #[tracing::instrument]
+    async fn delete_customer(&self, id: CustomerId) -> Result<u64, Error> {
+        sqlx::query!("DELETE FROM skjera.customer WHERE id=$1", id.0)
+            .execute(&self.pool)
+            .await
+            .map(|r| r.rows_affected())
+    }
+}
@@ -0,0 +1,688 @@
+//! Import and export of the employee directory, the employees and their SoMe accounts.
+//!
+//! Imports are keyed by the employee's email, so the same file can be imported again. Empty
+//! fields keep the current value. The role, the active flag and the visibility settings are
+//! managed in skjera and are not part of the directory.
+
+use crate::model::*;
+use anyhow::{anyhow, Context};
+use serde::{Deserialize, Serialize};
+use sqlx::types::time::Date;
+use sqlx::PgConnection;
+use std::path::Path;
+use std::str::FromStr;
+use time::format_description;
+use tracing::info;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DirectoryFormat {
+    /// One row per SoMe account, the employee columns are repeated on each row.
+    Csv,
+    Json,
+}
+
+impl DirectoryFormat {
+    pub fn from_path(path: &Path) -> Option<DirectoryFormat> {
+        path.extension()
+            .and_then(|extension| extension.to_str())
+            .and_then(|extension| extension.to_lowercase().parse().ok())
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            DirectoryFormat::Csv => "csv",
+            DirectoryFormat::Json => "json",
+        }
+    }
+
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            DirectoryFormat::Csv => "text/csv; charset=utf-8",
+            DirectoryFormat::Json => "application/json",
+        }
+    }
+}
+
+impl FromStr for DirectoryFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "csv" => Ok(DirectoryFormat::Csv),
+            "json" => Ok(DirectoryFormat::Json),
+            _ => Err(format!("invalid format: {}, use csv or json", s)),
+        }
+    }
+}
+
+/// An employee as it is written to and read from JSON.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DirectoryEmployee {
+    pub email: String,
+    pub name: Option<String>,
+    /// As YYYY-MM-DD.
+    pub dob: Option<String>,
+    pub personal_email: Option<String>,
+    pub phone_number: Option<String>,
+    pub scienta_slack_name: Option<String>,
+    pub noa_slack_name: Option<String>,
+    #[serde(default)]
+    pub some_accounts: Vec<DirectoryAccount>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DirectoryAccount {
+    pub network: String,
+    pub network_instance: Option<String>,
+    pub subject: Option<String>,
+    pub name: Option<String>,
+    pub nick: Option<String>,
+    pub url: Option<String>,
+    pub avatar: Option<String>,
+    pub authenticated: Option<bool>,
+}
+
+/// A CSV row, the account columns are empty for employees without accounts.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+struct CsvRow {
+    email: String,
+    name: Option<String>,
+    dob: Option<String>,
+    personal_email: Option<String>,
+    phone_number: Option<String>,
+    scienta_slack_name: Option<String>,
+    noa_slack_name: Option<String>,
+    network: Option<String>,
+    network_instance: Option<String>,
+    subject: Option<String>,
+    account_name: Option<String>,
+    nick: Option<String>,
+    url: Option<String>,
+    avatar: Option<String>,
+    authenticated: Option<bool>,
+}
+
+/// A problem with a single row. The row is the line in CSV files and the position in the list
+/// in JSON files, both starting at 1.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportError {
+    pub row: usize,
+    pub message: String,
+}
+
+/// A validated employee, ready to be imported.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportEmployee {
+    pub row: usize,
+    pub email: String,
+    /// Required for new employees.
+    pub name: Option<String>,
+    pub dob: Option<Date>,
+    pub personal_email: Option<String>,
+    pub phone_number: Option<String>,
+    pub scienta_slack_name: Option<String>,
+    pub noa_slack_name: Option<String>,
+    pub some_accounts: Vec<ImportAccount>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportAccount {
+    pub row: usize,
+    pub network: SomeNetwork,
+    pub network_instance: Option<String>,
+    pub subject: Option<String>,
+    pub name: Option<String>,
+    pub nick: Option<String>,
+    pub url: Option<String>,
+    pub avatar: Option<String>,
+    pub authenticated: Option<bool>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ImportReport {
+    pub created: usize,
+    /// Employees where the employee or any of the accounts changed.
+    pub updated: usize,
+    pub unchanged: usize,
+    pub errors: Vec<ImportError>,
+}
+
+/// Builds the directory from all employees and their accounts. Fields that are hidden from
+/// admins are left out.
+pub fn directory_employees(
+    employees: Vec<Employee>,
+    accounts: &[SomeAccount],
+) -> Vec<DirectoryEmployee> {
+    employees
+        .into_iter()
+        .map(|employee| {
+            let some_accounts = accounts
+                .iter()
+                .filter(|a| a.employee == employee.id)
+                .map(|a| DirectoryAccount {
+                    network: a.network.to_string(),
+                    network_instance: a.network_instance.clone(),
+                    subject: a.subject.clone(),
+                    name: a.name.clone(),
+                    nick: a.nick.clone(),
+                    url: a.url.clone(),
+                    avatar: a.avatar.clone(),
+                    authenticated: Some(a.authenticated),
+                })
+                .collect();
+
+            DirectoryEmployee {
+                email: employee.email,
+                name: Some(employee.name),
+                dob: employee
+                    .dob
+                    .filter(|_| employee.dob_visibility.is_visible(false, true))
+                    .map(|dob| dob.to_string()),
+                personal_email: employee
+                    .personal_email
+                    .filter(|_| employee.personal_email_visibility.is_visible(false, true)),
+                phone_number: employee
+                    .phone_number
+                    .filter(|_| employee.phone_number_visibility.is_visible(false, true)),
+                scienta_slack_name: employee.scienta_slack_name,
+                noa_slack_name: employee.noa_slack_name,
+                some_accounts,
+            }
+        })
+        .collect()
+}
+
+pub fn write_directory(
+    format: DirectoryFormat,
+    employees: &[DirectoryEmployee],
+) -> anyhow::Result<Vec<u8>> {
+    match format {
+        DirectoryFormat::Json => Ok(serde_json::to_vec_pretty(employees)?),
+        DirectoryFormat::Csv => {
+            let mut writer = csv::Writer::from_writer(Vec::new());
+
+            for e in employees {
+                let row = |account: Option<&DirectoryAccount>| CsvRow {
+                    email: e.email.clone(),
+                    name: e.name.clone(),
+                    dob: e.dob.clone(),
+                    personal_email: e.personal_email.clone(),
+                    phone_number: e.phone_number.clone(),
+                    scienta_slack_name: e.scienta_slack_name.clone(),
+                    noa_slack_name: e.noa_slack_name.clone(),
+                    network: account.map(|a| a.network.clone()),
+                    network_instance: account.and_then(|a| a.network_instance.clone()),
+                    subject: account.and_then(|a| a.subject.clone()),
+                    account_name: account.and_then(|a| a.name.clone()),
+                    nick: account.and_then(|a| a.nick.clone()),
+                    url: account.and_then(|a| a.url.clone()),
+                    avatar: account.and_then(|a| a.avatar.clone()),
+                    authenticated: account.and_then(|a| a.authenticated),
+                };
+
+                if e.some_accounts.is_empty() {
+                    writer.serialize(row(None))?;
+                }
+                for account in &e.some_accounts {
+                    writer.serialize(row(Some(account)))?;
+                }
+            }
+
+            Ok(writer.into_inner()?)
+        }
+    }
+}
+
+/// Parses and validates a directory file. Only errors that make the whole file unreadable are
+/// returned as an error, invalid rows are reported and left out.
+pub fn read_directory(
+    format: DirectoryFormat,
+    data: &[u8],
+) -> anyhow::Result<(Vec<ImportEmployee>, Vec<ImportError>)> {
+    let mut employees: Vec<ImportEmployee> = Vec::new();
+    let mut errors = Vec::new();
+
+    let mut add = |row: usize, employee: Result<ImportEmployee, String>| {
+        let result = employee.and_then(|employee| {
+            match employees
+                .iter_mut()
+                .find(|e| e.email.eq_ignore_ascii_case(&employee.email))
+            {
+                Some(existing) => merge_employee(existing, employee),
+                None => {
+                    employees.push(employee);
+                    Ok(())
+                }
+            }
+        });
+
+        if let Err(message) = result {
+            errors.push(ImportError { row, message });
+        }
+    };
+
+    match format {
+        DirectoryFormat::Json => {
+            let values: Vec<serde_json::Value> =
+                serde_json::from_slice(data).context("expected a list of employees")?;
+
+            for (index, value) in values.into_iter().enumerate() {
+                let row = index + 1;
+
+                let employee = serde_json::from_value::<DirectoryEmployee>(value)
+                    .map_err(|e| e.to_string())
+                    .and_then(|e| validate_employee(row, e));
+
+                add(row, employee);
+            }
+        }
+        DirectoryFormat::Csv => {
+            let mut reader = csv::Reader::from_reader(data);
+
+            let headers = reader.headers()?.clone();
+            if !headers.iter().any(|header| header == "email") {
+                return Err(anyhow!("the email column is missing"));
+            }
+
+            for record in reader.records() {
+                // Rows with the wrong number of columns make the rest of the file unreliable
+                let record = record?;
+                let row = record
+                    .position()
+                    .map(|p| p.line() as usize)
+                    .unwrap_or_default();
+
+                let employee =
+                    record
+                        .deserialize::<CsvRow>(Some(&headers))
+                        .map_err(|e| match e.kind() {
+                            csv::ErrorKind::Deserialize { err, .. } => match err.field() {
+                                Some(field) => format!(
+                                    "{}: {}",
+                                    headers.get(field as usize).unwrap_or_default(),
+                                    err.kind()
+                                ),
+                                None => err.kind().to_string(),
+                            },
+                            _ => e.to_string(),
+                        });
+
+                let employee = employee.and_then(|record| {
+                    let account = record.network.map(|network| DirectoryAccount {
+                        network,
+                        network_instance: record.network_instance,
+                        subject: record.subject,
+                        name: record.account_name,
+                        nick: record.nick,
+                        url: record.url,
+                        avatar: record.avatar,
+                        authenticated: record.authenticated,
+                    });
+
+                    validate_employee(
+                        row,
+                        DirectoryEmployee {
+                            email: record.email,
+                            name: record.name,
+                            dob: record.dob,
+                            personal_email: record.personal_email,
+                            phone_number: record.phone_number,
+                            scienta_slack_name: record.scienta_slack_name,
+                            noa_slack_name: record.noa_slack_name,
+                            some_accounts: account.into_iter().collect(),
+                        },
+                    )
+                });
+
+                add(row, employee);
+            }
+        }
+    }
+
+    Ok((employees, errors))
+}
+
+/// Everything in the row has to be valid, including the accounts.
+fn validate_employee(row: usize, e: DirectoryEmployee) -> Result<ImportEmployee, String> {
+    let field = |name: &str, value: Option<String>, parse: fn(&str) -> Result<String, String>| {
+        value
+            .map(|value| value.trim().to_string())
+            .filter(|value| !value.is_empty())
+            .map(|value| parse(&value).map_err(|e| format!("{} {}", name, e)))
+            .transpose()
+    };
+
+    let email = parse_email(&e.email).map_err(|e| format!("email {}", e))?;
+
+    let dob = match e.dob.as_deref().map(str::trim).filter(|s| !s.is_empty()) {
+        Some(dob) => {
+            let f = format_description::parse("[year]-[month]-[day]").map_err(|e| e.to_string())?;
+
+            Some(
+                Date::parse(dob, &f)
+                    .map_err(|_| format!("dob {} is not a YYYY-MM-DD date", dob))?,
+            )
+        }
+        None => None,
+    };
+
+    let some_accounts = e
+        .some_accounts
+        .into_iter()
+        .map(|account| validate_account(row, account))
+        .collect::<Result<_, _>>()?;
+
+    Ok(ImportEmployee {
+        row,
+        email,
+        name: field("name", e.name, |s| Ok(s.to_string()))?,
+        dob,
+        personal_email: field("personal_email", e.personal_email, parse_email)?,
+        phone_number: field("phone_number", e.phone_number, parse_phone_number)?,
+        scienta_slack_name: field("scienta_slack_name", e.scienta_slack_name, parse_slack_name)?,
+        noa_slack_name: field("noa_slack_name", e.noa_slack_name, parse_slack_name)?,
+        some_accounts,
+    })
+}
+
+fn validate_account(row: usize, a: DirectoryAccount) -> Result<ImportAccount, String> {
+    let network = SomeNetwork::from_str(a.network.trim())
+        .map_err(|_| format!("unknown network: {}", a.network))?;
+    let definition = network.definition();
+
+    let non_empty = |value: Option<String>| {
+        value
+            .map(|value| value.trim().to_string())
+            .filter(|value| !value.is_empty())
+    };
+
+    let network_instance = non_empty(a.network_instance);
+    if network_instance.is_some() && !definition.allows_instances {
+        return Err(format!(
+            "{} accounts can't have a network_instance",
+            definition.name
+        ));
+    }
+
+    // The nicks on the other networks come from the networks themselves
+    let nick = match non_empty(a.nick) {
+        Some(nick) if definition.manual => Some(definition.parse_nick(&nick)?),
+        nick => nick,
+    };
+
+    let subject = non_empty(a.subject);
+    if nick.is_none() && subject.is_none() {
+        return Err(format!(
+            "{} account needs a nick or a subject",
+            definition.name
+        ));
+    }
+
+    let url = non_empty(a.url).or_else(|| {
+        nick.as_deref()
+            .filter(|_| definition.manual)
+            .and_then(|nick| definition.profile_url(nick))
+    });
+
+    Ok(ImportAccount {
+        row,
+        network,
+        network_instance,
+        subject,
+        name: non_empty(a.name),
+        nick,
+        url,
+        avatar: non_empty(a.avatar),
+        authenticated: a.authenticated,
+    })
+}
+
+/// Rows with the same email are combined, they can't disagree on the employee's fields.
+fn merge_employee(existing: &mut ImportEmployee, other: ImportEmployee) -> Result<(), String> {
+    fn merge<T: PartialEq>(
+        name: &str,
+        value: &mut Option<T>,
+        other: Option<T>,
+    ) -> Result<(), String> {
+        match (value.is_some(), other) {
+            (true, Some(other)) if value.as_ref() != Some(&other) => Err(format!(
+                "{} doesn't match the earlier row with the same email",
+                name
+            )),
+            (false, Some(other)) => {
+                *value = Some(other);
+                Ok(())
+            }
+            _ => Ok(()),
+        }
+    }
+
+    let mut merged = existing.clone();
+    merge("name", &mut merged.name, other.name)?;
+    merge("dob", &mut merged.dob, other.dob)?;
+    merge(
+        "personal_email",
+        &mut merged.personal_email,
+        other.personal_email,
+    )?;
+    merge("phone_number", &mut merged.phone_number, other.phone_number)?;
+    merge(
+        "scienta_slack_name",
+        &mut merged.scienta_slack_name,
+        other.scienta_slack_name,
+    )?;
+    merge(
+        "noa_slack_name",
+        &mut merged.noa_slack_name,
+        other.noa_slack_name,
+    )?;
+    merged.some_accounts.extend(other.some_accounts);
+
+    *existing = merged;
+    Ok(())
+}
+
+/// Creates or updates the employees and their accounts. Each employee is imported in a
+/// transaction, an employee with a row that fails is reported in the returned report and left as
+/// it was, the rest are imported.
+pub async fn import_directory(
+    dao: &Dao,
+    employees: Vec<ImportEmployee>,
+    errors: Vec<ImportError>,
+) -> Result<ImportReport, sqlx::Error> {
+    let existing = dao.all_employees().await?;
+
+    let mut report = ImportReport {
+        errors,
+        ..Default::default()
+    };
+
+    for import in employees {
+        let existing = existing
+            .iter()
+            .find(|e| e.email.eq_ignore_ascii_case(&import.email))
+            .cloned();
+
+        let mut tx = dao.pool.begin().await?;
+
+        match import_employee(&mut tx, existing, import).await {
+            Ok(outcome) => {
+                tx.commit().await?;
+
+                match outcome {
+                    Imported::Created => report.created += 1,
+                    Imported::Updated => report.updated += 1,
+                    Imported::Unchanged => report.unchanged += 1,
+                }
+            }
+            Err(e) => report.errors.push(e),
+        }
+    }
+
+    report.errors.sort_by_key(|e| e.row);
+
+    Ok(report)
+}
+
+enum Imported {
+    Created,
+    Updated,
+    Unchanged,
+}
+
+/// Returns the error of the first row that fails, the transaction is rolled back then.
+async fn import_employee(
+    conn: &mut PgConnection,
+    existing: Option<Employee>,
+    import: ImportEmployee,
+) -> Result<Imported, ImportError> {
+    let row = import.row;
+    let import_error = |row: usize, e: anyhow::Error| ImportError {
+        row,
+        message: format!("{:#}", e),
+    };
+
+    let (mut employee, created) = match existing {
+        Some(employee) => (employee, false),
+        None => {
+            let name = import
+                .name
+                .clone()
+                .ok_or_else(|| anyhow!("name is required for new employees"))
+                .map_err(|e| import_error(row, e))?;
+
+            let employee = employee::insert_employee(conn, import.email.clone(), name)
+                .await
+                .map_err(|e| import_error(row, e.into()))?;
+            info!(employee = employee.id.0, "Imported new employee");
+
+            (employee, true)
+        }
+    };
+
+    fn set<T: PartialEq>(field: &mut T, value: Option<T>) -> bool {
+        match value {
+            Some(value) if *field != value => {
+                *field = value;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    let mut changed = set(&mut employee.name, import.name);
+    changed |= set(
+        &mut employee.scienta_slack_name,
+        import.scienta_slack_name.map(Some),
+    );
+    changed |= set(
+        &mut employee.noa_slack_name,
+        import.noa_slack_name.map(Some),
+    );
+
+    // Like on the admin page, fields that are hidden from admins can't be changed
+    if employee.dob_visibility.is_visible(false, true) {
+        changed |= set(&mut employee.dob, import.dob.map(Some));
+    }
+    if employee.personal_email_visibility.is_visible(false, true) {
+        changed |= set(
+            &mut employee.personal_email,
+            import.personal_email.map(Some),
+        );
+    }
+    if employee.phone_number_visibility.is_visible(false, true) {
+        changed |= set(&mut employee.phone_number, import.phone_number.map(Some));
+    }
+
+    if changed {
+        employee::update_employee(conn, &employee)
+            .await
+            .map_err(|e| import_error(row, e.into()))?;
+    }
+
+    for account in import.some_accounts {
+        let row = account.row;
+
+        changed |= import_account(conn, employee.id, account)
+            .await
+            .map_err(|e| import_error(row, e))?;
+    }
+
+    Ok(match (created, changed) {
+        (true, _) => Imported::Created,
+        (false, true) => Imported::Updated,
+        (false, false) => Imported::Unchanged,
+    })
+}
+
+/// Returns true if the account was created or changed.
+///
+/// Only the employee can show that an account is theirs, so an import can't mark an account as
+/// authenticated, it can only take that away.
+async fn import_account(
+    conn: &mut PgConnection,
+    employee: EmployeeId,
+    import: ImportAccount,
+) -> anyhow::Result<bool> {
+    let existing = employee::some_account_for_network(
+        conn,
+        employee,
+        import.network,
+        import.network_instance.clone(),
+    )
+    .await?;
+
+    let Some(account) = existing else {
+        employee::add_some_account(
+            conn,
+            employee,
+            import.network,
+            import.network_instance,
+            false,
+            None,
+            import.subject,
+            import.name,
+            import.nick,
+            import.url,
+            import.avatar,
+        )
+        .await?;
+
+        return Ok(true);
+    };
+
+    let authenticated = account.authenticated && import.authenticated.unwrap_or(true);
+    let subject = import.subject.or(account.subject.clone());
+    let name = import.name.or(account.name.clone());
+    let nick = import.nick.or(account.nick.clone());
+    let url = import.url.or(account.url.clone());
+    let avatar = import.avatar.or(account.avatar.clone());
+
+    let unchanged = authenticated == account.authenticated
+        && subject == account.subject
+        && name == account.name
+        && nick == account.nick
+        && url == account.url
+        && avatar == account.avatar;
+
+    if unchanged {
+        return Ok(false);
+    }
+
+    employee::update_some_account(
+        conn,
+        account.id,
+        authenticated,
+        account.network_avatar,
+        subject,
+        name,
+        nick,
+        url,
+        avatar,
+    )
+    .await?;
+
+    Ok(true)
+}
@@ -1,26 +1,38 @@
 use crate::id_type;
 use crate::model::*;
 use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
 use sqlx::types::time::Date;
 use sqlx::*;
+use std::str::FromStr;
 
 id_type!(EmployeeId);
 
-#[derive(Debug)]
-pub struct Dao {
-    pool: Pool<Postgres>,
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, sqlx::Type, Serialize, Deserialize)]
+#[sqlx(type_name = "varchar", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum EmployeeRole {
+    Employee,
+    /// Can edit everyone's data, manage customers and approve sign-ups.
+    Admin,
 }
 
-impl Dao {
-    pub fn new(pool: Pool<Postgres>) -> Self {
-        Self { pool }
-    }
+/// Who can see an optional profile field. The employees can always see their own fields.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, sqlx::Type, Serialize, Deserialize)]
+#[sqlx(type_name = "varchar", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum FieldVisibility {
+    Everyone,
+    Admins,
+    Hidden,
 }
 
-impl Clone for Dao {
-    fn clone(&self) -> Self {
-        Self {
-            pool: self.pool.clone(),
+impl FieldVisibility {
+    pub fn is_visible(&self, is_self: bool, is_admin: bool) -> bool {
+        match self {
+            FieldVisibility::Everyone => true,
+            FieldVisibility::Admins => is_self || is_admin,
+            FieldVisibility::Hidden => is_self,
         }
     }
 }
@@ -31,16 +43,192 @@ pub struct Employee {
     pub email: String,
     pub name: String,
     pub dob: Option<Date>,
+    pub role: EmployeeRole,
+    /// Deactivated employees can't log in.
+    pub active: bool,
+    pub personal_email: Option<String>,
+    /// In E.164 format, see [parse_phone_number].
+    pub phone_number: Option<String>,
+    pub scienta_slack_name: Option<String>,
+    pub noa_slack_name: Option<String>,
+    pub dob_visibility: FieldVisibility,
+    pub personal_email_visibility: FieldVisibility,
+    pub phone_number_visibility: FieldVisibility,
+    /// False if the employee has opted out of public birthday announcements.
+    pub birthday_announcement: bool,
+    /// False if only the day and month are shown in the birthday calendar.
+    pub birthday_calendar_year: bool,
+}
+
+impl Employee {
+    /// Clears the fields that the viewer isn't allowed to see. Everything that is shown to other
+    /// employees has to go through this.
+    pub fn redacted(mut self, viewer: EmployeeId, viewer_role: EmployeeRole) -> Employee {
+        let is_self = self.id == viewer;
+        let is_admin = viewer_role == EmployeeRole::Admin;
+
+        if !self.dob_visibility.is_visible(is_self, is_admin) {
+            self.dob = None;
+        }
+        if !self.personal_email_visibility.is_visible(is_self, is_admin) {
+            self.personal_email = None;
+        }
+        if !self.phone_number_visibility.is_visible(is_self, is_admin) {
+            self.phone_number = None;
+        }
+
+        self
+    }
+}
+
+/// Normalizes a phone number to E.164, "+47 900 00 000" becomes "+4790000000". A leading "00"
+/// is accepted instead of the "+".
+pub fn parse_phone_number(s: &str) -> Result<String, String> {
+    let s: String = s
+        .chars()
+        .filter(|c| !matches!(c, ' ' | '-' | '.' | '(' | ')'))
+        .collect();
+
+    let digits = match (s.strip_prefix('+'), s.strip_prefix("00")) {
+        (Some(digits), _) | (None, Some(digits)) => digits,
+        _ => return Err("must start with + and the country code".to_string()),
+    };
+
+    if !digits.chars().all(|c| c.is_ascii_digit()) {
+        return Err("can only contain digits".to_string());
+    }
+
+    if digits.starts_with('0') || !(2..=15).contains(&digits.len()) {
+        return Err("is not a valid international phone number".to_string());
+    }
+
+    Ok(format!("+{}", digits))
+}
+
+pub fn parse_email(s: &str) -> Result<String, String> {
+    let s = s.trim();
+
+    let valid = match s.split_once('@') {
+        Some((local, domain)) => {
+            !local.is_empty()
+                && !domain.contains('@')
+                && domain.contains('.')
+                && !domain.starts_with('.')
+                && !domain.ends_with('.')
+                && !s.chars().any(char::is_whitespace)
+        }
+        None => false,
+    };
+
+    match valid {
+        true => Ok(s.to_string()),
+        false => Err("is not a valid email address".to_string()),
+    }
+}
+
+/// Slack display names are at most 80 characters. The leading "@" that people tend to include is
+/// removed.
+pub fn parse_slack_name(s: &str) -> Result<String, String> {
+    let s = s.trim();
+    let s = s.strip_prefix('@').unwrap_or(s);
+
+    if s.is_empty() || s.chars().count() > 80 {
+        return Err("must be between 1 and 80 characters".to_string());
+    }
+
+    if s.chars().any(|c| c == '@' || c.is_control()) {
+        return Err("can't contain @ or control characters".to_string());
+    }
+
+    Ok(s.to_string())
+}
+
+/// How to sort the results of [EmployeeDao::search_employees].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+pub enum EmployeeSort {
+    #[default]
+    #[serde(rename = "name")]
+    Name,
+    #[serde(rename = "-name")]
+    NameDesc,
+    #[serde(rename = "email")]
+    Email,
+    #[serde(rename = "-email")]
+    EmailDesc,
+}
+
+impl EmployeeSort {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EmployeeSort::Name => "name",
+            EmployeeSort::NameDesc => "-name",
+            EmployeeSort::Email => "email",
+            EmployeeSort::EmailDesc => "-email",
+        }
+    }
+}
+
+impl FromStr for EmployeeSort {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "name" => Ok(EmployeeSort::Name),
+            "-name" => Ok(EmployeeSort::NameDesc),
+            "email" => Ok(EmployeeSort::Email),
+            "-email" => Ok(EmployeeSort::EmailDesc),
+            _ => Err(format!("invalid sort: {}", s)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct EmployeeQuery {
+    /// Matches a part of the name, the email or the nick of any SoMe account.
+    pub text: Option<String>,
+    /// Only employees with an account on this network.
+    pub network: Option<SomeNetwork>,
+    /// Only employees that are currently assigned to this customer.
+    pub customer: Option<CustomerId>,
+    pub include_inactive: bool,
+    pub sort: EmployeeSort,
+    pub limit: i64,
+    pub offset: i64,
+}
+
+/// Turns the search text into a pattern that matches it anywhere in the value.
+fn contains_pattern(text: &str) -> String {
+    let escaped = text
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_");
+
+    format!("%{}%", escaped)
 }
 
 #[async_trait]
 pub(crate) trait EmployeeDao {
-    async fn employees(&self) -> Result<Vec<Employee>, Error>;
+    /// Returns a page of the employees matching the query, and the total number of matches. The
+    /// total is 0 for a page past the last one.
+    async fn search_employees(
+        &self,
+        query: &EmployeeQuery,
+        today: Date,
+    ) -> Result<(Vec<Employee>, i64), Error>;
+    /// Includes the deactivated employees too.
+    async fn all_employees(&self) -> Result<Vec<Employee>, Error>;
     async fn employee_by_id(&self, id: EmployeeId) -> Result<Option<Employee>, Error>;
     async fn employee_by_email(&self, email: String) -> Result<Option<Employee>, Error>;
     async fn employee_by_name(&self, username: String) -> Result<Option<Employee>, Error>;
     async fn insert_employee(&self, email: String, name: String) -> Result<Employee, Error>;
+    /// Updates everything but the role and the active flag.
     async fn update(&self, employee: &Employee) -> Result<Employee, Error>;
+    async fn update_employee_role(&self, id: EmployeeId, role: EmployeeRole) -> Result<u64, Error>;
+    /// Marks the employee as gone, revokes their sessions and API tokens and says goodbye in the
+    /// news feed.
+    async fn deactivate_employee(&self, id: EmployeeId) -> Result<u64, Error>;
+    async fn reactivate_employee(&self, id: EmployeeId) -> Result<u64, Error>;
+    #[allow(clippy::too_many_arguments)]
     async fn add_some_account(
         &self,
         employee: EmployeeId,
@@ -60,13 +248,22 @@ pub(crate) trait EmployeeDao {
         employee_id: EmployeeId,
     ) -> Result<Vec<SomeAccount>, Error>;
 
+    async fn some_accounts_by_employees(
+        &self,
+        employee_ids: &[EmployeeId],
+    ) -> Result<Vec<SomeAccount>, Error>;
+
+    /// The networks that anyone has an account on.
+    async fn some_networks(&self) -> Result<Vec<SomeNetwork>, Error>;
+
     async fn some_account_for_network(
         &self,
         employee_id: EmployeeId,
-        network: String,
+        network: SomeNetwork,
         network_instance: Option<String>,
     ) -> Result<Option<SomeAccount>, Error>;
 
+    #[allow(clippy::too_many_arguments)]
     async fn update_some_account(
         &self,
         id: SomeAccountId,
@@ -79,6 +276,19 @@ pub(crate) trait EmployeeDao {
         avatar: Option<String>,
     ) -> Result<SomeAccount, Error>;
 
+    /// The accounts on a network, optionally only on one instance of it.
+    async fn some_accounts_by_network(
+        &self,
+        network: SomeNetwork,
+        network_instance: Option<String>,
+    ) -> Result<Vec<SomeAccount>, Error>;
+
+    async fn set_some_account_deactivated(
+        &self,
+        id: SomeAccountId,
+        deactivated: bool,
+    ) -> Result<u64, Error>;
+
     async fn delete_some_account(
         &self,
         id: SomeAccountId,
@@ -87,31 +297,128 @@ pub(crate) trait EmployeeDao {
 }
 
 #[async_trait]
-impl EmployeeDao for Dao
-{
+impl EmployeeDao for Dao {
     // pub(crate) fn new(pool: Pool<Db>) -> EmployeeDao<Db> {
     //     EmployeeDao { pool }
     // }
 
     #[tracing::instrument]
-    async fn employees(&self) -> Result<Vec<Employee>, Error> {
-        sqlx::query_as!(Employee, "SELECT * FROM skjera.employee")
-            .fetch_all(&self.pool)
-            .await
+    async fn search_employees(
+        &self,
+        query: &EmployeeQuery,
+        today: Date,
+    ) -> Result<(Vec<Employee>, i64), Error> {
+        let pattern = query.text.as_deref().map(contains_pattern);
+        let network = query.network.map(|n| n.to_string());
+        let customer = query.customer.map(|c| c.0);
+
+        let rows = sqlx::query!(
+            r#"SELECT id, email, name, dob, role AS "role: EmployeeRole", active,
+                personal_email, phone_number, scienta_slack_name, noa_slack_name,
+                dob_visibility AS "dob_visibility: FieldVisibility",
+                personal_email_visibility AS "personal_email_visibility: FieldVisibility",
+                phone_number_visibility AS "phone_number_visibility: FieldVisibility",
+                birthday_announcement, birthday_calendar_year,
+                COUNT(*) OVER () AS "total!"
+            FROM skjera.employee e
+            WHERE (e.active OR $1)
+              AND ($2::TEXT IS NULL
+                OR e.name ILIKE $2 OR e.email ILIKE $2
+                OR EXISTS(SELECT 1 FROM skjera.some_account sa WHERE sa.employee=e.id AND sa.nick ILIKE $2))
+              AND ($3::TEXT IS NULL
+                OR EXISTS(SELECT 1 FROM skjera.some_account sa WHERE sa.employee=e.id AND sa.network=$3))
+              AND ($4::BIGINT IS NULL
+                OR EXISTS(SELECT 1 FROM skjera.assignment a WHERE a.employee=e.id AND a.customer=$4
+                    AND a.start_date <= $5 AND (a.end_date IS NULL OR a.end_date >= $5)))
+            ORDER BY
+                CASE WHEN $6 = 'name' THEN LOWER(e.name) END,
+                CASE WHEN $6 = '-name' THEN LOWER(e.name) END DESC,
+                CASE WHEN $6 = 'email' THEN e.email END,
+                CASE WHEN $6 = '-email' THEN e.email END DESC,
+                e.id
+            LIMIT $7 OFFSET $8"#,
+            query.include_inactive,
+            pattern,
+            network,
+            customer,
+            today,
+            query.sort.as_str(),
+            query.limit,
+            query.offset,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        let total = rows.first().map(|row| row.total).unwrap_or(0);
+
+        let employees = rows
+            .into_iter()
+            .map(|row| Employee {
+                id: EmployeeId(row.id),
+                email: row.email,
+                name: row.name,
+                dob: row.dob,
+                role: row.role,
+                active: row.active,
+                personal_email: row.personal_email,
+                phone_number: row.phone_number,
+                scienta_slack_name: row.scienta_slack_name,
+                noa_slack_name: row.noa_slack_name,
+                dob_visibility: row.dob_visibility,
+                personal_email_visibility: row.personal_email_visibility,
+                phone_number_visibility: row.phone_number_visibility,
+                birthday_announcement: row.birthday_announcement,
+                birthday_calendar_year: row.birthday_calendar_year,
+            })
+            .collect();
+
+        Ok((employees, total))
+    }
+
+    #[tracing::instrument]
+    async fn all_employees(&self) -> Result<Vec<Employee>, Error> {
+        sqlx::query_as!(
+            Employee,
+            r#"SELECT id, email, name, dob, role AS "role: EmployeeRole", active,
+                personal_email, phone_number, scienta_slack_name, noa_slack_name,
+                dob_visibility AS "dob_visibility: FieldVisibility",
+                personal_email_visibility AS "personal_email_visibility: FieldVisibility",
+                phone_number_visibility AS "phone_number_visibility: FieldVisibility",
+                birthday_announcement, birthday_calendar_year
+            FROM skjera.employee"#
+        )
+        .fetch_all(&self.pool)
+        .await
     }
 
     #[tracing::instrument]
     async fn employee_by_id(&self, id: EmployeeId) -> Result<Option<Employee>, Error> {
-        sqlx::query_as!(Employee, "SELECT * FROM skjera.employee WHERE id=$1", id.0)
-            .fetch_optional(&self.pool)
-            .await
+        sqlx::query_as!(
+            Employee,
+            r#"SELECT id, email, name, dob, role AS "role: EmployeeRole", active,
+                personal_email, phone_number, scienta_slack_name, noa_slack_name,
+                dob_visibility AS "dob_visibility: FieldVisibility",
+                personal_email_visibility AS "personal_email_visibility: FieldVisibility",
+                phone_number_visibility AS "phone_number_visibility: FieldVisibility",
+                birthday_announcement, birthday_calendar_year
+            FROM skjera.employee WHERE id=$1"#,
+            id.0
+        )
+        .fetch_optional(&self.pool)
+        .await
     }
 
     #[tracing::instrument]
     async fn employee_by_email(&self, email: String) -> Result<Option<Employee>, Error> {
         sqlx::query_as!(
             Employee,
-            "SELECT * FROM skjera.employee WHERE email=$1",
+            r#"SELECT id, email, name, dob, role AS "role: EmployeeRole", active,
+                personal_email, phone_number, scienta_slack_name, noa_slack_name,
+                dob_visibility AS "dob_visibility: FieldVisibility",
+                personal_email_visibility AS "personal_email_visibility: FieldVisibility",
+                phone_number_visibility AS "phone_number_visibility: FieldVisibility",
+                birthday_announcement, birthday_calendar_year
+            FROM skjera.employee WHERE email=$1"#,
             email
         )
         .fetch_optional(&self.pool)
@@ -122,7 +429,13 @@ impl EmployeeDao for Dao
     async fn employee_by_name(&self, name: String) -> Result<Option<Employee>, Error> {
         sqlx::query_as!(
             Employee,
-            "SELECT * FROM skjera.employee WHERE name=$1",
+            r#"SELECT id, email, name, dob, role AS "role: EmployeeRole", active,
+                personal_email, phone_number, scienta_slack_name, noa_slack_name,
+                dob_visibility AS "dob_visibility: FieldVisibility",
+                personal_email_visibility AS "personal_email_visibility: FieldVisibility",
+                phone_number_visibility AS "phone_number_visibility: FieldVisibility",
+                birthday_announcement, birthday_calendar_year
+            FROM skjera.employee WHERE name=$1"#,
             name
         )
         .fetch_optional(&self.pool)
@@ -131,27 +444,76 @@ impl EmployeeDao for Dao
 
     #[tracing::instrument]
     async fn insert_employee(&self, email: String, name: String) -> Result<Employee, Error> {
-        sqlx::query_as!(
-            Employee,
-            "INSERT INTO skjera.employee (email, name) VALUES($1, $2) RETURNING *",
-            email,
-            name
-        )
-        .fetch_one(&self.pool)
-        .await
+        let mut tx = self.pool.begin().await?;
+
+        let employee = insert_employee(&mut tx, email, name).await?;
+
+        tx.commit().await?;
+
+        Ok(employee)
     }
 
     #[tracing::instrument]
     async fn update(&self, employee: &Employee) -> Result<Employee, Error> {
-        sqlx::query_as!(
-            Employee,
-            "UPDATE skjera.employee SET dob=$1 WHERE id=$2
-                RETURNING *",
-            employee.dob,
-            employee.id.0,
+        update_employee(&mut *self.pool.acquire().await?, employee).await
+    }
+
+    #[tracing::instrument]
+    async fn update_employee_role(&self, id: EmployeeId, role: EmployeeRole) -> Result<u64, Error> {
+        sqlx::query!(
+            "UPDATE skjera.employee SET role=$1 WHERE id=$2",
+            role as EmployeeRole,
+            id.0,
         )
-        .fetch_one(&self.pool)
+        .execute(&self.pool)
         .await
+        .map(|r| r.rows_affected())
+    }
+
+    #[tracing::instrument]
+    async fn deactivate_employee(&self, id: EmployeeId) -> Result<u64, Error> {
+        let mut tx = self.pool.begin().await?;
+
+        let Some(name) = sqlx::query_scalar!(
+            "UPDATE skjera.employee SET active=FALSE WHERE id=$1 AND active RETURNING name",
+            id.0
+        )
+        .fetch_optional(&mut *tx)
+        .await?
+        else {
+            return Ok(0);
+        };
+
+        sqlx::query!("DELETE FROM skjera.api_token WHERE employee=$1", id.0)
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query!("DELETE FROM skjera.session WHERE employee=$1", id.0)
+            .execute(&mut *tx)
+            .await?;
+
+        news::insert_news_item(
+            &mut tx,
+            NewsKind::EmployeeLeft,
+            format!("Goodbye, {}!", name),
+            format!("{} has left us.", name),
+            Some(id),
+            None,
+            None,
+        )
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(1)
+    }
+
+    #[tracing::instrument]
+    async fn reactivate_employee(&self, id: EmployeeId) -> Result<u64, Error> {
+        sqlx::query!("UPDATE skjera.employee SET active=TRUE WHERE id=$1", id.0)
+            .execute(&self.pool)
+            .await
+            .map(|r| r.rows_affected())
     }
 
     #[tracing::instrument]
@@ -168,13 +530,10 @@ impl EmployeeDao for Dao
         url: Option<String>,
         avatar: Option<String>,
     ) -> Result<SomeAccount, Error> {
-        sqlx::query_as!(
-            SomeAccount,
-            "INSERT INTO skjera.some_account(employee, network, network_instance, authenticated, network_avatar, subject, name, nick, url, avatar)
-             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
-             RETURNING *",
-            employee.0,
-            network.0,
+        add_some_account(
+            &mut *self.pool.acquire().await?,
+            employee,
+            network,
             network_instance,
             authenticated,
             network_avatar,
@@ -184,8 +543,7 @@ impl EmployeeDao for Dao
             url,
             avatar,
         )
-            .fetch_one(&self.pool)
-            .await
+        .await
     }
 
     #[tracing::instrument]
@@ -195,28 +553,55 @@ impl EmployeeDao for Dao
     ) -> Result<Vec<SomeAccount>, Error> {
         sqlx::query_as!(
             SomeAccount,
-            "SELECT * FROM skjera.some_account WHERE employee=$1",
+            r#"SELECT id, employee, network AS "network: SomeNetwork", authenticated, network_instance,
+                network_avatar, subject, name, nick, url, avatar, deactivated
+            FROM skjera.some_account WHERE employee=$1"#,
             employee_id.0,
         )
         .fetch_all(&self.pool)
         .await
     }
 
+    #[tracing::instrument]
+    async fn some_networks(&self) -> Result<Vec<SomeNetwork>, Error> {
+        sqlx::query_scalar!(
+            r#"SELECT DISTINCT network AS "network: SomeNetwork" FROM skjera.some_account ORDER BY 1"#
+        )
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    #[tracing::instrument]
+    async fn some_accounts_by_employees(
+        &self,
+        employee_ids: &[EmployeeId],
+    ) -> Result<Vec<SomeAccount>, Error> {
+        let employee_ids: Vec<i64> = employee_ids.iter().map(|e| e.0).collect();
+
+        sqlx::query_as!(
+            SomeAccount,
+            r#"SELECT id, employee, network AS "network: SomeNetwork", authenticated, network_instance,
+                network_avatar, subject, name, nick, url, avatar, deactivated
+            FROM skjera.some_account WHERE employee = ANY ($1) ORDER BY id"#,
+            &employee_ids
+        )
+        .fetch_all(&self.pool)
+        .await
+    }
+
     #[tracing::instrument]
     async fn some_account_for_network(
         &self,
         employee_id: EmployeeId,
-        network: String,
+        network: SomeNetwork,
         network_instance: Option<String>,
     ) -> Result<Option<SomeAccount>, Error> {
-        sqlx::query_as!(
-            SomeAccount,
-            "SELECT * FROM skjera.some_account WHERE employee=$1 AND network=$2 AND ((network_instance IS NULL AND $3::TEXT IS NULL) OR (network_instance=$3::TEXT))",
-            employee_id.0,
+        some_account_for_network(
+            &mut *self.pool.acquire().await?,
+            employee_id,
             network,
             network_instance,
         )
-        .fetch_optional(&self.pool)
         .await
     }
 
@@ -232,19 +617,9 @@ impl EmployeeDao for Dao
         url: Option<String>,
         avatar: Option<String>,
     ) -> Result<SomeAccount, Error> {
-        sqlx::query_as!(
-            SomeAccount,
-            "UPDATE skjera.some_account
-            SET authenticated=$1,
-                network_avatar=$2,
-                subject=$3,
-                name=$4,
-                nick=$5,
-                url=$6,
-                avatar=$7
-            WHERE id = $8
-            RETURNING *;
-            ",
+        update_some_account(
+            &mut *self.pool.acquire().await?,
+            id,
             authenticated,
             network_avatar,
             subject,
@@ -252,10 +627,44 @@ impl EmployeeDao for Dao
             nick,
             url,
             avatar,
+        )
+        .await
+    }
+
+    #[tracing::instrument]
+    async fn some_accounts_by_network(
+        &self,
+        network: SomeNetwork,
+        network_instance: Option<String>,
+    ) -> Result<Vec<SomeAccount>, Error> {
+        sqlx::query_as!(
+            SomeAccount,
+            r#"SELECT id, employee, network AS "network: SomeNetwork", authenticated, network_instance,
+                network_avatar, subject, name, nick, url, avatar, deactivated
+            FROM skjera.some_account
+            WHERE network=$1 AND ($2::TEXT IS NULL OR network_instance=$2::TEXT)
+            ORDER BY id"#,
+            network as SomeNetwork,
+            network_instance,
+        )
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    #[tracing::instrument]
+    async fn set_some_account_deactivated(
+        &self,
+        id: SomeAccountId,
+        deactivated: bool,
+    ) -> Result<u64, Error> {
+        sqlx::query!(
+            "UPDATE skjera.some_account SET deactivated=$1 WHERE id=$2",
+            deactivated,
             id.0,
         )
-        .fetch_one(&self.pool)
+        .execute(&self.pool)
         .await
+        .map(|r| r.rows_affected())
     }
 
     #[tracing::instrument]
@@ -274,3 +683,174 @@ impl EmployeeDao for Dao
         .map(|r| r.rows_affected())
     }
 }
+
+// The queries that are also used in transactions
+
+pub(super) async fn insert_employee(
+    conn: &mut PgConnection,
+    email: String,
+    name: String,
+) -> Result<Employee, Error> {
+    let employee = sqlx::query_as!(
+        Employee,
+        r#"INSERT INTO skjera.employee (email, name) VALUES($1, $2)
+        RETURNING id, email, name, dob, role AS "role: EmployeeRole", active,
+            personal_email, phone_number, scienta_slack_name, noa_slack_name,
+            dob_visibility AS "dob_visibility: FieldVisibility",
+            personal_email_visibility AS "personal_email_visibility: FieldVisibility",
+            phone_number_visibility AS "phone_number_visibility: FieldVisibility",
+            birthday_announcement, birthday_calendar_year"#,
+        email,
+        name
+    )
+    .fetch_one(&mut *conn)
+    .await?;
+
+    news::insert_news_item(
+        conn,
+        NewsKind::EmployeeJoined,
+        format!("Welcome, {}!", employee.name),
+        format!("{} has joined us.", employee.name),
+        Some(employee.id),
+        None,
+        None,
+    )
+    .await?;
+
+    Ok(employee)
+}
+
+pub(super) async fn update_employee(
+    conn: &mut PgConnection,
+    employee: &Employee,
+) -> Result<Employee, Error> {
+    sqlx::query_as!(
+        Employee,
+        r#"UPDATE skjera.employee
+        SET name=$1, email=$2, dob=$3, personal_email=$4, phone_number=$5,
+            scienta_slack_name=$6, noa_slack_name=$7,
+            dob_visibility=$8, personal_email_visibility=$9, phone_number_visibility=$10,
+            birthday_announcement=$11, birthday_calendar_year=$12
+        WHERE id=$13
+        RETURNING id, email, name, dob, role AS "role: EmployeeRole", active,
+            personal_email, phone_number, scienta_slack_name, noa_slack_name,
+            dob_visibility AS "dob_visibility: FieldVisibility",
+            personal_email_visibility AS "personal_email_visibility: FieldVisibility",
+            phone_number_visibility AS "phone_number_visibility: FieldVisibility",
+            birthday_announcement, birthday_calendar_year"#,
+        employee.name,
+        employee.email,
+        employee.dob,
+        employee.personal_email,
+        employee.phone_number,
+        employee.scienta_slack_name,
+        employee.noa_slack_name,
+        employee.dob_visibility as FieldVisibility,
+        employee.personal_email_visibility as FieldVisibility,
+        employee.phone_number_visibility as FieldVisibility,
+        employee.birthday_announcement,
+        employee.birthday_calendar_year,
+        employee.id.0,
+    )
+    .fetch_one(&mut *conn)
+    .await
+}
+
+#[allow(clippy::too_many_arguments)]
+pub(super) async fn add_some_account(
+    conn: &mut PgConnection,
+    employee: EmployeeId,
+    network: SomeNetwork,
+    network_instance: Option<String>,
+    authenticated: bool,
+    network_avatar: Option<String>,
+    subject: Option<String>,
+    name: Option<String>,
+    nick: Option<String>,
+    url: Option<String>,
+    avatar: Option<String>,
+) -> Result<SomeAccount, Error> {
+    if network_instance.is_some() && !network.definition().allows_instances {
+        let e = format!("{} accounts can't have a network instance", network);
+        return Err(Error::Encode(e.into()));
+    }
+
+    sqlx::query_as!(
+        SomeAccount,
+        r#"INSERT INTO skjera.some_account(employee, network, network_instance, authenticated, network_avatar, subject, name, nick, url, avatar)
+         VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+         RETURNING id, employee, network AS "network: SomeNetwork", authenticated, network_instance,
+            network_avatar, subject, name, nick, url, avatar, deactivated"#,
+        employee.0,
+        network as SomeNetwork,
+        network_instance,
+        authenticated,
+        network_avatar,
+        subject,
+        name,
+        nick,
+        url,
+        avatar,
+    )
+        .fetch_one(&mut *conn)
+        .await
+}
+
+pub(super) async fn some_account_for_network(
+    conn: &mut PgConnection,
+    employee_id: EmployeeId,
+    network: SomeNetwork,
+    network_instance: Option<String>,
+) -> Result<Option<SomeAccount>, Error> {
+    sqlx::query_as!(
+        SomeAccount,
+        r#"SELECT id, employee, network AS "network: SomeNetwork", authenticated, network_instance,
+            network_avatar, subject, name, nick, url, avatar, deactivated
+        FROM skjera.some_account
+        WHERE employee=$1 AND network=$2 AND ((network_instance IS NULL AND $3::TEXT IS NULL) OR (network_instance=$3::TEXT))"#,
+        employee_id.0,
+        network as SomeNetwork,
+        network_instance,
+    )
+    .fetch_optional(&mut *conn)
+    .await
+}
+
+#[allow(clippy::too_many_arguments)]
+pub(super) async fn update_some_account(
+    conn: &mut PgConnection,
+    id: SomeAccountId,
+    authenticated: bool,
+    network_avatar: Option<String>,
+    subject: Option<String>,
+    name: Option<String>,
+    nick: Option<String>,
+    url: Option<String>,
+    avatar: Option<String>,
+) -> Result<SomeAccount, Error> {
+    sqlx::query_as!(
+        SomeAccount,
+        r#"UPDATE skjera.some_account
+        SET authenticated=$1,
+            network_avatar=$2,
+            subject=$3,
+            name=$4,
+            nick=$5,
+            url=$6,
+            avatar=$7
+        WHERE id = $8
+        RETURNING id, employee, network AS "network: SomeNetwork", authenticated, network_instance,
+            network_avatar, subject, name, nick, url, avatar, deactivated
+        "#,
+        authenticated,
+        network_avatar,
+        subject,
+        name,
+        nick,
+        url,
+        avatar,
+        id.0,
+    )
+    .fetch_one(&mut *conn)
+    .await
+}
@@ -0,0 +1,145 @@
+use crate::id_type;
+use crate::model::*;
+use async_trait::async_trait;
+use serde::Deserialize;
+use sqlx::types::time::OffsetDateTime;
+use sqlx::*;
+
+id_type!(LoginAccessId);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, sqlx::Type, Deserialize)]
+#[sqlx(type_name = "varchar", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum LoginAccessStatus {
+    /// Someone outside the allowed domains tried to log in, an admin has to decide.
+    Pending,
+    Allowed,
+    Denied,
+}
+
+/// An entry in the allowlist/denylist for logging in, keyed on email address.
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct LoginAccess {
+    pub id: LoginAccessId,
+    pub email: String,
+    pub name: Option<String>,
+    pub status: LoginAccessStatus,
+    pub created_at: OffsetDateTime,
+    pub decided_at: Option<OffsetDateTime>,
+}
+
+impl LoginAccess {
+    pub fn is_pending(&self) -> bool {
+        self.status == LoginAccessStatus::Pending
+    }
+}
+
+#[async_trait]
+pub(crate) trait LoginAccessDao {
+    /// Pending entries first, newest first.
+    async fn login_accesses(&self) -> Result<Vec<LoginAccess>, Error>;
+
+    async fn login_access_by_email(&self, email: String) -> Result<Option<LoginAccess>, Error>;
+
+    /// Records a sign-up that an admin has to approve. Does nothing if there already is an entry
+    /// for the email address.
+    async fn insert_pending_login_access(&self, email: String, name: String) -> Result<(), Error>;
+
+    /// Adds or replaces the entry for the email address with a decision.
+    async fn decide_login_access(
+        &self,
+        email: String,
+        status: LoginAccessStatus,
+    ) -> Result<LoginAccess, Error>;
+
+    async fn update_login_access(
+        &self,
+        id: LoginAccessId,
+        status: LoginAccessStatus,
+    ) -> Result<u64, Error>;
+
+    async fn delete_login_access(&self, id: LoginAccessId) -> Result<u64, Error>;
+}
+
+#[async_trait]
+impl LoginAccessDao for Dao {
+    #[tracing::instrument]
+    async fn login_accesses(&self) -> Result<Vec<LoginAccess>, Error> {
+        sqlx::query_as!(
+            LoginAccess,
+            r#"SELECT id, email, name, status AS "status: LoginAccessStatus", created_at, decided_at
+            FROM skjera.login_access
+            ORDER BY status <> 'pending', created_at DESC"#
+        )
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    #[tracing::instrument]
+    async fn login_access_by_email(&self, email: String) -> Result<Option<LoginAccess>, Error> {
+        sqlx::query_as!(
+            LoginAccess,
+            r#"SELECT id, email, name, status AS "status: LoginAccessStatus", created_at, decided_at
+            FROM skjera.login_access
+            WHERE email=LOWER($1)"#,
+            email
+        )
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    #[tracing::instrument]
+    async fn insert_pending_login_access(&self, email: String, name: String) -> Result<(), Error> {
+        sqlx::query!(
+            "INSERT INTO skjera.login_access(email, name, status) VALUES (LOWER($1), $2, 'pending')
+             ON CONFLICT DO NOTHING",
+            email,
+            name,
+        )
+        .execute(&self.pool)
+        .await
+        .map(|_| ())
+    }
+
+    #[tracing::instrument]
+    async fn decide_login_access(
+        &self,
+        email: String,
+        status: LoginAccessStatus,
+    ) -> Result<LoginAccess, Error> {
+        sqlx::query_as!(
+            LoginAccess,
+            r#"INSERT INTO skjera.login_access(email, status, decided_at) VALUES (LOWER($1), $2, NOW())
+            ON CONFLICT (email) DO UPDATE SET status=excluded.status, decided_at=excluded.decided_at
+            RETURNING id, email, name, status AS "status: LoginAccessStatus", created_at, decided_at"#,
+            email,
+            status as LoginAccessStatus,
+        )
+        .fetch_one(&self.pool)
+        .await
+    }
+
+    #[tracing::instrument]
+    async fn update_login_access(
+        &self,
+        id: LoginAccessId,
+        status: LoginAccessStatus,
+    ) -> Result<u64, Error> {
+        sqlx::query!(
+            "UPDATE skjera.login_access SET status=$1, decided_at=NOW() WHERE id=$2",
+            status as LoginAccessStatus,
+            id.0,
+        )
+        .execute(&self.pool)
+        .await
+        .map(|r| r.rows_affected())
+    }
+
+    #[tracing::instrument]
+    async fn delete_login_access(&self, id: LoginAccessId) -> Result<u64, Error> {
+        sqlx::query!("DELETE FROM skjera.login_access WHERE id=$1", id.0)
+            .execute(&self.pool)
+            .await
+            .map(|r| r.rows_affected())
+    }
+}
@@ -0,0 +1,79 @@
+use crate::id_type;
+use crate::model::*;
+use async_trait::async_trait;
+use sqlx::types::time::OffsetDateTime;
+use sqlx::*;
+
+id_type!(NewsItemId);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, sqlx::Type)]
+#[sqlx(type_name = "varchar", rename_all = "snake_case")]
+pub enum NewsKind {
+    EmployeeJoined,
+    EmployeeLeft,
+    AssignmentStarted,
+    CustomerAdded,
+}
+
+/// An entry in the news feed. The related entities are cleared if they are deleted, the item
+/// itself stays.
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct NewsItem {
+    pub id: NewsItemId,
+    pub kind: NewsKind,
+    pub publish_date: OffsetDateTime,
+    pub title: String,
+    pub body: String,
+    pub employee: Option<EmployeeId>,
+    pub customer: Option<CustomerId>,
+}
+
+#[async_trait]
+pub(crate) trait NewsDao {
+    /// The newest news items, newest first.
+    async fn news_items(&self, limit: i64) -> Result<Vec<NewsItem>, Error>;
+}
+
+#[async_trait]
+impl NewsDao for Dao {
+    #[tracing::instrument]
+    async fn news_items(&self, limit: i64) -> Result<Vec<NewsItem>, Error> {
+        sqlx::query_as!(
+            NewsItem,
+            r#"SELECT id, kind AS "kind: NewsKind", publish_date, title, body,
+                employee AS "employee: EmployeeId",
+                customer AS "customer: CustomerId"
+            FROM skjera.news_item
+            ORDER BY publish_date DESC, id DESC
+            LIMIT $1"#,
+            limit
+        )
+        .fetch_all(&self.pool)
+        .await
+    }
+}
+
+/// Inserts a news item as part of the transaction that created the entities it is about.
+pub(super) async fn insert_news_item(
+    conn: &mut PgConnection,
+    kind: NewsKind,
+    title: String,
+    body: String,
+    employee: Option<EmployeeId>,
+    customer: Option<CustomerId>,
+    assignment: Option<AssignmentId>,
+) -> Result<NewsItemId, Error> {
+    sqlx::query_scalar!(
+        r#"INSERT INTO skjera.news_item(kind, title, body, employee, customer, assignment)
+        VALUES ($1, $2, $3, $4, $5, $6)
+        RETURNING id AS "id: NewsItemId""#,
+        kind as NewsKind,
+        title,
+        body,
+        employee.map(|e| e.0),
+        customer.map(|c| c.0),
+        assignment.map(|a| a.0),
+    )
+    .fetch_one(conn)
+    .await
+}
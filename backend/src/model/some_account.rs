@@ -1,9 +1,10 @@
 use crate::id_type;
 use crate::model::*;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::Deserialize;
 use std::fmt::{Display, Formatter};
 use std::str::FromStr;
-use std::string::ToString;
-use once_cell::sync::Lazy;
 
 id_type!(SomeAccountId);
 
@@ -22,39 +23,218 @@ pub struct SomeAccount {
     pub nick: Option<String>,
     pub url: Option<String>,
     pub avatar: Option<String>,
+    /// The account has been deactivated on the network, only Slack accounts are checked.
+    pub deactivated: bool,
+}
+
+/// The networks that employees can have accounts on, see [SomeNetworkDefinition] for the
+/// details of each.
+#[derive(Debug, Clone, Copy, Hash, Eq, PartialEq, Ord, PartialOrd, sqlx::Type, Deserialize)]
+#[sqlx(type_name = "varchar", rename_all = "kebab-case")]
+#[serde(rename_all = "kebab-case")]
+pub enum SomeNetwork {
+    Bluesky,
+    Github,
+    Gitlab,
+    LinkedIn,
+    Slack,
+    X,
+}
+
+#[derive(Debug)]
+pub struct SomeNetworkDefinition {
+    pub network: SomeNetwork,
+    /// The value stored in the database and used in URLs and the API.
+    pub id: &'static str,
+    pub name: &'static str,
+    /// An emoji that is shown next to the name.
+    pub icon: &'static str,
+    /// What the network calls a nick, like "handle" or "username".
+    pub nick_label: &'static str,
+    /// Used in error messages and as a placeholder in forms.
+    pub nick_example: &'static str,
+    /// The URL of an account's profile, `{nick}` is replaced with the nick.
+    pub url_template: Option<&'static str>,
+    /// If the network has workspaces or self-hosted installations, see
+    /// [SomeAccount::network_instance].
+    pub allows_instances: bool,
+    /// Accounts on the other networks can only be added by connecting them through OAuth.
+    pub manual: bool,
+    /// Nicks are case-insensitive and stored in lowercase.
+    pub lowercase: bool,
+    nick_pattern: Regex,
 }
 
-#[derive(Debug, Clone, Hash, Eq, PartialEq)]
-pub struct SomeNetwork(pub String);
+static NETWORKS: Lazy<Vec<SomeNetworkDefinition>> = Lazy::new(|| {
+    vec![
+        SomeNetworkDefinition {
+            network: SomeNetwork::Bluesky,
+            id: "bluesky",
+            name: "Bluesky",
+            icon: "🦋",
+            nick_label: "handle",
+            nick_example: "name.bsky.social",
+            url_template: Some("https://bsky.app/profile/{nick}"),
+            allows_instances: false,
+            manual: true,
+            lowercase: true,
+            // Handles are domain names, see <https://atproto.com/specs/handle>
+            nick_pattern: Regex::new(
+                r"^([a-z0-9]([a-z0-9-]{0,61}[a-z0-9])?\.)+[a-z]([a-z0-9-]{0,61}[a-z0-9])?$",
+            )
+            .unwrap(),
+        },
+        SomeNetworkDefinition {
+            network: SomeNetwork::Github,
+            id: "github",
+            name: "GitHub",
+            icon: "🐙",
+            nick_label: "username",
+            nick_example: "octocat",
+            url_template: Some("https://github.com/{nick}"),
+            allows_instances: false,
+            manual: false,
+            lowercase: false,
+            nick_pattern: Regex::new(r"^[A-Za-z0-9](-?[A-Za-z0-9]){0,38}$").unwrap(),
+        },
+        SomeNetworkDefinition {
+            network: SomeNetwork::Gitlab,
+            id: "gitlab",
+            name: "GitLab",
+            icon: "🦊",
+            nick_label: "username",
+            nick_example: "name",
+            url_template: Some("https://gitlab.com/{nick}"),
+            allows_instances: true,
+            manual: false,
+            lowercase: false,
+            nick_pattern: Regex::new(r"^[A-Za-z0-9_.-]{1,255}$").unwrap(),
+        },
+        SomeNetworkDefinition {
+            network: SomeNetwork::LinkedIn,
+            id: "linked-in",
+            name: "LinkedIn",
+            icon: "💼",
+            nick_label: "profile URL",
+            nick_example: "https://www.linkedin.com/in/my-profile",
+            url_template: Some("https://www.linkedin.com/in/{nick}"),
+            allows_instances: false,
+            manual: true,
+            lowercase: false,
+            nick_pattern: Regex::new(r"^[A-Za-z0-9%_-]{3,100}$").unwrap(),
+        },
+        SomeNetworkDefinition {
+            network: SomeNetwork::Slack,
+            id: "slack",
+            name: "Slack",
+            icon: "💬",
+            nick_label: "display name",
+            nick_example: "name",
+            url_template: None,
+            allows_instances: true,
+            manual: false,
+            lowercase: false,
+            nick_pattern: Regex::new(r"^.{1,80}$").unwrap(),
+        },
+        SomeNetworkDefinition {
+            network: SomeNetwork::X,
+            id: "x",
+            name: "X",
+            icon: "✖️",
+            nick_label: "handle",
+            nick_example: "@handle",
+            url_template: Some("https://x.com/{nick}"),
+            allows_instances: false,
+            manual: true,
+            lowercase: false,
+            nick_pattern: Regex::new(r"^[A-Za-z0-9_]{1,15}$").unwrap(),
+        },
+    ]
+});
 
-pub(crate) static BLUESKY: Lazy<SomeNetwork> = Lazy::new(|| SomeNetwork(String::from("bluesky")));
-pub(crate) static LINKED_IN: Lazy<SomeNetwork> = Lazy::new(|| SomeNetwork(String::from("linked-in")));
-pub(crate) static SLACK: Lazy<SomeNetwork> = Lazy::new(|| SomeNetwork(String::from("slack")));
-pub(crate) static X: Lazy<SomeNetwork> = Lazy::new(|| SomeNetwork(String::from("x")));
+/// All networks in the order they are shown.
+pub fn some_network_definitions() -> &'static [SomeNetworkDefinition] {
+    NETWORKS.as_slice()
+}
 
 impl SomeNetwork {
-    // pub fn is_slack(self: &Self) -> bool {
-    //     self.0 == SLACK.0
-    // }
+    pub fn definition(&self) -> &'static SomeNetworkDefinition {
+        NETWORKS
+            .iter()
+            .find(|d| d.network == *self)
+            .expect("all networks are defined")
+    }
 }
 
-impl FromStr for SomeNetwork {
-    type Err = ();
+impl SomeNetworkDefinition {
+    /// Parses what the employee entered as their nick, which can also be the URL of their
+    /// profile. A leading "@" is removed.
+    pub fn parse_nick(&self, input: &str) -> Result<String, String> {
+        let s = input.trim();
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let s: String = String::from(s);
-        Ok(SomeNetwork(s.to_string()))
+        let s = match self.url_template.and_then(|t| strip_url_prefix(s, t)) {
+            Some(nick) => nick.split(['?', '#']).next().unwrap_or_default(),
+            None => s,
+        };
+        let s = s.trim_end_matches('/');
+        let s = s.strip_prefix('@').unwrap_or(s);
+
+        let s = match self.lowercase {
+            true => s.to_lowercase(),
+            false => s.to_string(),
+        };
+
+        match self.nick_pattern.is_match(&s) {
+            true => Ok(s),
+            false => Err(format!(
+                "{} is not a valid {} {}, it should look like {}",
+                input.trim(),
+                self.name,
+                self.nick_label,
+                self.nick_example
+            )),
+        }
+    }
+
+    pub fn profile_url(&self, nick: &str) -> Option<String> {
+        self.url_template.map(|t| t.replace("{nick}", nick))
     }
 }
 
-impl From<String> for SomeNetwork {
-    fn from(value: String) -> Self {
-        SomeNetwork(value)
+/// Returns the part of `url` after the part of the template before `{nick}`. The scheme and
+/// "www." are optional.
+fn strip_url_prefix<'a>(url: &'a str, template: &str) -> Option<&'a str> {
+    fn normalize(s: &str) -> &str {
+        let s = s
+            .strip_prefix("https://")
+            .or_else(|| s.strip_prefix("http://"))
+            .unwrap_or(s);
+        s.strip_prefix("www.").unwrap_or(s)
+    }
+
+    let (prefix, _) = template.split_once("{nick}")?;
+    let prefix = normalize(prefix);
+    let url = normalize(url);
+
+    url.get(..prefix.len())
+        .filter(|start| start.eq_ignore_ascii_case(prefix))
+        .map(|_| &url[prefix.len()..])
+}
+
+impl FromStr for SomeNetwork {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        NETWORKS
+            .iter()
+            .find(|d| d.id == s)
+            .map(|d| d.network)
+            .ok_or_else(|| format!("unknown network: {}", s))
     }
 }
 
 impl Display for SomeNetwork {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", self.0)
+        write!(f, "{}", self.definition().id)
     }
 }
@@ -0,0 +1,77 @@
+use crate::model::*;
+use async_trait::async_trait;
+use rand::RngCore;
+use sqlx::types::time::OffsetDateTime;
+use sqlx::*;
+
+/// How long a challenge can be used, a new one is made after that.
+pub const VERIFICATION_CHALLENGE_VALIDITY: time::Duration = time::Duration::days(1);
+
+/// Creates a new random challenge for the employee to post on the network.
+pub fn generate_verification_challenge() -> String {
+    let mut bytes = [0u8; 8];
+    rand::thread_rng().fill_bytes(&mut bytes);
+
+    format!("skjera-{}", hex::encode(bytes))
+}
+
+#[async_trait]
+pub(crate) trait SomeAccountVerificationDao {
+    /// Returns the challenge for the account, `challenge` is stored and returned if it doesn't
+    /// have one already or if it has expired.
+    async fn some_account_challenge(
+        &self,
+        some_account: SomeAccountId,
+        challenge: String,
+    ) -> Result<String, Error>;
+
+    async fn delete_some_account_challenge(
+        &self,
+        some_account: SomeAccountId,
+    ) -> Result<u64, Error>;
+}
+
+#[async_trait]
+impl SomeAccountVerificationDao for Dao {
+    #[tracing::instrument]
+    async fn some_account_challenge(
+        &self,
+        some_account: SomeAccountId,
+        challenge: String,
+    ) -> Result<String, Error> {
+        let expired_before = OffsetDateTime::now_utc() - VERIFICATION_CHALLENGE_VALIDITY;
+
+        sqlx::query!(
+            "INSERT INTO skjera.some_account_verification(some_account, challenge) VALUES ($1, $2)
+            ON CONFLICT (some_account) DO UPDATE
+            SET challenge=excluded.challenge, created_at=excluded.created_at
+            WHERE some_account_verification.created_at < $3",
+            some_account.0,
+            challenge,
+            expired_before,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        sqlx::query_scalar!(
+            "SELECT challenge FROM skjera.some_account_verification WHERE some_account=$1",
+            some_account.0,
+        )
+        .fetch_one(&self.pool)
+        .await
+    }
+
+    #[tracing::instrument]
+    async fn delete_some_account_challenge(
+        &self,
+        some_account: SomeAccountId,
+    ) -> Result<u64, Error> {
+        sqlx::query!(
+            "DELETE FROM skjera.some_account_verification WHERE some_account=$1",
+            some_account.0,
+        )
+        .execute(&self.pool)
+        .await
+        .map(|r| r.rows_affected())
+    }
+}
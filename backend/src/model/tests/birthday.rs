@@ -0,0 +1,35 @@
+use crate::model::tests::{delete_test_employee, insert_test_employee, test_dao};
+use crate::model::*;
+use time::macros::date;
+
+#[tokio::test]
+async fn test_birthday_announcement() {
+    let Some(dao) = test_dao().await else {
+        return;
+    };
+    let employee = insert_test_employee(&dao, "Ola Nordmann").await;
+    let today = date!(2025 - 12 - 09);
+
+    assert!(dao
+        .insert_birthday_announcement(employee.id, today)
+        .await
+        .unwrap());
+    assert!(!dao
+        .insert_birthday_announcement(employee.id, today)
+        .await
+        .unwrap());
+
+    // Released when the announcement couldn't be posted
+    assert_eq!(
+        1,
+        dao.delete_birthday_announcement(employee.id, today)
+            .await
+            .unwrap()
+    );
+    assert!(dao
+        .insert_birthday_announcement(employee.id, today)
+        .await
+        .unwrap());
+
+    delete_test_employee(&dao, employee.id).await;
+}
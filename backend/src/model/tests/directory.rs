@@ -0,0 +1,266 @@
+use crate::model::tests::{delete_test_employee, insert_test_employee, test_dao, test_employee};
+use crate::model::*;
+use std::path::Path;
+use time::macros::date;
+use uuid::Uuid;
+
+fn employee(id: i64, email: &str) -> Employee {
+    Employee {
+        email: email.to_string(),
+        scienta_slack_name: Some("ola".to_string()),
+        ..test_employee(id)
+    }
+}
+
+fn bluesky_account(employee: i64) -> SomeAccount {
+    SomeAccount {
+        id: SomeAccountId(1),
+        employee: EmployeeId(employee),
+        network: SomeNetwork::Bluesky,
+        authenticated: true,
+        network_instance: None,
+        network_avatar: None,
+        subject: Some("did:plc:abc".to_string()),
+        name: None,
+        nick: Some("ola.bsky.social".to_string()),
+        url: Some("https://bsky.app/profile/ola.bsky.social".to_string()),
+        avatar: None,
+        deactivated: false,
+    }
+}
+
+#[test]
+fn test_directory_format() {
+    assert_eq!(
+        Some(DirectoryFormat::Csv),
+        DirectoryFormat::from_path(Path::new("employees.CSV"))
+    );
+    assert_eq!(
+        Some(DirectoryFormat::Json),
+        DirectoryFormat::from_path(Path::new("/tmp/employees.json"))
+    );
+    assert_eq!(None, DirectoryFormat::from_path(Path::new("employees")));
+}
+
+#[test]
+fn test_round_trip() {
+    let employees = vec![
+        employee(1, "ola@scienta.no"),
+        employee(2, "kari@scienta.no"),
+    ];
+    let directory = directory_employees(employees, &[bluesky_account(1)]);
+
+    // Hidden from admins
+    assert_eq!(None, directory[0].phone_number);
+    assert_eq!(Some("1980-12-09"), directory[0].dob.as_deref());
+
+    for format in [DirectoryFormat::Csv, DirectoryFormat::Json] {
+        let data = write_directory(format, &directory).unwrap();
+        let (employees, errors) = read_directory(format, &data).unwrap();
+
+        assert_eq!(Vec::<ImportError>::new(), errors);
+        assert_eq!(2, employees.len());
+
+        let ola = &employees[0];
+        assert_eq!("ola@scienta.no", ola.email);
+        assert_eq!(Some(date!(1980 - 12 - 09)), ola.dob);
+        assert_eq!(Some("ola@example.com"), ola.personal_email.as_deref());
+        assert_eq!(1, ola.some_accounts.len());
+        assert_eq!(SomeNetwork::Bluesky, ola.some_accounts[0].network);
+        assert_eq!(Some(true), ola.some_accounts[0].authenticated);
+
+        assert!(employees[1].some_accounts.is_empty());
+    }
+}
+
+#[test]
+fn test_read_csv() {
+    let csv = "\
+email,name,dob,phone_number,network,nick,authenticated
+ola@scienta.no,Ola Nordmann,1980-12-09,+47 900 00 000,x,https://x.com/ola,
+ola@scienta.no,,,,github,ola,true
+kari@scienta,Kari Nordmann,,,,,
+per@scienta.no,Per Hansen,1980-13-01,,,,
+lise@scienta.no,Lise Hansen,,,mastodon,lise,
+ola@scienta.no,Ola Normann,,,,,
+nils@scienta.no,Nils,,,github,nils,yes
+";
+
+    let (employees, errors) = read_directory(DirectoryFormat::Csv, csv.as_bytes()).unwrap();
+
+    assert_eq!(1, employees.len());
+    let ola = &employees[0];
+    assert_eq!(2, ola.row);
+    assert_eq!(Some("Ola Nordmann"), ola.name.as_deref());
+    assert_eq!(Some("+4790000000"), ola.phone_number.as_deref());
+
+    let accounts: Vec<_> = ola
+        .some_accounts
+        .iter()
+        .map(|a| (a.row, a.network, a.nick.as_deref(), a.authenticated))
+        .collect();
+    assert_eq!(
+        vec![
+            (2, SomeNetwork::X, Some("ola"), None),
+            (3, SomeNetwork::Github, Some("ola"), Some(true)),
+        ],
+        accounts
+    );
+    assert_eq!(
+        Some("https://x.com/ola"),
+        ola.some_accounts[0].url.as_deref()
+    );
+
+    let errors: Vec<_> = errors.iter().map(|e| e.row).collect();
+    assert_eq!(vec![4, 5, 6, 7, 8], errors);
+}
+
+#[test]
+fn test_read_csv_without_email() {
+    assert!(read_directory(DirectoryFormat::Csv, b"name\nOla\n").is_err());
+}
+
+#[test]
+fn test_read_json() {
+    let json = r#"[
+        {"email": "ola@scienta.no", "name": "Ola", "some_accounts": [{"network": "bluesky", "nick": "@ola.bsky.social"}]},
+        {"email": "kari@scienta.no", "some_accounts": [{"network": "linked-in"}]},
+        {"name": "Per"},
+        {"email": "lise@scienta.no", "noa_slack_name": "@lise"}
+    ]"#;
+
+    let (employees, errors) = read_directory(DirectoryFormat::Json, json.as_bytes()).unwrap();
+
+    let emails: Vec<_> = employees.iter().map(|e| e.email.as_str()).collect();
+    assert_eq!(vec!["ola@scienta.no", "lise@scienta.no"], emails);
+    assert_eq!(
+        Some("ola.bsky.social"),
+        employees[0].some_accounts[0].nick.as_deref()
+    );
+    assert_eq!(Some("lise"), employees[1].noa_slack_name.as_deref());
+
+    assert_eq!(
+        vec![
+            ImportError {
+                row: 2,
+                message: "LinkedIn account needs a nick or a subject".to_string()
+            },
+            ImportError {
+                row: 3,
+                message: "missing field `email`".to_string()
+            },
+        ],
+        errors
+    );
+
+    assert!(read_directory(DirectoryFormat::Json, b"{}").is_err());
+}
+
+fn import_employee(email: &str, accounts: Vec<ImportAccount>) -> ImportEmployee {
+    ImportEmployee {
+        row: 2,
+        email: email.to_string(),
+        name: Some("Ola Nordmann".to_string()),
+        dob: None,
+        personal_email: None,
+        phone_number: None,
+        scienta_slack_name: None,
+        noa_slack_name: None,
+        some_accounts: accounts,
+    }
+}
+
+fn import_account(
+    row: usize,
+    network: SomeNetwork,
+    network_instance: Option<&str>,
+    authenticated: Option<bool>,
+) -> ImportAccount {
+    ImportAccount {
+        row,
+        network,
+        network_instance: network_instance.map(|s| s.to_string()),
+        subject: None,
+        name: None,
+        nick: Some("ola".to_string()),
+        url: None,
+        avatar: None,
+        authenticated,
+    }
+}
+
+/// An employee is imported with all of its accounts or not at all.
+#[tokio::test]
+async fn test_import_rolls_back_employee() {
+    let Some(dao) = test_dao().await else {
+        return;
+    };
+    let email = format!("test-{}@scienta.no", Uuid::now_v7());
+
+    let accounts = vec![
+        import_account(2, SomeNetwork::Github, None, None),
+        import_account(3, SomeNetwork::Bluesky, Some("example.com"), None),
+    ];
+    let report = import_directory(&dao, vec![import_employee(&email, accounts)], vec![])
+        .await
+        .unwrap();
+
+    assert_eq!(0, report.created);
+    assert_eq!(1, report.errors.len());
+    assert_eq!(3, report.errors[0].row);
+    assert!(report.errors[0]
+        .message
+        .contains("can't have a network instance"));
+    assert!(dao.employee_by_email(email).await.unwrap().is_none());
+}
+
+async fn authenticated(dao: &Dao, employee: EmployeeId) -> Vec<bool> {
+    let mut accounts = dao.some_accounts_by_employee(employee).await.unwrap();
+    accounts.sort_by_key(|a| a.id.0);
+
+    accounts.iter().map(|a| a.authenticated).collect()
+}
+
+/// An import can take away that an account is authenticated, but not give it.
+#[tokio::test]
+async fn test_import_authenticated() {
+    let Some(dao) = test_dao().await else {
+        return;
+    };
+    let employee = insert_test_employee(&dao, "Ola Nordmann").await;
+
+    let import = |authenticated| {
+        let accounts = vec![
+            import_account(2, SomeNetwork::Github, None, authenticated),
+            import_account(3, SomeNetwork::Gitlab, Some("gitlab.example.com"), None),
+        ];
+        vec![import_employee(&employee.email, accounts)]
+    };
+
+    let report = import_directory(&dao, import(Some(true)), vec![])
+        .await
+        .unwrap();
+    assert_eq!(Vec::<ImportError>::new(), report.errors);
+    assert_eq!(1, report.updated);
+    assert_eq!(vec![false, false], authenticated(&dao, employee.id).await);
+
+    sqlx::query("UPDATE skjera.some_account SET authenticated=TRUE WHERE employee=$1")
+        .bind(employee.id.0)
+        .execute(&dao.pool)
+        .await
+        .unwrap();
+
+    let report = import_directory(&dao, import(Some(true)), vec![])
+        .await
+        .unwrap();
+    assert_eq!(1, report.unchanged);
+    assert_eq!(vec![true, true], authenticated(&dao, employee.id).await);
+
+    let report = import_directory(&dao, import(Some(false)), vec![])
+        .await
+        .unwrap();
+    assert_eq!(1, report.updated);
+    assert_eq!(vec![false, true], authenticated(&dao, employee.id).await);
+
+    delete_test_employee(&dao, employee.id).await;
+}
@@ -0,0 +1,176 @@
+use crate::model::tests::{delete_test_employee, insert_test_employee, test_dao, test_employee};
+use crate::model::*;
+use crate::session::{PgSessionStore, AUTH_DATA_KEY};
+use serde_json::json;
+use std::collections::HashMap;
+use time::OffsetDateTime;
+use tower_sessions::session::{Id, Record};
+use tower_sessions::SessionStore;
+use uuid::Uuid;
+
+#[test]
+fn test_parse_phone_number() {
+    assert_eq!(
+        Ok("+4790000000".to_string()),
+        parse_phone_number("+47 900 00 000")
+    );
+    assert_eq!(
+        Ok("+4790000000".to_string()),
+        parse_phone_number("0047 900-00-000")
+    );
+    assert_eq!(
+        Ok("+14155552671".to_string()),
+        parse_phone_number("+1 (415) 555-2671")
+    );
+
+    assert!(parse_phone_number("900 00 000").is_err());
+    assert!(parse_phone_number("+47 900 OO 000").is_err());
+    assert!(parse_phone_number("+0 123").is_err());
+    assert!(parse_phone_number("+1").is_err());
+    assert!(parse_phone_number("+1234567890123456").is_err());
+}
+
+#[test]
+fn test_parse_email() {
+    assert_eq!(
+        Ok("me@example.com".to_string()),
+        parse_email(" me@example.com ")
+    );
+
+    assert!(parse_email("me").is_err());
+    assert!(parse_email("@example.com").is_err());
+    assert!(parse_email("me@example").is_err());
+    assert!(parse_email("me@example.com.").is_err());
+    assert!(parse_email("me@you@example.com").is_err());
+    assert!(parse_email("m e@example.com").is_err());
+}
+
+#[test]
+fn test_parse_slack_name() {
+    assert_eq!(Ok("trygvis".to_string()), parse_slack_name("@trygvis"));
+    assert_eq!(Ok("Trygve L".to_string()), parse_slack_name(" Trygve L "));
+
+    assert!(parse_slack_name("@").is_err());
+    assert!(parse_slack_name(&"x".repeat(81)).is_err());
+    assert!(parse_slack_name("trygvis@scienta").is_err());
+}
+
+#[test]
+fn test_redacted() {
+    let colleague = test_employee(1).redacted(EmployeeId(2), EmployeeRole::Employee);
+    assert!(colleague.dob.is_some());
+    assert!(colleague.personal_email.is_none());
+    assert!(colleague.phone_number.is_none());
+
+    let admin = test_employee(1).redacted(EmployeeId(2), EmployeeRole::Admin);
+    assert!(admin.dob.is_some());
+    assert!(admin.personal_email.is_some());
+    assert!(admin.phone_number.is_none());
+
+    let me = test_employee(1).redacted(EmployeeId(1), EmployeeRole::Employee);
+    assert!(me.dob.is_some());
+    assert!(me.personal_email.is_some());
+    assert!(me.phone_number.is_some());
+}
+
+#[tokio::test]
+async fn test_search_employees() {
+    let Some(dao) = test_dao().await else {
+        return;
+    };
+    let text = Uuid::now_v7().to_string();
+    let mut employees = Vec::new();
+    for name in ["Kari", "Ola", "Per"] {
+        employees.push(insert_test_employee(&dao, &format!("{} {}", name, text)).await);
+    }
+    dao.deactivate_employee(employees[2].id).await.unwrap();
+
+    let today = OffsetDateTime::now_utc().date();
+    let search = |offset, include_inactive| EmployeeQuery {
+        text: Some(text.clone()),
+        include_inactive,
+        limit: 2,
+        offset,
+        ..EmployeeQuery::default()
+    };
+    let names = |employees: Vec<Employee>| -> Vec<String> {
+        employees
+            .into_iter()
+            .map(|e| e.name.split(' ').next().unwrap().to_string())
+            .collect()
+    };
+
+    let (found, total) = dao.search_employees(&search(0, true), today).await.unwrap();
+    assert_eq!(
+        (vec!["Kari".to_string(), "Ola".to_string()], 3),
+        (names(found), total)
+    );
+
+    let (found, total) = dao.search_employees(&search(2, true), today).await.unwrap();
+    assert_eq!((vec!["Per".to_string()], 3), (names(found), total));
+
+    let (found, total) = dao
+        .search_employees(&search(0, false), today)
+        .await
+        .unwrap();
+    assert_eq!(2, found.len());
+    assert_eq!(2, total);
+
+    let (found, total) = dao.search_employees(&search(4, true), today).await.unwrap();
+    assert!(found.is_empty());
+    assert_eq!(0, total);
+
+    for employee in employees {
+        delete_test_employee(&dao, employee.id).await;
+    }
+}
+
+/// A session where the employee is logged in, as axum-login stores it.
+async fn login_session(store: &PgSessionStore, employee: EmployeeId) -> Id {
+    let mut record = Record {
+        id: Id::default(),
+        data: HashMap::from([(
+            AUTH_DATA_KEY.to_string(),
+            json!({"user_id": employee.0, "auth_hash": employee.0.to_be_bytes()}),
+        )]),
+        expiry_date: OffsetDateTime::now_utc() + time::Duration::hours(1),
+    };
+    store.create(&mut record).await.unwrap();
+
+    record.id
+}
+
+#[tokio::test]
+async fn test_deactivate_employee_revokes_sessions() {
+    let Some(dao) = test_dao().await else {
+        return;
+    };
+    let ola = insert_test_employee(&dao, "Ola Nordmann").await;
+    let kari = insert_test_employee(&dao, "Kari Nordmann").await;
+    let store = PgSessionStore::new(dao.clone());
+
+    let ola_session = login_session(&store, ola.id).await;
+    let kari_session = login_session(&store, kari.id).await;
+
+    assert_eq!(1, dao.deactivate_employee(ola.id).await.unwrap());
+
+    assert!(store.load(&ola_session).await.unwrap().is_none());
+    assert!(store.load(&kari_session).await.unwrap().is_some());
+
+    delete_test_employee(&dao, ola.id).await;
+    delete_test_employee(&dao, kari.id).await;
+}
+
+#[tokio::test]
+async fn test_new_employee_field_visibility() {
+    let Some(dao) = test_dao().await else {
+        return;
+    };
+    let ola = insert_test_employee(&dao, "Ola Nordmann").await;
+
+    assert_eq!(FieldVisibility::Everyone, ola.dob_visibility);
+    assert_eq!(FieldVisibility::Admins, ola.personal_email_visibility);
+    assert_eq!(FieldVisibility::Admins, ola.phone_number_visibility);
+
+    delete_test_employee(&dao, ola.id).await;
+}
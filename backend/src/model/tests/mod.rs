@@ -0,0 +1,88 @@
+mod birthday;
+mod directory;
+mod employee;
+mod some_account;
+
+use crate::model::*;
+use time::macros::date;
+use uuid::Uuid;
+
+/// An employee with every field set, tests override what they care about with
+/// `Employee { name: ..., ..test_employee(1) }`.
+pub(crate) fn test_employee(id: i64) -> Employee {
+    Employee {
+        id: EmployeeId(id),
+        email: "ola@scienta.no".to_string(),
+        name: "Ola Nordmann".to_string(),
+        dob: Some(date!(1980 - 12 - 09)),
+        role: EmployeeRole::Employee,
+        active: true,
+        personal_email: Some("ola@example.com".to_string()),
+        phone_number: Some("+4790000000".to_string()),
+        scienta_slack_name: None,
+        noa_slack_name: None,
+        dob_visibility: FieldVisibility::Everyone,
+        personal_email_visibility: FieldVisibility::Admins,
+        phone_number_visibility: FieldVisibility::Hidden,
+        birthday_announcement: true,
+        birthday_calendar_year: false,
+    }
+}
+
+/// The tests that need a database use the one in `DATABASE_URL`, the same one that the queries
+/// are checked against when building. They are skipped when it isn't set.
+pub(crate) async fn test_dao() -> Option<Dao> {
+    let url = std::env::var("DATABASE_URL").ok()?;
+
+    let pool = sqlx::PgPool::connect(&url)
+        .await
+        .expect("could not connect to DATABASE_URL");
+
+    Some(Dao::new(pool))
+}
+
+/// The pool of the test DAO, for building a server.
+pub(crate) fn test_pool(dao: &Dao) -> sqlx::PgPool {
+    dao.pool.clone()
+}
+
+/// Inserts an employee with a unique email, remove it again with [delete_test_employee].
+pub(crate) async fn insert_test_employee(dao: &Dao, name: &str) -> Employee {
+    let email = format!("test-{}@scienta.no", Uuid::now_v7());
+
+    dao.insert_employee(email, name.to_string()).await.unwrap()
+}
+
+/// The number of birthday greetings that have been sent to the employee.
+pub(crate) async fn birthday_greetings(dao: &Dao, employee: EmployeeId) -> i64 {
+    sqlx::query_scalar("SELECT COUNT(*) FROM skjera.birthday_greeting WHERE employee=$1")
+        .bind(employee.0)
+        .fetch_one(&dao.pool)
+        .await
+        .unwrap()
+}
+
+/// Deletes the employee and everything that refers to it.
+pub(crate) async fn delete_test_employee(dao: &Dao, id: EmployeeId) {
+    for table in [
+        "birthday_greeting",
+        "birthday_announcement",
+        "birthday_conversation",
+        "some_account",
+        "assignment",
+        "news_item",
+        "api_token",
+    ] {
+        sqlx::query(&format!("DELETE FROM skjera.{} WHERE employee=$1", table))
+            .bind(id.0)
+            .execute(&dao.pool)
+            .await
+            .unwrap();
+    }
+
+    sqlx::query("DELETE FROM skjera.employee WHERE id=$1")
+        .bind(id.0)
+        .execute(&dao.pool)
+        .await
+        .unwrap();
+}
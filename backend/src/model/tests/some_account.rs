@@ -0,0 +1,171 @@
+use crate::model::tests::{delete_test_employee, insert_test_employee, test_dao, test_pool};
+use crate::model::*;
+
+#[test]
+fn test_some_network_ids() {
+    for definition in some_network_definitions() {
+        assert_eq!(definition.id, definition.network.to_string());
+        assert_eq!(Ok(definition.network), definition.id.parse());
+        assert_eq!(definition.id, definition.network.definition().id);
+    }
+
+    assert_eq!(6, some_network_definitions().len());
+    assert!("twitter".parse::<SomeNetwork>().is_err());
+    assert!("Slack".parse::<SomeNetwork>().is_err());
+    assert!("".parse::<SomeNetwork>().is_err());
+}
+
+#[test]
+fn test_parse_bluesky_nick() {
+    let bluesky = SomeNetwork::Bluesky.definition();
+
+    assert_eq!(
+        Ok("alice.bsky.social".to_string()),
+        bluesky.parse_nick(" @Alice.bsky.social ")
+    );
+    assert_eq!(
+        Ok("xn--ls8h.example.no".to_string()),
+        bluesky.parse_nick("xn--ls8h.example.no")
+    );
+    assert_eq!(
+        Ok("alice.bsky.social".to_string()),
+        bluesky.parse_nick("https://bsky.app/profile/alice.bsky.social")
+    );
+
+    assert!(bluesky.parse_nick("alice").is_err());
+    assert!(bluesky.parse_nick("alice..bsky.social").is_err());
+    assert!(bluesky.parse_nick("-alice.bsky.social").is_err());
+    assert!(bluesky.parse_nick("alice.bsky.123").is_err());
+    assert!(bluesky.parse_nick("alice_b.bsky.social").is_err());
+    assert!(bluesky.parse_nick("https://x.com/alice").is_err());
+
+    assert_eq!(
+        Err(
+            "alice is not a valid Bluesky handle, it should look like name.bsky.social".to_string()
+        ),
+        bluesky.parse_nick("alice")
+    );
+}
+
+#[test]
+fn test_parse_linked_in_nick() {
+    let linked_in = SomeNetwork::LinkedIn.definition();
+
+    assert_eq!(
+        Ok("trygvis".to_string()),
+        linked_in.parse_nick("https://www.linkedin.com/in/trygvis/")
+    );
+    assert_eq!(
+        Ok("trygvis".to_string()),
+        linked_in.parse_nick("linkedin.com/in/trygvis?originalSubdomain=no")
+    );
+    assert_eq!(Ok("trygvis".to_string()), linked_in.parse_nick("trygvis"));
+
+    assert!(linked_in
+        .parse_nick("https://www.linkedin.com/company/scienta")
+        .is_err());
+    assert!(linked_in.parse_nick("").is_err());
+}
+
+#[test]
+fn test_parse_x_nick() {
+    let x = SomeNetwork::X.definition();
+
+    assert_eq!(Ok("Trygvis".to_string()), x.parse_nick("@Trygvis"));
+    assert_eq!(
+        Ok("trygvis".to_string()),
+        x.parse_nick("http://x.com/trygvis")
+    );
+
+    assert!(x.parse_nick("@").is_err());
+    assert!(x.parse_nick("much_too_long_handle").is_err());
+    assert!(x.parse_nick("https://bsky.app/profile/trygvis").is_err());
+}
+
+#[test]
+fn test_profile_url() {
+    assert_eq!(
+        Some("https://bsky.app/profile/alice.bsky.social".to_string()),
+        SomeNetwork::Bluesky
+            .definition()
+            .profile_url("alice.bsky.social")
+    );
+    assert_eq!(
+        Some("https://www.linkedin.com/in/trygvis".to_string()),
+        SomeNetwork::LinkedIn.definition().profile_url("trygvis")
+    );
+    assert_eq!(None, SomeNetwork::Slack.definition().profile_url("trygvis"));
+}
+
+#[tokio::test]
+async fn test_some_account_challenge_expires() {
+    let Some(dao) = test_dao().await else {
+        return;
+    };
+    let employee = insert_test_employee(&dao, "Ola Nordmann").await;
+    let account = dao
+        .add_some_account(
+            employee.id,
+            SomeNetwork::Bluesky,
+            None,
+            false,
+            None,
+            None,
+            None,
+            Some("ola.bsky.social".to_string()),
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+
+    let challenge = |c: &str| dao.some_account_challenge(account.id, c.to_string());
+
+    assert_eq!("first", challenge("first").await.unwrap());
+    assert_eq!("first", challenge("second").await.unwrap());
+
+    sqlx::query(
+        "UPDATE skjera.some_account_verification SET created_at=created_at - $1 WHERE some_account=$2",
+    )
+    .bind(VERIFICATION_CHALLENGE_VALIDITY)
+    .bind(account.id.0)
+    .execute(&test_pool(&dao))
+    .await
+    .unwrap();
+
+    assert_eq!("third", challenge("third").await.unwrap());
+    assert_eq!("third", challenge("fourth").await.unwrap());
+
+    delete_test_employee(&dao, employee.id).await;
+}
+
+#[tokio::test]
+async fn test_some_account_unique_without_network_instance() {
+    let Some(dao) = test_dao().await else {
+        return;
+    };
+    let ola = insert_test_employee(&dao, "Ola Nordmann").await;
+    let kari = insert_test_employee(&dao, "Kari Nordmann").await;
+    let subject = format!("github-{}", ola.id);
+
+    let add = |employee: EmployeeId, network: SomeNetwork, subject: Option<String>| {
+        dao.add_some_account(
+            employee, network, None, false, None, subject, None, None, None, None,
+        )
+    };
+
+    add(ola.id, SomeNetwork::Github, Some(subject.clone()))
+        .await
+        .unwrap();
+    assert!(add(ola.id, SomeNetwork::Github, None).await.is_err());
+    assert!(add(kari.id, SomeNetwork::Github, Some(subject.clone()))
+        .await
+        .is_err());
+
+    // Manually added accounts have no subject
+    add(ola.id, SomeNetwork::X, None).await.unwrap();
+    add(kari.id, SomeNetwork::X, None).await.unwrap();
+
+    delete_test_employee(&dao, ola.id).await;
+    delete_test_employee(&dao, kari.id).await;
+}
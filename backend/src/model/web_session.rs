@@ -0,0 +1,115 @@
+use crate::model::*;
+use async_trait::async_trait;
+use serde_json::Value;
+use sqlx::types::time::OffsetDateTime;
+use sqlx::*;
+
+/// A row in the session table, the data is the serialized tower-sessions record.
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct WebSession {
+    pub id: String,
+    pub data: Value,
+    pub expiry_date: OffsetDateTime,
+}
+
+#[async_trait]
+pub(crate) trait WebSessionDao {
+    /// Returns the session if it exists and hasn't expired.
+    async fn web_session_by_id(&self, id: String) -> Result<Option<WebSession>, Error>;
+
+    /// Inserts a new session. Returns false if a session with the same id already exists.
+    async fn insert_web_session(
+        &self,
+        id: String,
+        employee: Option<EmployeeId>,
+        data: Value,
+        expiry_date: OffsetDateTime,
+    ) -> Result<bool, Error>;
+
+    /// The employee is the logged in user, so their sessions can be found without looking into
+    /// the data.
+    async fn save_web_session(
+        &self,
+        id: String,
+        employee: Option<EmployeeId>,
+        data: Value,
+        expiry_date: OffsetDateTime,
+    ) -> Result<(), Error>;
+
+    async fn delete_web_session(&self, id: String) -> Result<u64, Error>;
+
+    async fn delete_expired_web_sessions(&self) -> Result<u64, Error>;
+}
+
+#[async_trait]
+impl WebSessionDao for Dao {
+    #[tracing::instrument(skip(id))]
+    async fn web_session_by_id(&self, id: String) -> Result<Option<WebSession>, Error> {
+        sqlx::query_as!(
+            WebSession,
+            "SELECT id, data, expiry_date FROM skjera.session WHERE id=$1 AND expiry_date > NOW()",
+            id
+        )
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    #[tracing::instrument(skip(id, data))]
+    async fn insert_web_session(
+        &self,
+        id: String,
+        employee: Option<EmployeeId>,
+        data: Value,
+        expiry_date: OffsetDateTime,
+    ) -> Result<bool, Error> {
+        sqlx::query!(
+            "INSERT INTO skjera.session(id, employee, data, expiry_date) VALUES ($1, $2, $3, $4)
+             ON CONFLICT DO NOTHING",
+            id,
+            employee.map(|e| e.0),
+            data,
+            expiry_date,
+        )
+        .execute(&self.pool)
+        .await
+        .map(|r| r.rows_affected() > 0)
+    }
+
+    #[tracing::instrument(skip(id, data))]
+    async fn save_web_session(
+        &self,
+        id: String,
+        employee: Option<EmployeeId>,
+        data: Value,
+        expiry_date: OffsetDateTime,
+    ) -> Result<(), Error> {
+        sqlx::query!(
+            "INSERT INTO skjera.session(id, employee, data, expiry_date) VALUES ($1, $2, $3, $4)
+             ON CONFLICT (id) DO UPDATE
+             SET employee=excluded.employee, data=excluded.data, expiry_date=excluded.expiry_date",
+            id,
+            employee.map(|e| e.0),
+            data,
+            expiry_date,
+        )
+        .execute(&self.pool)
+        .await
+        .map(|_| ())
+    }
+
+    #[tracing::instrument(skip(id))]
+    async fn delete_web_session(&self, id: String) -> Result<u64, Error> {
+        sqlx::query!("DELETE FROM skjera.session WHERE id=$1", id)
+            .execute(&self.pool)
+            .await
+            .map(|r| r.rows_affected())
+    }
+
+    #[tracing::instrument]
+    async fn delete_expired_web_sessions(&self) -> Result<u64, Error> {
+        sqlx::query!("DELETE FROM skjera.session WHERE expiry_date <= NOW()")
+            .execute(&self.pool)
+            .await
+            .map(|r| r.rows_affected())
+    }
+}
@@ -1,8 +1,12 @@
-use crate::model::EmployeeId;
+use crate::model::{ApiTokenScope, Dao, Employee, EmployeeId, EmployeeRole, WebSessionDao};
+use async_trait::async_trait;
 use axum_login::AuthUser;
 use oauth2::{CsrfToken, PkceCodeVerifier};
 use openidconnect::Nonce;
 use serde::{Deserialize, Serialize};
+use tower_sessions::session::{Id, Record};
+use tower_sessions::session_store;
+use tower_sessions::{ExpiredDeletion, SessionStore};
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub(crate) struct SkjeraSessionData {
@@ -10,7 +14,20 @@ pub(crate) struct SkjeraSessionData {
     pub(crate) session_hash: Box<[u8]>,
     pub(crate) email: String,
     pub(crate) name: String,
-    pub(crate) slack_connect: Option<SlackConnectData>,
+    pub(crate) role: EmployeeRole,
+    /// Set when the user authenticated with an API token instead of logging in.
+    pub(crate) api_token_scope: Option<ApiTokenScope>,
+}
+
+impl SkjeraSessionData {
+    pub(crate) fn is_admin(&self) -> bool {
+        self.role == EmployeeRole::Admin
+    }
+
+    /// Hides the fields of `employee` that this user isn't allowed to see.
+    pub(crate) fn redact(&self, employee: Employee) -> Employee {
+        employee.redacted(self.employee, self.role)
+    }
 }
 
 impl AuthUser for SkjeraSessionData {
@@ -25,27 +42,192 @@ impl AuthUser for SkjeraSessionData {
     }
 }
 
+/// The state of an ongoing Google login, kept in the session between redirecting the user to
+/// Google and Google redirecting back.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub(crate) struct GoogleLoginData {
+    pub(crate) csrf_token: CsrfToken,
+    pub(crate) nonce: Nonce,
+    pub(crate) pkce_verifier: String,
+}
+
+impl GoogleLoginData {
+    pub(crate) const SESSION_KEY: &'static str = "google_login";
+
+    pub(crate) fn new(
+        csrf_token: CsrfToken,
+        nonce: Nonce,
+        pkce_verifier: PkceCodeVerifier,
+    ) -> Self {
+        GoogleLoginData {
+            csrf_token,
+            nonce,
+            pkce_verifier: pkce_verifier.secret().to_string(),
+        }
+    }
+}
+
+/// The state of an ongoing Slack connect flow, kept in the session between redirecting the user
+/// to Slack and Slack redirecting back.
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub(crate) struct SlackConnectData {
-    csrf_token: CsrfToken,
+    pub(crate) csrf_token: CsrfToken,
     pub(crate) nonce: Nonce,
     pub(crate) pkce_verifier: String,
 }
 
-impl SkjeraSessionData {
-    pub(crate) fn with_slack_connect(
-        self,
+impl SlackConnectData {
+    pub(crate) const SESSION_KEY: &'static str = "slack_connect";
+
+    pub(crate) fn new(
         csrf_token: CsrfToken,
         nonce: Nonce,
         pkce_verifier: PkceCodeVerifier,
     ) -> Self {
-        SkjeraSessionData {
-            slack_connect: Some(SlackConnectData {
-                csrf_token,
-                nonce,
-                pkce_verifier: pkce_verifier.secret().to_string(),
-            }),
-            ..self
+        SlackConnectData {
+            csrf_token,
+            nonce,
+            pkce_verifier: pkce_verifier.secret().to_string(),
         }
     }
 }
+
+/// The state of an ongoing GitHub or GitLab connect flow.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub(crate) struct ForgeConnectData {
+    pub(crate) network: String,
+    pub(crate) csrf_token: CsrfToken,
+    pub(crate) pkce_verifier: String,
+}
+
+impl ForgeConnectData {
+    pub(crate) const SESSION_KEY: &'static str = "forge_connect";
+
+    pub(crate) fn new(
+        network: String,
+        csrf_token: CsrfToken,
+        pkce_verifier: PkceCodeVerifier,
+    ) -> Self {
+        ForgeConnectData {
+            network,
+            csrf_token,
+            pkce_verifier: pkce_verifier.secret().to_string(),
+        }
+    }
+}
+
+/// Stores the sessions in Postgres so they survive restarts and can be shared between replicas.
+#[derive(Clone, Debug)]
+pub(crate) struct PgSessionStore {
+    dao: Dao,
+}
+
+impl PgSessionStore {
+    pub(crate) fn new(dao: Dao) -> Self {
+        Self { dao }
+    }
+}
+
+/// Where axum-login keeps the id of the logged in user in the session, set on the auth layer.
+pub(crate) const AUTH_DATA_KEY: &str = "axum-login.data";
+
+/// The logged in employee of the session, if any.
+fn session_employee(record: &Record) -> Option<EmployeeId> {
+    record
+        .data
+        .get(AUTH_DATA_KEY)?
+        .get("user_id")?
+        .as_i64()
+        .map(EmployeeId)
+}
+
+fn backend_error(e: sqlx::Error) -> session_store::Error {
+    session_store::Error::Backend(e.to_string())
+}
+
+fn encode_data(record: &Record) -> session_store::Result<serde_json::Value> {
+    serde_json::to_value(&record.data).map_err(|e| session_store::Error::Encode(e.to_string()))
+}
+
+#[async_trait]
+impl SessionStore for PgSessionStore {
+    async fn create(&self, record: &mut Record) -> session_store::Result<()> {
+        let data = encode_data(record)?;
+
+        // Pick a new id on the unlikely collision with an existing session.
+        while !self
+            .dao
+            .insert_web_session(
+                record.id.to_string(),
+                session_employee(record),
+                data.clone(),
+                record.expiry_date,
+            )
+            .await
+            .map_err(backend_error)?
+        {
+            record.id = Id::default();
+        }
+
+        Ok(())
+    }
+
+    async fn save(&self, record: &Record) -> session_store::Result<()> {
+        self.dao
+            .save_web_session(
+                record.id.to_string(),
+                session_employee(record),
+                encode_data(record)?,
+                record.expiry_date,
+            )
+            .await
+            .map_err(backend_error)
+    }
+
+    async fn load(&self, session_id: &Id) -> session_store::Result<Option<Record>> {
+        let Some(session) = self
+            .dao
+            .web_session_by_id(session_id.to_string())
+            .await
+            .map_err(backend_error)?
+        else {
+            return Ok(None);
+        };
+
+        let id = session
+            .id
+            .parse::<Id>()
+            .map_err(|e| session_store::Error::Decode(e.to_string()))?;
+        let data = serde_json::from_value(session.data)
+            .map_err(|e| session_store::Error::Decode(e.to_string()))?;
+
+        Ok(Some(Record {
+            id,
+            data,
+            expiry_date: session.expiry_date,
+        }))
+    }
+
+    async fn delete(&self, session_id: &Id) -> session_store::Result<()> {
+        self.dao
+            .delete_web_session(session_id.to_string())
+            .await
+            .map(|_| ())
+            .map_err(backend_error)
+    }
+}
+
+#[async_trait]
+impl ExpiredDeletion for PgSessionStore {
+    async fn delete_expired(&self) -> session_store::Result<()> {
+        let count = self
+            .dao
+            .delete_expired_web_sessions()
+            .await
+            .map_err(backend_error)?;
+
+        tracing::debug!(count, "Deleted expired sessions");
+
+        Ok(())
+    }
+}
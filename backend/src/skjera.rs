@@ -1,84 +1,314 @@
 use crate::model::*;
-use crate::ServerImpl;
+use crate::session::SkjeraSessionData;
+use crate::web::api;
+use crate::{AppError, ServerImpl};
+use anyhow::Context;
 use async_trait::async_trait;
-use axum::extract::Host;
-use axum::http::Method;
-use axum_extra::extract::CookieJar;
-use skjera_api::apis::skjera::{ListEmployeesResponse, Skjera};
+use axum::http::{HeaderMap, Method};
+use axum_extra::extract::{CookieJar, Host};
+use chrono::{Datelike, NaiveDate};
+use skjera_api::apis::skjera::*;
+use skjera_api::apis::{ApiKeyAuthHeader, CookieAuthentication, ErrorHandler};
+use skjera_api::models;
 use std::collections::HashMap;
+use time::{Date, Month, OffsetDateTime};
+use tracing::info;
+
+impl AsRef<ServerImpl> for ServerImpl {
+    fn as_ref(&self) -> &ServerImpl {
+        self
+    }
+}
+
+impl ErrorHandler<AppError> for ServerImpl {}
+
+#[async_trait]
+impl CookieAuthentication for ServerImpl {
+    type Claims = SkjeraSessionData;
+
+    /// The session cookie has already been resolved by the auth layer, see [api::with_session].
+    async fn extract_claims_from_cookie(&self, _: &CookieJar, _: &str) -> Option<Self::Claims> {
+        api::session_user().filter(|user| user.api_token_scope.is_none())
+    }
+}
+
+#[async_trait]
+impl ApiKeyAuthHeader for ServerImpl {
+    type Claims = SkjeraSessionData;
+
+    /// The token has already been checked by [api::bearer_auth].
+    async fn extract_claims_from_header(&self, _: &HeaderMap, _: &str) -> Option<Self::Claims> {
+        api::session_user().filter(|user| user.api_token_scope.is_some())
+    }
+}
 
 #[allow(unused_variables)]
 #[async_trait]
-impl Skjera for ServerImpl {
-    async fn list_employees(
+impl Skjera<AppError> for ServerImpl {
+    type Claims = SkjeraSessionData;
+
+    async fn add_some_account(
+        &self,
+        method: &Method,
+        host: &Host,
+        cookies: &CookieJar,
+        claims: &Self::Claims,
+        body: &models::NewSomeAccount,
+    ) -> Result<AddSomeAccountResponse, AppError> {
+        let network = from_api_network(body.network);
+        let definition = network.definition();
+
+        if !definition.manual {
+            return Ok(AddSomeAccountResponse::Status400_BadRequest);
+        }
+
+        // The URL is accepted as the nick too, so older clients that send the URL still work
+        let input = body
+            .nick
+            .as_ref()
+            .or(body.url.as_ref())
+            .filter(|s| !s.trim().is_empty());
+
+        let Some(Ok(nick)) = input.map(|input| definition.parse_nick(input)) else {
+            return Ok(AddSomeAccountResponse::Status400_BadRequest);
+        };
+
+        info!(%network, nick, "Adding some account");
+
+        let some_account = self
+            .employee_dao
+            .add_some_account(
+                claims.employee,
+                network,
+                None,
+                false,
+                None,
+                None,
+                None,
+                Some(nick.clone()),
+                definition.profile_url(&nick),
+                None,
+            )
+            .await?;
+
+        Ok(AddSomeAccountResponse::Status201_TheCreatedAccount(
+            api_some_account(&some_account),
+        ))
+    }
+
+    async fn delete_some_account(
         &self,
-        method: Method,
-        host: Host,
-        cookies: CookieJar,
-    ) -> Result<ListEmployeesResponse, String> {
-        let employees = sqlx::query_as!(Employee, "SELECT id, email, name, dob FROM skjera.employee")
-            .fetch_all(&self.pool)
-            .await
-            .map_err(|e| {
-                eprintln!("Database query failed: {}", e);
-                e.to_string()
-            })?;
-
-        let employee_ids: Vec<i64> = employees.iter().map(|x| x.id.into()).collect();
-
-        let some_accounts = sqlx::query_as!(
-            SomeAccount,
-            "SELECT id, employee, network, nick, url FROM skjera.some_account WHERE employee = ANY ($1) ORDER BY id",
-            &employee_ids
-        )
-            .fetch_all(&self.pool)
-            .await;
-
-        if let Err(e) = some_accounts {
-            let msg = format!("An error occurred: {}", e);
-            eprintln!("{}", &msg);
-            return Err(msg);
+        method: &Method,
+        host: &Host,
+        cookies: &CookieJar,
+        claims: &Self::Claims,
+        path_params: &models::DeleteSomeAccountPathParams,
+    ) -> Result<DeleteSomeAccountResponse, AppError> {
+        let count = self
+            .employee_dao
+            .delete_some_account(path_params.some_account_id.into(), claims.employee)
+            .await?;
+
+        Ok(if count > 0 {
+            DeleteSomeAccountResponse::Status204_Deleted
+        } else {
+            DeleteSomeAccountResponse::Status404_NotFound
+        })
+    }
+
+    async fn get_employee(
+        &self,
+        method: &Method,
+        host: &Host,
+        cookies: &CookieJar,
+        claims: &Self::Claims,
+        path_params: &models::GetEmployeePathParams,
+    ) -> Result<GetEmployeeResponse, AppError> {
+        let Some(employee) = self
+            .employee_dao
+            .employee_by_id(path_params.employee_id.into())
+            .await?
+        else {
+            return Ok(GetEmployeeResponse::Status404_NotFound);
+        };
+        if !employee.active && !claims.is_admin() {
+            return Ok(GetEmployeeResponse::Status404_NotFound);
         }
+        let employee = claims.redact(employee);
+
+        let some_accounts = self
+            .employee_dao
+            .some_accounts_by_employee(employee.id)
+            .await?;
+
+        Ok(GetEmployeeResponse::Status200_TheEmployee(api_employee(
+            &employee,
+            &some_accounts,
+        )))
+    }
+
+    async fn get_me(
+        &self,
+        method: &Method,
+        host: &Host,
+        cookies: &CookieJar,
+        claims: &Self::Claims,
+    ) -> Result<GetMeResponse, AppError> {
+        let me = self
+            .employee_dao
+            .employee_by_id(claims.employee)
+            .await?
+            .context("error loading me")?;
+
+        let some_accounts = self.employee_dao.some_accounts_by_employee(me.id).await?;
+
+        Ok(GetMeResponse::Status200_TheLoggedInEmployee(api_employee(
+            &me,
+            &some_accounts,
+        )))
+    }
+
+    async fn list_employees(
+        &self,
+        method: &Method,
+        host: &Host,
+        cookies: &CookieJar,
+        claims: &Self::Claims,
+        query_params: &models::ListEmployeesQueryParams,
+    ) -> Result<ListEmployeesResponse, AppError> {
+        let per_page = query_params.per_page.unwrap_or(50) as i64;
+        let page = query_params.page.unwrap_or(1) as i64;
+
+        let query = EmployeeQuery {
+            text: query_params.q.clone().filter(|q| !q.trim().is_empty()),
+            network: query_params.network.map(from_api_network),
+            customer: query_params.customer.map(CustomerId),
+            include_inactive: false,
+            sort: query_params
+                .sort
+                .as_deref()
+                .and_then(|sort| sort.parse().ok())
+                .unwrap_or_default(),
+            limit: per_page,
+            offset: (page - 1).saturating_mul(per_page),
+        };
+
+        let today = OffsetDateTime::now_utc().date();
+        let (employees, total) = self.employee_dao.search_employees(&query, today).await?;
+        let employees: Vec<Employee> = employees.into_iter().map(|e| claims.redact(e)).collect();
+
+        let employee_ids: Vec<EmployeeId> = employees.iter().map(|e| e.id).collect();
+
+        let some_accounts = self
+            .employee_dao
+            .some_accounts_by_employees(&employee_ids)
+            .await?;
 
         let mut some_accounts_by_employee: HashMap<EmployeeId, Vec<SomeAccount>> = HashMap::new();
-        some_accounts.unwrap_or_default().iter().for_each(|a| {
+        some_accounts.into_iter().for_each(|a| {
             some_accounts_by_employee
                 .entry(a.employee)
-                .or_insert_with(Vec::new)
-                .push(a.clone())
+                .or_default()
+                .push(a)
         });
 
-        let empty: Vec<SomeAccount> = Vec::new();
         let employees = employees
             .iter()
-            .map(|e| Self::api_employee(e, some_accounts_by_employee.get(&e.id).unwrap_or(&empty)))
+            .map(|e| {
+                api_employee(
+                    e,
+                    some_accounts_by_employee
+                        .get(&e.id)
+                        .map(Vec::as_slice)
+                        .unwrap_or_default(),
+                )
+            })
             .collect();
 
-        Ok(ListEmployeesResponse::Status200_ListOfEmployees(employees))
+        Ok(ListEmployeesResponse::Status200_ListOfEmployees {
+            body: employees,
+            x_total_count: Some(total),
+        })
     }
 
-    fn api_employee(
-        e: &Employee,
-        some_accounts: &Vec<SomeAccount>,
-    ) -> skjera_api::models::Employee {
-        skjera_api::models::Employee {
-            // id: e.id,
-            name: e.name.clone(),
-            email: e.email.clone(),
-            nick: None,
-            some_accounts: some_accounts
-                .iter()
-                .map(ServerImpl::api_some_account)
-                .collect(),
-        }
+    async fn update_me(
+        &self,
+        method: &Method,
+        host: &Host,
+        cookies: &CookieJar,
+        claims: &Self::Claims,
+        body: &models::UpdateMeRequest,
+    ) -> Result<UpdateMeResponse, AppError> {
+        let mut me = self
+            .employee_dao
+            .employee_by_id(claims.employee)
+            .await?
+            .context("error loading me")?;
+
+        me.dob = body.dob.and_then(from_api_date);
+
+        let me = self.employee_dao.update(&me).await?;
+
+        let some_accounts = self.employee_dao.some_accounts_by_employee(me.id).await?;
+
+        Ok(UpdateMeResponse::Status200_TheUpdatedEmployee(
+            api_employee(&me, &some_accounts),
+        ))
     }
+}
 
-    fn api_some_account(s: &SomeAccount) -> skjera_api::models::SomeAccount {
-        skjera_api::models::SomeAccount {
-            id: s.id.into(),
-            network: s.network.to_string(),
-            nick: s.nick.clone().unwrap_or_default(),
-            url: s.url.clone().unwrap_or_default(),
-        }
+fn api_date(date: Date) -> Option<NaiveDate> {
+    NaiveDate::from_ymd_opt(date.year(), date.month() as u32, date.day() as u32)
+}
+
+fn from_api_date(date: NaiveDate) -> Option<Date> {
+    let month = Month::try_from(date.month() as u8).ok()?;
+
+    Date::from_calendar_date(date.year(), month, date.day() as u8).ok()
+}
+
+fn api_network(network: SomeNetwork) -> models::SomeNetwork {
+    match network {
+        SomeNetwork::Bluesky => models::SomeNetwork::Bluesky,
+        SomeNetwork::Github => models::SomeNetwork::Github,
+        SomeNetwork::Gitlab => models::SomeNetwork::Gitlab,
+        SomeNetwork::LinkedIn => models::SomeNetwork::LinkedIn,
+        SomeNetwork::Slack => models::SomeNetwork::Slack,
+        SomeNetwork::X => models::SomeNetwork::X,
+    }
+}
+
+fn from_api_network(network: models::SomeNetwork) -> SomeNetwork {
+    match network {
+        models::SomeNetwork::Bluesky => SomeNetwork::Bluesky,
+        models::SomeNetwork::Github => SomeNetwork::Github,
+        models::SomeNetwork::Gitlab => SomeNetwork::Gitlab,
+        models::SomeNetwork::LinkedIn => SomeNetwork::LinkedIn,
+        models::SomeNetwork::Slack => SomeNetwork::Slack,
+        models::SomeNetwork::X => SomeNetwork::X,
+    }
+}
+
+fn api_employee(e: &Employee, some_accounts: &[SomeAccount]) -> models::Employee {
+    models::Employee {
+        id: e.id.into(),
+        name: e.name.clone(),
+        email: e.email.clone(),
+        nick: None,
+        dob: e.dob.and_then(api_date),
+        some_accounts: some_accounts.iter().map(api_some_account).collect(),
+    }
+}
+
+fn api_some_account(s: &SomeAccount) -> models::SomeAccount {
+    models::SomeAccount {
+        id: s.id.into(),
+        network: api_network(s.network),
+        network_instance: s.network_instance.clone(),
+        authenticated: s.authenticated,
+        name: s.name.clone(),
+        nick: s.nick.clone(),
+        url: s.url.clone(),
+        avatar: s.avatar.clone(),
     }
 }
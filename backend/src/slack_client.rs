@@ -1,6 +1,8 @@
 use anyhow::anyhow;
-use reqwest::Client;
+use reqwest::header::RETRY_AFTER;
+use reqwest::{Client, Response, StatusCode};
 use serde::{Deserialize, Serialize};
+use std::time::Duration;
 use tracing::{info, info_span, instrument};
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -12,17 +14,73 @@ pub struct SlackResponse {
 #[derive(Debug, Deserialize, Serialize)]
 pub struct SlackUserProfile {
     pub display_name: String,
+    #[serde(default)]
+    pub real_name: String,
+    pub image_192: Option<String>,
+    pub image_512: Option<String>,
+    /// Only included with the `users:read.email` scope.
+    pub email: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SlackUsersInfoResponse {
+    ok: bool,
+    error: Option<String>,
+    user: Option<SlackUser>,
+}
+
+/// A user as returned by `users.info` and `users.list`.
+#[derive(Debug, Deserialize)]
+pub struct SlackUser {
+    pub id: String,
+    #[serde(default)]
+    pub is_bot: bool,
+    /// Set when the user has been deactivated.
+    #[serde(default)]
+    pub deleted: bool,
+    /// Set for multi-channel guests.
+    #[serde(default)]
+    pub is_restricted: bool,
+    /// Set for single-channel guests.
+    #[serde(default)]
+    pub is_ultra_restricted: bool,
+    /// The home workspace of the user, another one for users that are shared through Slack
+    /// Connect.
+    pub team_id: Option<String>,
+    pub profile: SlackUserProfile,
+}
+
+#[derive(Debug, Deserialize)]
+struct SlackUsersListResponse {
+    ok: bool,
+    error: Option<String>,
+    #[serde(default)]
+    members: Vec<SlackUser>,
+    response_metadata: Option<SlackResponseMetadata>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SlackResponseMetadata {
+    next_cursor: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SlackAuthTestResponse {
+    ok: bool,
+    error: Option<String>,
+    team_id: Option<String>,
 }
 
 #[instrument(skip_all)]
 pub(crate) async fn user_profile_get(
     http_client: &Client,
+    api_url: &str,
     token: &String,
 ) -> anyhow::Result<SlackUserProfile> {
     let response = info_span!("users.profile.get")
         .in_scope(|| async {
             http_client
-                .get("https://slack.com/api/users.profile.get")
+                .get(format!("{}/users.profile.get", api_url))
                 .bearer_auth(token)
                 .send()
                 .await
@@ -47,3 +105,121 @@ pub(crate) async fn user_profile_get(
 
     Ok(response)
 }
+
+/// Slack answered with a 429, the request can be made again after `retry_after`.
+#[derive(Debug, thiserror::Error)]
+#[error("rate limited by Slack, retry after {retry_after:?}")]
+pub(crate) struct RateLimited {
+    pub(crate) retry_after: Duration,
+}
+
+fn check_rate_limited(response: &Response) -> Result<(), RateLimited> {
+    if response.status() != StatusCode::TOO_MANY_REQUESTS {
+        return Ok(());
+    }
+
+    // Slack always sends it, a minute is what it usually asks for otherwise
+    let retry_after = response
+        .headers()
+        .get(RETRY_AFTER)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.trim().parse().ok())
+        .unwrap_or(60);
+
+    Err(RateLimited {
+        retry_after: Duration::from_secs(retry_after),
+    })
+}
+
+/// Looks up a user in the token's workspace, returns None if the user doesn't exist.
+#[instrument(skip(http_client, token))]
+pub(crate) async fn users_info(
+    http_client: &Client,
+    api_url: &str,
+    token: &str,
+    user: &str,
+) -> anyhow::Result<Option<SlackUser>> {
+    let response = http_client
+        .get(format!("{}/users.info", api_url))
+        .bearer_auth(token)
+        .query(&[("user", user)])
+        .send()
+        .await?;
+
+    check_rate_limited(&response)?;
+
+    let response: SlackUsersInfoResponse = response.error_for_status()?.json().await?;
+
+    match (response.ok, response.error.as_deref()) {
+        (true, _) => Ok(Some(response.user.ok_or_else(|| anyhow!("bad response"))?)),
+        (false, Some("user_not_found")) => Ok(None),
+        (false, error) => Err(anyhow!("users.info failed: {}", error.unwrap_or("unknown"))),
+    }
+}
+
+/// Returns all the users in the token's workspace, including bots and deactivated users.
+#[instrument(skip(http_client, token))]
+pub(crate) async fn users_list(
+    http_client: &Client,
+    api_url: &str,
+    token: &str,
+) -> anyhow::Result<Vec<SlackUser>> {
+    let mut users = Vec::new();
+    let mut cursor = String::new();
+
+    loop {
+        let response = http_client
+            .get(format!("{}/users.list", api_url))
+            .bearer_auth(token)
+            .query(&[("limit", "200"), ("cursor", cursor.as_str())])
+            .send()
+            .await?;
+
+        check_rate_limited(&response)?;
+
+        let response: SlackUsersListResponse = response.error_for_status()?.json().await?;
+
+        if !response.ok {
+            return Err(anyhow!(
+                "users.list failed: {}",
+                response.error.as_deref().unwrap_or("unknown")
+            ));
+        }
+
+        users.extend(response.members);
+
+        cursor = response
+            .response_metadata
+            .and_then(|m| m.next_cursor)
+            .unwrap_or_default();
+
+        if cursor.is_empty() {
+            return Ok(users);
+        }
+    }
+}
+
+/// Returns the ID of the token's workspace.
+#[instrument(skip(http_client, token))]
+pub(crate) async fn auth_test(
+    http_client: &Client,
+    api_url: &str,
+    token: &str,
+) -> anyhow::Result<String> {
+    let response: SlackAuthTestResponse = http_client
+        .post(format!("{}/auth.test", api_url))
+        .bearer_auth(token)
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+
+    match (response.ok, response.team_id) {
+        (true, Some(team_id)) => Ok(team_id),
+        _ => Err(anyhow!(
+            "auth.test failed: {}",
+            response.error.as_deref().unwrap_or("unknown")
+        )),
+    }
+}
@@ -1,7 +1,7 @@
 use ractor::MessagingErr::{ChannelClosed, InvalidActorType, SendErr};
 use ractor::{Actor, ActorProcessingErr, ActorRef, MessagingErr, RpcReplyPort};
 use slack_morphism::events::SlackInteractionBlockActionsEvent;
-use slack_morphism::prelude::SlackInteractionActionInfo;
+use slack_morphism::prelude::{SlackInteractionActionInfo, SlackUserId};
 use slack_morphism::SlackActionId;
 use std::collections::HashMap;
 use std::fmt::{Display, Formatter};
@@ -9,7 +9,11 @@ use tracing::{info, warn};
 use uuid::Uuid;
 
 pub trait InteractionSubscriber: Send + 'static {
-    fn on_interaction(&self, event: SlackInteractionActionInfo) -> Result<(), MessagingErr<()>>;
+    fn on_interaction(
+        &self,
+        user: Option<SlackUserId>,
+        event: SlackInteractionActionInfo,
+    ) -> Result<(), MessagingErr<()>>;
 }
 
 pub fn map_err<T>(err: MessagingErr<T>) -> MessagingErr<()> {
@@ -20,11 +24,17 @@ pub fn map_err<T>(err: MessagingErr<T>) -> MessagingErr<()> {
     }
 }
 
+#[allow(clippy::large_enum_variant)]
 pub enum SlackInteractionServerMsg {
     AddInteraction(
         Box<dyn InteractionSubscriber>,
         RpcReplyPort<SlackInteractionId>,
     ),
+    /// Registers a subscriber for an interaction id that was handed out earlier, typically before
+    /// a restart.
+    RestoreInteraction(SlackInteractionId, Box<dyn InteractionSubscriber>),
+    /// Unregisters the subscriber once the interaction can't happen anymore.
+    RemoveInteraction(SlackInteractionId),
     OnInteractionActions(SlackInteractionBlockActionsEvent),
 }
 
@@ -80,6 +90,20 @@ impl Actor for SlackInteractionServer {
 
                 Ok(())
             }
+            SlackInteractionServerMsg::RestoreInteraction(interaction_id, subscription) => {
+                info!("Restoring interaction {}", interaction_id);
+
+                state.handlers.insert(interaction_id, subscription);
+
+                Ok(())
+            }
+            SlackInteractionServerMsg::RemoveInteraction(interaction_id) => {
+                info!("Removing interaction {}", interaction_id);
+
+                state.handlers.remove(&interaction_id);
+
+                Ok(())
+            }
             SlackInteractionServerMsg::OnInteractionActions(event) => {
                 info!("Handling interaction action");
 
@@ -87,7 +111,10 @@ impl Actor for SlackInteractionServer {
                     if let Ok(interaction_id) = action.clone().action_id.try_into() {
                         match state.handlers.get(&interaction_id) {
                             Some(recipient) => {
-                                let res = recipient.on_interaction(action.clone());
+                                let res = recipient.on_interaction(
+                                    event.user.as_ref().map(|u| u.id.clone()),
+                                    action.clone(),
+                                );
 
                                 match res {
                                     Ok(_) => (),
@@ -0,0 +1,362 @@
+use crate::model::*;
+use crate::web::html::{format_timestamp, parse_date_input, UnauthorizedTemplate};
+use crate::{AppError, AuthSession, ServerImpl};
+use anyhow::{anyhow, Context};
+use askama_axum::Template;
+use axum::extract::{Path, Query, Request, State};
+use axum::http::header::{CONTENT_DISPOSITION, CONTENT_TYPE};
+use axum::middleware::Next;
+use axum::response::{Html, IntoResponse, Redirect, Response};
+use axum::Form;
+use axum_extra::extract::Multipart;
+use axum_login::AuthzBackend;
+use http::StatusCode;
+use serde::Deserialize;
+use time::OffsetDateTime;
+use tracing::info;
+
+/// Only lets users with the admin permission through, everyone else gets the unauthorized page.
+pub(crate) async fn admin_required(session: AuthSession, request: Request, next: Next) -> Response {
+    let is_admin = match &session.user {
+        Some(user) => session
+            .backend
+            .has_perm(user, EmployeeRole::Admin)
+            .await
+            .unwrap_or(false),
+        None => false,
+    };
+
+    match is_admin {
+        true => next.run(request).await,
+        false => {
+            let template = UnauthorizedTemplate {};
+
+            match template.render() {
+                Ok(html) => (StatusCode::FORBIDDEN, Html(html)).into_response(),
+                Err(_) => StatusCode::FORBIDDEN.into_response(),
+            }
+        }
+    }
+}
+
+#[derive(Template)]
+#[template(path = "admin-login-access.html")]
+struct LoginAccessTemplate {
+    login_accesses: Vec<LoginAccess>,
+}
+
+impl LoginAccessTemplate {
+    pub fn timestamp(timestamp: &OffsetDateTime) -> String {
+        format_timestamp(timestamp)
+    }
+}
+
+#[tracing::instrument(skip(app))]
+pub async fn login_access(State(app): State<ServerImpl>) -> Result<Html<String>, AppError> {
+    let login_accesses = app.employee_dao.login_accesses().await?;
+
+    let template = LoginAccessTemplate { login_accesses };
+
+    Ok(Html(template.render()?))
+}
+
+#[derive(Deserialize, Debug)]
+pub(crate) struct AddLoginAccessForm {
+    email: String,
+    status: LoginAccessStatus,
+}
+
+#[tracing::instrument(skip(app))]
+pub async fn add_login_access(
+    State(app): State<ServerImpl>,
+    Form(input): Form<AddLoginAccessForm>,
+) -> Result<Redirect, AppError> {
+    let email = input.email.trim().to_string();
+
+    if !email.is_empty() {
+        info!(email, status = ?input.status, "Adding login access");
+
+        app.employee_dao
+            .decide_login_access(email, input.status)
+            .await?;
+    }
+
+    Ok(Redirect::to("/admin/login-access"))
+}
+
+#[derive(Deserialize, Debug)]
+pub(crate) struct LoginAccessForm {
+    status: LoginAccessStatus,
+}
+
+#[tracing::instrument(skip(app))]
+pub async fn update_login_access(
+    State(app): State<ServerImpl>,
+    Path(login_access_id): Path<LoginAccessId>,
+    Form(input): Form<LoginAccessForm>,
+) -> Result<Redirect, AppError> {
+    info!(status = ?input.status, "Updating login access");
+
+    app.employee_dao
+        .update_login_access(login_access_id, input.status)
+        .await?;
+
+    Ok(Redirect::to("/admin/login-access"))
+}
+
+#[tracing::instrument(skip(app))]
+pub async fn delete_login_access(
+    State(app): State<ServerImpl>,
+    Path(login_access_id): Path<LoginAccessId>,
+) -> Result<Redirect, AppError> {
+    info!("Deleting login access");
+
+    app.employee_dao
+        .delete_login_access(login_access_id)
+        .await?;
+
+    Ok(Redirect::to("/admin/login-access"))
+}
+
+#[derive(Template)]
+#[template(path = "admin-employee.html")]
+struct EmployeeTemplate {
+    employee: Employee,
+    is_self: bool,
+    /// False if the employee has hidden the date of birth, even from admins.
+    dob_visible: bool,
+}
+
+#[tracing::instrument(skip(app, session))]
+pub async fn employee(
+    State(app): State<ServerImpl>,
+    session: AuthSession,
+    Path(employee_id): Path<EmployeeId>,
+) -> Result<Html<String>, AppError> {
+    let employee = app
+        .employee_dao
+        .employee_by_id(employee_id)
+        .await?
+        .context("error loading employee")?;
+
+    let user = session.user.unwrap();
+    let is_self = user.employee == employee.id;
+    let dob_visible = employee.dob_visibility.is_visible(is_self, true);
+    let employee = user.redact(employee);
+
+    let template = EmployeeTemplate {
+        employee,
+        is_self,
+        dob_visible,
+    };
+
+    Ok(Html(template.render()?))
+}
+
+#[derive(Deserialize, Debug)]
+pub(crate) struct EmployeeForm {
+    name: String,
+    email: String,
+    /// Missing when the date of birth is hidden from admins.
+    dob: Option<String>,
+    role: EmployeeRole,
+}
+
+#[tracing::instrument(skip(app, session))]
+pub async fn update_employee(
+    State(app): State<ServerImpl>,
+    session: AuthSession,
+    Path(employee_id): Path<EmployeeId>,
+    Form(input): Form<EmployeeForm>,
+) -> Result<Redirect, AppError> {
+    let mut employee = app
+        .employee_dao
+        .employee_by_id(employee_id)
+        .await?
+        .context("error loading employee")?;
+
+    let name = input.name.trim();
+    if !name.is_empty() {
+        employee.name = name.to_string();
+    }
+    let email = input.email.trim();
+    if !email.is_empty() {
+        employee.email = email.to_string();
+    }
+    let is_self = session
+        .user
+        .is_some_and(|user| user.employee == employee.id);
+    if employee.dob_visibility.is_visible(is_self, true) {
+        if let Some(dob) = &input.dob {
+            employee.dob = parse_date_input(dob)?;
+        }
+    }
+
+    info!(employee = employee.id.0, "Updating employee");
+
+    app.employee_dao.update(&employee).await?;
+
+    // Admins can't demote themselves, so there is always at least one admin left.
+    if input.role != employee.role && !is_self {
+        info!(employee = employee.id.0, role = ?input.role, "Changing role");
+
+        app.employee_dao
+            .update_employee_role(employee.id, input.role)
+            .await?;
+    }
+
+    Ok(Redirect::to(&format!("/admin/employee/{}", employee_id)))
+}
+
+#[tracing::instrument(skip(app, session))]
+pub async fn deactivate_employee(
+    State(app): State<ServerImpl>,
+    session: AuthSession,
+    Path(employee_id): Path<EmployeeId>,
+) -> Result<Redirect, AppError> {
+    if session
+        .user
+        .is_some_and(|user| user.employee == employee_id)
+    {
+        return Err(AppError::Anyhow(anyhow!(
+            "admins can't deactivate themselves"
+        )));
+    }
+
+    info!(employee = employee_id.0, "Deactivating employee");
+
+    app.employee_dao.deactivate_employee(employee_id).await?;
+
+    Ok(Redirect::to(&format!("/admin/employee/{}", employee_id)))
+}
+
+#[tracing::instrument(skip(app))]
+pub async fn reactivate_employee(
+    State(app): State<ServerImpl>,
+    Path(employee_id): Path<EmployeeId>,
+) -> Result<Redirect, AppError> {
+    info!(employee = employee_id.0, "Reactivating employee");
+
+    app.employee_dao.reactivate_employee(employee_id).await?;
+
+    Ok(Redirect::to(&format!("/admin/employee/{}", employee_id)))
+}
+
+#[derive(Template)]
+#[template(path = "admin-directory.html")]
+struct DirectoryTemplate {
+    report: Option<ImportReport>,
+    error: Option<String>,
+}
+
+#[tracing::instrument]
+pub async fn directory() -> Result<Html<String>, AppError> {
+    let template = DirectoryTemplate {
+        report: None,
+        error: None,
+    };
+
+    Ok(Html(template.render()?))
+}
+
+#[derive(Deserialize, Debug)]
+pub(crate) struct ExportDirectoryQuery {
+    format: DirectoryFormat,
+}
+
+#[tracing::instrument(skip(app))]
+pub async fn export_directory(
+    State(app): State<ServerImpl>,
+    Query(query): Query<ExportDirectoryQuery>,
+) -> Result<Response, AppError> {
+    let employees = app.employee_dao.all_employees().await?;
+    let ids: Vec<_> = employees.iter().map(|e| e.id).collect();
+    let accounts = app.employee_dao.some_accounts_by_employees(&ids).await?;
+
+    let body = write_directory(query.format, &directory_employees(employees, &accounts))?;
+
+    Ok((
+        [
+            (CONTENT_TYPE, query.format.content_type().to_string()),
+            (
+                CONTENT_DISPOSITION,
+                format!(
+                    "attachment; filename=\"skjera-employees.{}\"",
+                    query.format.extension()
+                ),
+            ),
+        ],
+        body,
+    )
+        .into_response())
+}
+
+/// Takes a multipart form with the file in `file`. The format is given in `format`, or it is
+/// guessed from the file name.
+#[tracing::instrument(skip(app, multipart))]
+pub async fn import_directory(
+    State(app): State<ServerImpl>,
+    mut multipart: Multipart,
+) -> Result<Html<String>, AppError> {
+    let mut format = None;
+    let mut file = None;
+
+    while let Some(field) = multipart
+        .next_field()
+        .await
+        .context("error reading the form")?
+    {
+        match field.name() {
+            Some("format") => {
+                format = field
+                    .text()
+                    .await
+                    .context("error reading the form")?
+                    .parse()
+                    .ok()
+            }
+            Some("file") => {
+                let name = field.file_name().map(std::path::PathBuf::from);
+                let data = field.bytes().await.context("error reading the file")?;
+                file = Some((name, data))
+            }
+            _ => {}
+        }
+    }
+
+    let directory = file
+        .ok_or_else(|| "No file was uploaded.".to_string())
+        .and_then(|(name, data)| {
+            let format = format
+                .or_else(|| name.and_then(|name| DirectoryFormat::from_path(&name)))
+                .ok_or_else(|| "Select the format of the file.".to_string())?;
+
+            read_directory(format, &data).map_err(|e| format!("Could not read the file: {:#}.", e))
+        });
+
+    let template = match directory {
+        Ok((employees, errors)) => {
+            let report =
+                crate::model::import_directory(&app.employee_dao, employees, errors).await?;
+
+            info!(
+                created = report.created,
+                updated = report.updated,
+                unchanged = report.unchanged,
+                errors = report.errors.len(),
+                "Imported directory"
+            );
+
+            DirectoryTemplate {
+                report: Some(report),
+                error: None,
+            }
+        }
+        Err(error) => DirectoryTemplate {
+            report: None,
+            error: Some(error),
+        },
+    };
+
+    Ok(Html(template.render()?))
+}
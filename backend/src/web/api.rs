@@ -0,0 +1,74 @@
+use crate::model::ApiTokenScope;
+use crate::session::SkjeraSessionData;
+use crate::web::oauth::SkjeraAuthnCredentials;
+use crate::AuthSession;
+use axum::extract::Request;
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use http::header::AUTHORIZATION;
+use http::StatusCode;
+use tracing::warn;
+
+tokio::task_local! {
+    static API_USER: Option<SkjeraSessionData>;
+}
+
+/// Makes the user that the auth layer resolved from the session cookie available to the
+/// generated API handlers, which only get to see the raw cookies.
+pub(crate) async fn with_session(session: AuthSession, request: Request, next: Next) -> Response {
+    API_USER.scope(session.user, next.run(request)).await
+}
+
+/// The user of the API request that is currently being handled, if any.
+pub(crate) fn session_user() -> Option<SkjeraSessionData> {
+    API_USER.try_with(|user| user.clone()).ok().flatten()
+}
+
+/// Authenticates requests with an API token in a Bearer authorization header. The user is put
+/// in the request's [AuthSession] without logging in, so no session is created and the rest of
+/// the application can't tell the difference. Read-only tokens can only do safe requests, and
+/// calendar tokens can't be used at all.
+pub(crate) async fn bearer_auth(mut request: Request, next: Next) -> Response {
+    let token = request
+        .headers()
+        .get(AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .map(|token| token.trim().to_string());
+
+    let Some(token) = token else {
+        return next.run(request).await;
+    };
+
+    let Some(mut auth_session) = request.extensions().get::<AuthSession>().cloned() else {
+        warn!("No auth session in request, is the auth layer missing?");
+        return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+    };
+
+    let user = match auth_session
+        .authenticate(SkjeraAuthnCredentials::ApiToken(token))
+        .await
+    {
+        Ok(Some(user)) => user,
+        Ok(None) => return StatusCode::UNAUTHORIZED.into_response(),
+        Err(e) => {
+            warn!("Could not authenticate API token: {}", e);
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+
+    let allowed = match user.api_token_scope {
+        Some(ApiTokenScope::Calendar) => false,
+        Some(scope) => !scope.is_read_only() || request.method().is_safe(),
+        None => true,
+    };
+
+    if !allowed {
+        return StatusCode::FORBIDDEN.into_response();
+    }
+
+    auth_session.user = Some(user);
+    request.extensions_mut().insert(auth_session);
+
+    next.run(request).await
+}
@@ -0,0 +1,268 @@
+use crate::model::*;
+use crate::{AppError, ServerImpl};
+use anyhow::{anyhow, Context};
+use axum::extract::{Path, Query, State};
+use axum::http::header::{CACHE_CONTROL, CONTENT_TYPE, ETAG, IF_NONE_MATCH};
+use axum::http::{HeaderMap, HeaderValue, StatusCode};
+use axum::response::{IntoResponse, Response};
+use image::imageops::FilterType;
+use image::ImageFormat;
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use std::io::Cursor;
+use time::OffsetDateTime;
+use tracing::{instrument, warn};
+
+/// The sizes that avatars are resized to, a request for any other size gets the next size up.
+pub(crate) const AVATAR_SIZES: [u32; 4] = [32, 64, 128, 256];
+const DEFAULT_AVATAR_SIZE: u32 = 64;
+
+/// How long a fetched avatar is used before it is revalidated with the upstream server.
+const AVATAR_MAX_AGE: time::Duration = time::Duration::hours(24);
+const AVATAR_MAX_BYTES: usize = 5 * 1024 * 1024;
+const AVATAR_FETCH_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+
+const INITIALS_COLORS: [&str; 8] = [
+    "#1e88e5", "#43a047", "#e53935", "#8e24aa", "#fb8c00", "#00897b", "#5e35b1", "#6d4c41",
+];
+
+#[derive(Deserialize, Debug)]
+pub(crate) struct AvatarQuery {
+    size: Option<u32>,
+}
+
+/// Picks the standard size to serve for the requested size.
+pub(crate) fn avatar_size(requested: Option<u32>) -> u32 {
+    let requested = requested.unwrap_or(DEFAULT_AVATAR_SIZE);
+
+    AVATAR_SIZES
+        .into_iter()
+        .find(|size| *size >= requested)
+        .unwrap_or(AVATAR_SIZES[AVATAR_SIZES.len() - 1])
+}
+
+/// Picks the avatar to show for an employee, accounts that the employee has authenticated with
+/// are preferred over those that were added by hand.
+///
+/// The `network_avatar` is the image of the network instance, like the Slack workspace, and is
+/// never used.
+pub(crate) fn best_avatar_url(accounts: &[SomeAccount]) -> Option<&str> {
+    let mut accounts: Vec<_> = accounts
+        .iter()
+        .filter(|a| {
+            a.avatar
+                .as_deref()
+                .is_some_and(|url| url.starts_with("https://") || url.starts_with("http://"))
+        })
+        .collect();
+
+    accounts.sort_by_key(|a| (!a.authenticated, a.id.0));
+
+    accounts.first().and_then(|a| a.avatar.as_deref())
+}
+
+/// The first letter of the first and last names. Only alphanumeric characters are used so the
+/// result can be put in the SVG without escaping.
+pub(crate) fn initials(name: &str) -> String {
+    let words: Vec<&str> = name
+        .split_whitespace()
+        .filter(|w| w.starts_with(char::is_alphanumeric))
+        .collect();
+
+    let initials: String = match words.as_slice() {
+        [] => return "?".to_string(),
+        [word] => word.chars().take(1).collect(),
+        [first, .., last] => first.chars().take(1).chain(last.chars().take(1)).collect(),
+    };
+
+    initials.to_uppercase()
+}
+
+/// The avatar that is used when the employee doesn't have one, or it can't be fetched.
+pub(crate) fn initials_svg(employee: EmployeeId, name: &str, size: u32) -> String {
+    let color = INITIALS_COLORS[employee.0.rem_euclid(INITIALS_COLORS.len() as i64) as usize];
+
+    format!(
+        r##"<svg xmlns="http://www.w3.org/2000/svg" width="{size}" height="{size}" viewBox="0 0 100 100"><rect width="100" height="100" fill="{color}"/><text x="50" y="50" dy=".35em" text-anchor="middle" font-family="sans-serif" font-size="40" fill="#ffffff">{initials}</text></svg>"##,
+        initials = initials(name),
+    )
+}
+
+/// Decodes the image and resizes it to each of the [AVATAR_SIZES], encoded as PNG.
+pub(crate) fn resize_avatar(data: &[u8]) -> anyhow::Result<Vec<(i32, Vec<u8>)>> {
+    let image = image::load_from_memory(data).context("could not decode avatar")?;
+
+    AVATAR_SIZES
+        .into_iter()
+        .map(|size| {
+            let mut png = Vec::new();
+            image
+                .resize_to_fill(size, size, FilterType::Lanczos3)
+                .write_to(&mut Cursor::new(&mut png), ImageFormat::Png)
+                .context("could not encode avatar")?;
+            Ok((size as i32, png))
+        })
+        .collect()
+}
+
+/// Checks if the client already has the representation with the given ETag.
+pub(crate) fn etag_matches(if_none_match: Option<&HeaderValue>, etag: &str) -> bool {
+    let Some(if_none_match) = if_none_match.and_then(|h| h.to_str().ok()) else {
+        return false;
+    };
+
+    if_none_match
+        .split(',')
+        .map(|tag| tag.trim())
+        .any(|tag| tag == "*" || tag.trim_start_matches("W/") == etag)
+}
+
+enum Fetched {
+    NotModified,
+    Image { etag: Option<String>, data: Vec<u8> },
+}
+
+async fn fetch_avatar(
+    client: &reqwest::Client,
+    url: &str,
+    etag: Option<&str>,
+) -> anyhow::Result<Fetched> {
+    let mut request = client.get(url).timeout(AVATAR_FETCH_TIMEOUT);
+    if let Some(etag) = etag {
+        request = request.header(reqwest::header::IF_NONE_MATCH, etag);
+    }
+
+    let response = request.send().await?;
+
+    if response.status() == reqwest::StatusCode::NOT_MODIFIED {
+        return Ok(Fetched::NotModified);
+    }
+
+    let mut response = response.error_for_status()?;
+
+    let etag = response
+        .headers()
+        .get(reqwest::header::ETAG)
+        .and_then(|etag| etag.to_str().ok())
+        .map(|etag| etag.to_string());
+
+    let mut data = Vec::new();
+    while let Some(chunk) = response.chunk().await? {
+        data.extend_from_slice(&chunk);
+        if data.len() > AVATAR_MAX_BYTES {
+            return Err(anyhow!("avatar is larger than {} bytes", AVATAR_MAX_BYTES));
+        }
+    }
+
+    Ok(Fetched::Image { etag, data })
+}
+
+/// Returns the avatar at `url` in the given size, fetching it if it isn't cached or the cached
+/// copy is too old. If the upstream server can't be reached the stale copy is used.
+pub(crate) async fn cached_avatar(
+    app: &ServerImpl,
+    url: &str,
+    size: u32,
+) -> Result<Option<Vec<u8>>, AppError> {
+    let dao = &app.employee_dao;
+    let size = size as i32;
+
+    let cached = dao.avatar_by_url(url).await?;
+
+    if let Some(avatar) = &cached {
+        if OffsetDateTime::now_utc() - avatar.fetched_at < AVATAR_MAX_AGE {
+            return Ok(dao.avatar_image(avatar.id, size).await?);
+        }
+    }
+
+    let etag = cached.as_ref().and_then(|avatar| avatar.etag.as_deref());
+
+    let images = match fetch_avatar(&app.http_client, url, etag).await {
+        Ok(Fetched::NotModified) => match &cached {
+            Some(avatar) => {
+                dao.touch_avatar(avatar.id).await?;
+                return Ok(dao.avatar_image(avatar.id, size).await?);
+            }
+            None => Err(anyhow!("not modified, but there is no cached avatar")),
+        },
+        // Decoding and resizing is too heavy for the async runtime
+        Ok(Fetched::Image { etag, data }) => {
+            tokio::task::spawn_blocking(move || resize_avatar(&data))
+                .await
+                .map_err(anyhow::Error::from)
+                .and_then(|images| images)
+                .map(|images| (etag, images))
+        }
+        Err(e) => Err(e),
+    };
+
+    match images {
+        Ok((etag, images)) => {
+            let image = images
+                .iter()
+                .find(|(s, _)| *s == size)
+                .map(|(_, data)| data.clone());
+            dao.save_avatar(url, etag, images).await?;
+            Ok(image)
+        }
+        Err(e) => {
+            warn!("could not fetch avatar {}: {:#}", url, e);
+
+            match &cached {
+                Some(avatar) => Ok(dao.avatar_image(avatar.id, size).await?),
+                None => Ok(None),
+            }
+        }
+    }
+}
+
+/// Serves the avatar of an employee, or an image with the employee's initials if there is none.
+#[instrument(skip(app, headers))]
+pub async fn avatar(
+    State(app): State<ServerImpl>,
+    Path(employee_id): Path<EmployeeId>,
+    Query(query): Query<AvatarQuery>,
+    headers: HeaderMap,
+) -> Result<Response, AppError> {
+    let Some(employee) = app.employee_dao.employee_by_id(employee_id).await? else {
+        return Ok(StatusCode::NOT_FOUND.into_response());
+    };
+
+    let size = avatar_size(query.size);
+
+    let accounts = app
+        .employee_dao
+        .some_accounts_by_employee(employee_id)
+        .await?;
+
+    let image = match best_avatar_url(&accounts) {
+        Some(url) => cached_avatar(&app, url, size).await?,
+        None => None,
+    };
+
+    let (content_type, body) = match image {
+        Some(png) => ("image/png", png),
+        None => (
+            "image/svg+xml",
+            initials_svg(employee.id, &employee.name, size).into_bytes(),
+        ),
+    };
+
+    let etag = format!("\"{}\"", hex::encode(Sha256::digest(&body)));
+
+    let headers_out = [
+        (ETAG, etag.clone()),
+        (CACHE_CONTROL, "private, max-age=3600".to_string()),
+    ];
+
+    if etag_matches(headers.get(IF_NONE_MATCH), &etag) {
+        return Ok((StatusCode::NOT_MODIFIED, headers_out).into_response());
+    }
+
+    Ok((
+        headers_out,
+        [(CONTENT_TYPE, content_type.to_string())],
+        body,
+    )
+        .into_response())
+}
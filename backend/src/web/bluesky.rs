@@ -0,0 +1,340 @@
+use crate::model::*;
+use crate::{AppError, AuthSession, ServerImpl};
+use anyhow::{anyhow, Result};
+use askama_axum::Template;
+use axum::extract::{Path, State};
+use axum::response::{Html, IntoResponse, Redirect, Response};
+use hickory_resolver::config::{ResolverConfig, ResolverOpts};
+use hickory_resolver::TokioAsyncResolver;
+use serde::Deserialize;
+use tracing::{debug, info, instrument, warn};
+
+pub(crate) const BLUESKY_APPVIEW_URL: &str = "https://public.api.bsky.app";
+
+/// How many of the latest posts are searched for the challenge.
+const VERIFICATION_POSTS: usize = 20;
+
+/// The Bluesky profile that a handle was verified to belong to.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct BlueskyProfile {
+    pub(crate) did: String,
+    pub(crate) display_name: Option<String>,
+    pub(crate) avatar: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct ResolveHandleOutput {
+    did: String,
+}
+
+#[derive(Deserialize)]
+struct AuthorFeed {
+    feed: Vec<FeedViewPost>,
+}
+
+#[derive(Deserialize)]
+struct FeedViewPost {
+    post: PostView,
+}
+
+#[derive(Deserialize)]
+struct PostView {
+    author: ProfileViewBasic,
+    record: PostRecord,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ProfileViewBasic {
+    did: String,
+    handle: String,
+    display_name: Option<String>,
+    avatar: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct PostRecord {
+    #[serde(default)]
+    text: String,
+}
+
+/// Verifies that an employee owns a Bluesky account.
+///
+/// The handle is resolved to a DID through DNS as described in
+/// <https://atproto.com/specs/handle>, then the account's latest posts are searched for the
+/// challenge through the public AppView.
+///
+/// Handles that are verified over HTTPS are resolved by the AppView too. The handle is chosen by
+/// the employee, so fetching it ourselves would let anyone make requests to hosts on our
+/// network.
+#[derive(Clone)]
+pub(crate) struct BlueskyVerifier {
+    http_client: reqwest::Client,
+    /// None disables the DNS lookup, so only the AppView is asked.
+    dns: Option<TokioAsyncResolver>,
+    appview_url: String,
+}
+
+impl BlueskyVerifier {
+    pub(crate) fn new(http_client: reqwest::Client, appview_url: String) -> BlueskyVerifier {
+        let dns = TokioAsyncResolver::tokio_from_system_conf().unwrap_or_else(|e| {
+            warn!("could not read the system's DNS configuration: {}", e);
+            TokioAsyncResolver::tokio(ResolverConfig::default(), ResolverOpts::default())
+        });
+
+        BlueskyVerifier {
+            http_client,
+            dns: Some(dns),
+            appview_url,
+        }
+    }
+
+    /// Creates a verifier that only resolves handles through the AppView.
+    #[cfg(test)]
+    pub(crate) fn without_dns(
+        http_client: reqwest::Client,
+        appview_url: String,
+    ) -> BlueskyVerifier {
+        BlueskyVerifier {
+            http_client,
+            dns: None,
+            appview_url,
+        }
+    }
+
+    /// Resolves the handle to a DID, the DNS TXT record is tried before asking the AppView.
+    #[instrument(skip(self))]
+    pub(crate) async fn resolve_handle(&self, handle: &str) -> Result<String> {
+        if let Some(did) = self.resolve_handle_dns(handle).await {
+            return Ok(did);
+        }
+
+        if let Some(did) = self.resolve_handle_appview(handle).await {
+            return Ok(did);
+        }
+
+        Err(anyhow!("could not resolve the handle {}", handle))
+    }
+
+    async fn resolve_handle_dns(&self, handle: &str) -> Option<String> {
+        let dns = self.dns.as_ref()?;
+
+        let lookup = match dns.txt_lookup(format!("_atproto.{}.", handle)).await {
+            Ok(lookup) => lookup,
+            Err(e) => {
+                debug!("no _atproto TXT record for {}: {}", handle, e);
+                return None;
+            }
+        };
+
+        lookup.iter().find_map(|txt| {
+            let data: Vec<u8> = txt
+                .txt_data()
+                .iter()
+                .flat_map(|d| d.iter())
+                .copied()
+                .collect();
+
+            String::from_utf8(data)
+                .ok()?
+                .strip_prefix("did=")
+                .filter(|did| is_did(did))
+                .map(|did| did.to_string())
+        })
+    }
+
+    async fn resolve_handle_appview(&self, handle: &str) -> Option<String> {
+        let url = format!(
+            "{}/xrpc/com.atproto.identity.resolveHandle",
+            self.appview_url
+        );
+
+        let response = match self
+            .http_client
+            .get(&url)
+            .query(&[("handle", handle)])
+            .send()
+            .await
+        {
+            Ok(response) if response.status().is_success() => response,
+            Ok(response) => {
+                debug!("resolving {} returned {}", handle, response.status());
+                return None;
+            }
+            Err(e) => {
+                debug!("could not resolve {}: {}", handle, e);
+                return None;
+            }
+        };
+
+        let output: ResolveHandleOutput = response.json().await.ok()?;
+
+        is_did(&output.did).then_some(output.did)
+    }
+
+    /// Checks that the account with `handle` has posted `challenge` recently.
+    #[instrument(skip(self))]
+    pub(crate) async fn verify(&self, handle: &str, challenge: &str) -> Result<BlueskyProfile> {
+        let did = self.resolve_handle(handle).await?;
+
+        let feed: AuthorFeed = self
+            .http_client
+            .get(format!(
+                "{}/xrpc/app.bsky.feed.getAuthorFeed",
+                self.appview_url
+            ))
+            .query(&[
+                ("actor", did.as_str()),
+                ("limit", &VERIFICATION_POSTS.to_string()),
+                ("filter", "posts_no_replies"),
+            ])
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+
+        // Reposts show up in the feed too, so the author of the post has to be checked
+        let author = feed
+            .feed
+            .into_iter()
+            .map(|item| item.post)
+            .find(|post| post.author.did == did && post.record.text.contains(challenge))
+            .map(|post| post.author)
+            .ok_or_else(|| anyhow!("the challenge was not found in the latest posts"))?;
+
+        if !author.handle.eq_ignore_ascii_case(handle) {
+            return Err(anyhow!(
+                "{} now belongs to {}",
+                did,
+                author.handle.to_ascii_lowercase()
+            ));
+        }
+
+        Ok(BlueskyProfile {
+            did,
+            display_name: author.display_name.filter(|s| !s.trim().is_empty()),
+            avatar: author.avatar,
+        })
+    }
+}
+
+fn is_did(s: &str) -> bool {
+    s.starts_with("did:plc:") || s.starts_with("did:web:")
+}
+
+/// The text that the employee is asked to post.
+pub(crate) fn verification_post(challenge: &str) -> String {
+    format!("Verifying my Bluesky account for Skjera: {}", challenge)
+}
+
+#[derive(Template)]
+#[template(path = "some-account-verify.html")]
+struct SomeAccountVerifyTemplate {
+    some_account: SomeAccount,
+    post: String,
+    error: Option<String>,
+}
+
+/// Finds the employee's unverified Bluesky account.
+async fn unverified_bluesky_account(
+    app: &ServerImpl,
+    employee: EmployeeId,
+    some_account_id: SomeAccountId,
+) -> Result<Option<SomeAccount>, AppError> {
+    Ok(app
+        .employee_dao
+        .some_accounts_by_employee(employee)
+        .await?
+        .into_iter()
+        .find(|a| a.id == some_account_id && a.network == SomeNetwork::Bluesky && !a.authenticated))
+}
+
+async fn render_verify(
+    app: &ServerImpl,
+    some_account: SomeAccount,
+    error: Option<String>,
+) -> Result<Response, AppError> {
+    let challenge = app
+        .employee_dao
+        .some_account_challenge(some_account.id, generate_verification_challenge())
+        .await?;
+
+    let template = SomeAccountVerifyTemplate {
+        some_account,
+        post: verification_post(&challenge),
+        error,
+    };
+
+    Ok(Html(template.render()?).into_response())
+}
+
+#[instrument(skip(app, session))]
+pub(crate) async fn bluesky_verify(
+    State(app): State<ServerImpl>,
+    session: AuthSession,
+    Path(some_account_id): Path<SomeAccountId>,
+) -> Result<Response, AppError> {
+    let user = session.user.unwrap();
+
+    let Some(some_account) =
+        unverified_bluesky_account(&app, user.employee, some_account_id).await?
+    else {
+        return Ok(Redirect::to("/me").into_response());
+    };
+
+    render_verify(&app, some_account, None).await
+}
+
+#[instrument(skip(app, session))]
+pub(crate) async fn bluesky_verify_check(
+    State(app): State<ServerImpl>,
+    session: AuthSession,
+    Path(some_account_id): Path<SomeAccountId>,
+) -> Result<Response, AppError> {
+    let user = session.user.unwrap();
+
+    let Some(some_account) =
+        unverified_bluesky_account(&app, user.employee, some_account_id).await?
+    else {
+        return Ok(Redirect::to("/me").into_response());
+    };
+
+    let Some(handle) = some_account.nick.clone() else {
+        return Err(anyhow!("Bluesky account without a handle: {}", some_account.id).into());
+    };
+
+    let challenge = app
+        .employee_dao
+        .some_account_challenge(some_account.id, generate_verification_challenge())
+        .await?;
+
+    let profile = match app.bluesky.verify(&handle, &challenge).await {
+        Ok(profile) => profile,
+        Err(e) => {
+            info!("Bluesky verification of {} failed: {:#}", handle, e);
+            return render_verify(&app, some_account, Some(format!("{:#}.", e))).await;
+        }
+    };
+
+    info!(did = profile.did, handle, "Verified Bluesky account");
+
+    app.employee_dao
+        .update_some_account(
+            some_account.id,
+            true,
+            some_account.network_avatar,
+            Some(profile.did),
+            profile.display_name,
+            some_account.nick,
+            some_account.url,
+            profile.avatar,
+        )
+        .await?;
+
+    app.employee_dao
+        .delete_some_account_challenge(some_account.id)
+        .await?;
+
+    Ok(Redirect::to("/me").into_response())
+}
@@ -0,0 +1,144 @@
+use crate::model::*;
+use crate::web::vcard::{escape, fold};
+use crate::{AppError, ServerImpl};
+use axum::extract::{Query, State};
+use axum::http::header::CONTENT_TYPE;
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use serde::Deserialize;
+use time::{Date, Month, OffsetDateTime};
+use tracing::instrument;
+
+const CALENDAR_CONTENT_TYPE: &str = "text/calendar; charset=utf-8";
+
+fn format_date(date: Date) -> String {
+    format!(
+        "{:04}{:02}{:02}",
+        date.year(),
+        date.month() as u8,
+        date.day()
+    )
+}
+
+fn format_timestamp(timestamp: OffsetDateTime) -> String {
+    let timestamp = timestamp.to_offset(time::UtcOffset::UTC);
+
+    format!(
+        "{}T{:02}{:02}{:02}Z",
+        format_date(timestamp.date()),
+        timestamp.hour(),
+        timestamp.minute(),
+        timestamp.second()
+    )
+}
+
+/// The first occurrence of the birthday, and the rule for the rest.
+///
+/// Unless the employee allows the year to be shown, the event starts in the current year so the
+/// year of birth can't be read from the calendar. Those born on February 29 celebrate on the last
+/// day of February, like the birthday announcements do.
+fn first_birthday(dob: Date, show_year: bool, today: Date) -> (Date, &'static str) {
+    let year = if show_year { dob.year() } else { today.year() };
+
+    if dob.month() == Month::February && dob.day() == 29 {
+        let last_day = Month::February.length(year);
+        let start = Date::from_calendar_date(year, Month::February, last_day).unwrap();
+
+        return (start, "FREQ=YEARLY;BYMONTH=2;BYMONTHDAY=-1");
+    }
+
+    (dob.replace_year(year).unwrap(), "FREQ=YEARLY")
+}
+
+/// Creates an iCalendar with a yearly, all-day event for every employee with a date of birth.
+/// The employees have to be redacted for the subscriber already, and deactivated employees are
+/// left out.
+pub(crate) fn birthday_calendar(employees: &[Employee], now: OffsetDateTime) -> String {
+    let today = now.date();
+    let stamp = format_timestamp(now);
+
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        "PRODID:-//Skjera//Birthdays//EN".to_string(),
+        "CALSCALE:GREGORIAN".to_string(),
+        "X-WR-CALNAME:Birthdays".to_string(),
+    ];
+
+    for employee in employees.iter().filter(|e| e.active) {
+        let Some(dob) = employee.dob else {
+            continue;
+        };
+
+        let (start, rule) = first_birthday(dob, employee.birthday_calendar_year, today);
+
+        lines.push("BEGIN:VEVENT".to_string());
+        lines.push(format!("UID:birthday-{}@skjera", employee.id));
+        lines.push(format!("DTSTAMP:{}", stamp));
+        lines.push(format!("DTSTART;VALUE=DATE:{}", format_date(start)));
+        lines.push(format!(
+            "DTEND;VALUE=DATE:{}",
+            format_date(start.next_day().unwrap())
+        ));
+        lines.push(format!("RRULE:{}", rule));
+        lines.push(format!(
+            "SUMMARY:{}",
+            escape(&format!("{}'s birthday", employee.name))
+        ));
+        if employee.birthday_calendar_year {
+            lines.push(format!("DESCRIPTION:Born {}", dob.year()));
+        }
+        lines.push("TRANSP:TRANSPARENT".to_string());
+        lines.push("END:VEVENT".to_string());
+    }
+
+    lines.push("END:VCALENDAR".to_string());
+
+    lines.iter().map(|line| fold(line)).collect()
+}
+
+#[derive(Deserialize)]
+pub(crate) struct CalendarQuery {
+    token: Option<String>,
+}
+
+/// Calendar apps can't log in, so the feed is authenticated with a calendar token in the URL
+/// instead.
+#[instrument(skip(app, query))]
+pub async fn birthdays(
+    State(app): State<ServerImpl>,
+    Query(query): Query<CalendarQuery>,
+) -> Result<Response, AppError> {
+    let Some(token) = query.token else {
+        return Ok(StatusCode::UNAUTHORIZED.into_response());
+    };
+
+    let Some(api_token) = app
+        .employee_dao
+        .use_api_token(hash_api_token(&token))
+        .await?
+    else {
+        return Ok(StatusCode::UNAUTHORIZED.into_response());
+    };
+
+    if api_token.scope != ApiTokenScope::Calendar {
+        return Ok(StatusCode::FORBIDDEN.into_response());
+    }
+
+    let owner = match app.employee_dao.employee_by_id(api_token.employee).await? {
+        Some(owner) if owner.active => owner,
+        _ => return Ok(StatusCode::UNAUTHORIZED.into_response()),
+    };
+
+    let employees: Vec<_> = app
+        .employee_dao
+        .all_employees()
+        .await?
+        .into_iter()
+        .map(|e| e.redacted(owner.id, owner.role))
+        .collect();
+
+    let body = birthday_calendar(&employees, OffsetDateTime::now_utc());
+
+    Ok(([(CONTENT_TYPE, CALENDAR_CONTENT_TYPE)], body).into_response())
+}
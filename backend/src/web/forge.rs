@@ -0,0 +1,322 @@
+use crate::model::*;
+use crate::session::ForgeConnectData;
+use crate::web::oauth::{InvalidOauthState, OauthResponse};
+use crate::{AppError, AuthSession, ServerImpl};
+use anyhow::{anyhow, Result};
+use axum::extract::{Query, State};
+use axum::response::Redirect;
+use oauth2::basic::BasicClient;
+use oauth2::reqwest::async_http_client;
+use oauth2::{
+    AuthUrl, AuthorizationCode, ClientId, ClientSecret, CsrfToken, PkceCodeChallenge,
+    PkceCodeVerifier, RedirectUrl, Scope, TokenResponse, TokenUrl,
+};
+use serde::Deserialize;
+use tower_sessions::Session;
+use tracing::{info, instrument, warn};
+use url::Url;
+
+pub(crate) const GITHUB_URL: &str = "https://github.com";
+pub(crate) const GITHUB_API_URL: &str = "https://api.github.com";
+pub(crate) const GITLAB_URL: &str = "https://gitlab.com";
+
+/// The user as returned by GitHub's `/user` and GitLab's `/api/v4/user`.
+#[derive(Debug, Deserialize)]
+struct ForgeApiUser {
+    id: i64,
+    #[serde(alias = "login")]
+    username: String,
+    name: Option<String>,
+    #[serde(alias = "html_url")]
+    web_url: Option<String>,
+    avatar_url: Option<String>,
+}
+
+/// The account that the user connected.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct ForgeUser {
+    pub(crate) subject: String,
+    pub(crate) nick: String,
+    pub(crate) name: Option<String>,
+    pub(crate) url: Option<String>,
+    pub(crate) avatar: Option<String>,
+}
+
+/// Connects a GitHub or GitLab account to an employee through OAuth. Neither support OpenID
+/// Connect for this, so the account is looked up in their APIs.
+#[derive(Clone, Debug)]
+pub(crate) struct ForgeConnect {
+    network: SomeNetwork,
+    /// The host of self-hosted GitLab installations.
+    network_instance: Option<String>,
+    client: BasicClient,
+    http_client: reqwest::Client,
+    api_url: String,
+    scope: &'static str,
+}
+
+impl ForgeConnect {
+    pub(crate) fn github(
+        http_client: reqwest::Client,
+        url: &str,
+        api_url: String,
+        client_id: String,
+        client_secret: String,
+        redirect_url: String,
+    ) -> Result<ForgeConnect> {
+        let client = BasicClient::new(
+            ClientId::new(client_id),
+            Some(ClientSecret::new(client_secret)),
+            AuthUrl::new(format!("{}/login/oauth/authorize", url))?,
+            Some(TokenUrl::new(format!("{}/login/oauth/access_token", url))?),
+        )
+        .set_redirect_uri(RedirectUrl::new(redirect_url)?);
+
+        Ok(ForgeConnect {
+            network: SomeNetwork::Github,
+            network_instance: None,
+            client,
+            http_client,
+            api_url,
+            scope: "read:user",
+        })
+    }
+
+    pub(crate) fn gitlab(
+        http_client: reqwest::Client,
+        url: &str,
+        client_id: String,
+        client_secret: String,
+        redirect_url: String,
+    ) -> Result<ForgeConnect> {
+        let client = BasicClient::new(
+            ClientId::new(client_id),
+            Some(ClientSecret::new(client_secret)),
+            AuthUrl::new(format!("{}/oauth/authorize", url))?,
+            Some(TokenUrl::new(format!("{}/oauth/token", url))?),
+        )
+        .set_redirect_uri(RedirectUrl::new(redirect_url)?);
+
+        Ok(ForgeConnect {
+            network: SomeNetwork::Gitlab,
+            network_instance: gitlab_instance(url)?,
+            client,
+            http_client,
+            api_url: format!("{}/api/v4", url),
+            scope: "read_user",
+        })
+    }
+
+    /// Creates the URL to send the user to, and stores the data needed to verify the response in
+    /// the session.
+    pub(crate) async fn connect_begin(&self, session: &Session) -> Result<Url> {
+        let (pkce_challenge, pkce_verifier) = PkceCodeChallenge::new_random_sha256();
+
+        let (auth_url, csrf_token) = self
+            .client
+            .authorize_url(CsrfToken::new_random)
+            .add_scope(Scope::new(self.scope.to_string()))
+            .set_pkce_challenge(pkce_challenge)
+            .url();
+
+        session
+            .insert(
+                ForgeConnectData::SESSION_KEY,
+                ForgeConnectData::new(self.network.to_string(), csrf_token, pkce_verifier),
+            )
+            .await?;
+
+        Ok(auth_url)
+    }
+
+    /// Completes the flow started by [Self::connect_begin]. The connect data is removed from the
+    /// session, so a response can only be used once.
+    #[instrument(skip_all, fields(network = %self.network))]
+    pub(crate) async fn connect_continue(
+        &self,
+        session: &Session,
+        response: OauthResponse,
+    ) -> Result<ForgeUser> {
+        let data = session
+            .remove::<ForgeConnectData>(ForgeConnectData::SESSION_KEY)
+            .await?
+            .ok_or(InvalidOauthState)?;
+
+        if data.network != self.network.to_string() {
+            warn!(data.network, "Connect response for another network");
+            return Err(InvalidOauthState.into());
+        }
+
+        if response.state.as_deref() != Some(data.csrf_token.secret().as_str()) {
+            warn!("Connect response with invalid state");
+            return Err(InvalidOauthState.into());
+        }
+
+        let token_response = self
+            .client
+            .exchange_code(AuthorizationCode::new(response.code))
+            .set_pkce_verifier(PkceCodeVerifier::new(data.pkce_verifier))
+            .request_async(async_http_client)
+            .await
+            .map_err(|e| anyhow!("could not exchange the code: {}", e))?;
+
+        // GitHub rejects requests without a User-Agent
+        let user: ForgeApiUser = self
+            .http_client
+            .get(format!("{}/user", self.api_url))
+            .bearer_auth(token_response.access_token().secret())
+            .header(reqwest::header::USER_AGENT, "skjera")
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+
+        info!(?user, "Got user");
+
+        Ok(ForgeUser {
+            subject: user.id.to_string(),
+            nick: user.username,
+            name: user.name.filter(|name| !name.trim().is_empty()),
+            url: user.web_url,
+            avatar: user.avatar_url,
+        })
+    }
+}
+
+/// github.com has its API on a separate host, GitHub Enterprise Server has it below `/api/v3`.
+pub(crate) fn github_api_url(url: &str) -> String {
+    match url {
+        GITHUB_URL => GITHUB_API_URL.to_string(),
+        _ => format!("{}/api/v3", url),
+    }
+}
+
+/// gitlab.com is the default instance, self-hosted installations are identified by their host.
+pub(crate) fn gitlab_instance(url: &str) -> Result<Option<String>> {
+    let url = Url::parse(url)?;
+    let host = url
+        .host_str()
+        .ok_or_else(|| anyhow!("GitLab URL without a host: {}", url))?;
+
+    if host == "gitlab.com" {
+        return Ok(None);
+    }
+
+    Ok(Some(match url.port() {
+        Some(port) => format!("{}:{}", host, port),
+        None => host.to_string(),
+    }))
+}
+
+async fn connect_begin(
+    connect: Option<ForgeConnect>,
+    session: Session,
+) -> Result<Redirect, AppError> {
+    let connect = connect.ok_or_else(|| anyhow!("not enabled"))?;
+
+    let auth_url = connect.connect_begin(&session).await?;
+
+    info!(
+        network = %connect.network,
+        auth_url = auth_url.as_str(),
+        "Connect started"
+    );
+
+    Ok(Redirect::to(auth_url.as_str()))
+}
+
+/// Creates or updates the employee's account on the network.
+async fn connect_callback(
+    app: ServerImpl,
+    connect: Option<ForgeConnect>,
+    auth_session: AuthSession,
+    session: Session,
+    query: OauthResponse,
+) -> Result<Redirect, AppError> {
+    let user = auth_session.user.unwrap();
+
+    let connect = connect.ok_or_else(|| anyhow!("not enabled"))?;
+
+    let forge_user = connect.connect_continue(&session, query).await?;
+
+    let account = app
+        .employee_dao
+        .some_account_for_network(
+            user.employee,
+            connect.network,
+            connect.network_instance.clone(),
+        )
+        .await?;
+
+    let account = match account {
+        Some(account) => {
+            app.employee_dao
+                .update_some_account(
+                    account.id,
+                    true,
+                    account.network_avatar,
+                    Some(forge_user.subject),
+                    forge_user.name,
+                    Some(forge_user.nick),
+                    forge_user.url,
+                    forge_user.avatar,
+                )
+                .await?
+        }
+        None => {
+            app.employee_dao
+                .add_some_account(
+                    user.employee,
+                    connect.network,
+                    connect.network_instance.clone(),
+                    true,
+                    None,
+                    Some(forge_user.subject),
+                    forge_user.name,
+                    Some(forge_user.nick),
+                    forge_user.url,
+                    forge_user.avatar,
+                )
+                .await?
+        }
+    };
+
+    info!(?account, "New/updated account");
+
+    Ok(Redirect::to("/me"))
+}
+
+pub(crate) async fn oauth_github_begin(
+    State(app): State<ServerImpl>,
+    session: Session,
+) -> Result<Redirect, AppError> {
+    connect_begin(app.github_connect, session).await
+}
+
+pub(crate) async fn oauth_github(
+    State(app): State<ServerImpl>,
+    Query(query): Query<OauthResponse>,
+    auth_session: AuthSession,
+    session: Session,
+) -> Result<Redirect, AppError> {
+    let connect = app.github_connect.clone();
+    connect_callback(app, connect, auth_session, session, query).await
+}
+
+pub(crate) async fn oauth_gitlab_begin(
+    State(app): State<ServerImpl>,
+    session: Session,
+) -> Result<Redirect, AppError> {
+    connect_begin(app.gitlab_connect, session).await
+}
+
+pub(crate) async fn oauth_gitlab(
+    State(app): State<ServerImpl>,
+    Query(query): Query<OauthResponse>,
+    auth_session: AuthSession,
+    session: Session,
+) -> Result<Redirect, AppError> {
+    let connect = app.gitlab_connect.clone();
+    connect_callback(app, connect, auth_session, session, query).await
+}
@@ -3,14 +3,19 @@ use crate::session::SkjeraSessionData;
 use crate::{AppError, AuthSession, ServerImpl};
 use anyhow::{anyhow, Context};
 use askama_axum::Template;
-use axum::extract::{Path, State};
+use axum::extract::{Path, Query, State};
+use axum::http::StatusCode;
 use axum::response::{Html, IntoResponse, Redirect, Response};
 use axum::Form;
 use once_cell::sync::Lazy;
-use serde::Deserialize;
-use time::{format_description, Date, Month};
+use serde::{de, Deserialize, Deserializer};
+use std::fmt::Display;
+use std::str::FromStr;
+use time::format_description::BorrowedFormatItem;
+use time::macros::format_description;
+use time::{format_description, Date, Month, OffsetDateTime};
+use tower_sessions::Session;
 use tracing::{debug, info, instrument, span, Level};
-use url::Url;
 
 static MONTH_NAMES: Lazy<Vec<String>> = Lazy::new(|| {
     vec![
@@ -29,6 +34,11 @@ static MONTH_NAMES: Lazy<Vec<String>> = Lazy::new(|| {
     ]
 });
 
+/// Used by the templates to hide what only admins can do.
+fn is_admin(session: &AuthSession) -> bool {
+    session.user.as_ref().is_some_and(|user| user.is_admin())
+}
+
 #[axum::debug_handler]
 #[tracing::instrument(skip(app, session))]
 pub async fn get_me(
@@ -45,6 +55,15 @@ pub async fn get_me(
         .ok_or_else(|| anyhow!("No such user: {}", user.email))
         .map_err(AppError::Anyhow)?;
 
+    render_me(app, me, Vec::new()).await
+}
+
+/// Renders the profile form, `errors` are the validation errors from the last submit.
+async fn render_me(
+    app: ServerImpl,
+    me: Employee,
+    errors: Vec<String>,
+) -> Result<Html<String>, AppError> {
     let some_accounts = app
         .employee_dao
         .some_accounts_by_employee(me.id)
@@ -54,6 +73,14 @@ pub async fn get_me(
     // let slack_url = app.slack_connect
     //     .and_then(|slack_connect| slack_connect.slack_url().ok());
     let slack_url = app.slack_connect.map(|_| "/oauth/slack-begin".to_string());
+    let github_url = app
+        .github_connect
+        .map(|_| "/oauth/github-begin".to_string());
+    let gitlab_url = app
+        .gitlab_connect
+        .map(|_| "/oauth/gitlab-begin".to_string());
+
+    let api_tokens = app.employee_dao.api_tokens_by_employee(me.id).await?;
 
     let template = MeTemplate {
         month_names: MONTH_NAMES.as_slice(),
@@ -61,8 +88,17 @@ pub async fn get_me(
         dob_year: me.dob.map(|d| d.year() as usize).unwrap_or_default(),
         dob_month: me.dob.map(|d| d.month() as usize).unwrap_or_default(),
         dob_day: me.dob.map(|d| d.day() as usize).unwrap_or_default(),
+        me,
+        errors,
         some_accounts,
+        manual_networks: some_network_definitions()
+            .iter()
+            .filter(|d| d.manual)
+            .collect(),
         slack_url,
+        github_url,
+        gitlab_url,
+        api_tokens,
     };
 
     Ok(Html(template.render()?))
@@ -73,13 +109,45 @@ pub(crate) struct MeForm {
     dob_year: i32,
     dob_month: u8,
     dob_day: u8,
+    personal_email: String,
+    phone_number: String,
+    scienta_slack_name: String,
+    noa_slack_name: String,
+    dob_visibility: FieldVisibility,
+    personal_email_visibility: FieldVisibility,
+    phone_number_visibility: FieldVisibility,
+    /// A checkbox, only sent when checked.
+    birthday_announcement: Option<String>,
+    birthday_calendar_year: Option<String>,
+}
+
+/// Parses an optional profile field, an empty input clears the field. An invalid input is kept as
+/// is so it can be shown again together with the error.
+fn profile_field(
+    label: &str,
+    input: &str,
+    parse: fn(&str) -> Result<String, String>,
+    errors: &mut Vec<String>,
+) -> Option<String> {
+    let input = input.trim();
+    if input.is_empty() {
+        return None;
+    }
+
+    match parse(input) {
+        Ok(value) => Some(value),
+        Err(e) => {
+            errors.push(format!("{} {}.", label, e));
+            Some(input.to_string())
+        }
+    }
 }
 
 pub async fn post_me(
     State(app): State<ServerImpl>,
     session: AuthSession,
     Form(input): Form<MeForm>,
-) -> Result<Redirect, AppError> {
+) -> Result<Response, AppError> {
     let _span = span!(Level::INFO, "post_me");
 
     let user = session.user.unwrap();
@@ -101,12 +169,47 @@ pub async fn post_me(
     };
 
     me.dob = dob;
+    me.dob_visibility = input.dob_visibility;
+    me.personal_email_visibility = input.personal_email_visibility;
+    me.phone_number_visibility = input.phone_number_visibility;
+    me.birthday_announcement = input.birthday_announcement.is_some();
+    me.birthday_calendar_year = input.birthday_calendar_year.is_some();
+
+    let mut errors = Vec::new();
+    me.personal_email = profile_field(
+        "Personal email",
+        &input.personal_email,
+        parse_email,
+        &mut errors,
+    );
+    me.phone_number = profile_field(
+        "Phone number",
+        &input.phone_number,
+        parse_phone_number,
+        &mut errors,
+    );
+    me.scienta_slack_name = profile_field(
+        "Scienta Slack name",
+        &input.scienta_slack_name,
+        parse_slack_name,
+        &mut errors,
+    );
+    me.noa_slack_name = profile_field(
+        "NoA Slack name",
+        &input.noa_slack_name,
+        parse_slack_name,
+        &mut errors,
+    );
+
+    if !errors.is_empty() {
+        return Ok(render_me(app, me, errors).await?.into_response());
+    }
 
     me = app.employee_dao.update(&me).await?;
 
     debug!("Updated Employee: {:?}", me);
 
-    Ok(Redirect::to("/"))
+    Ok(Redirect::to("/").into_response())
 }
 
 pub async fn delete_some_account(
@@ -130,90 +233,162 @@ pub async fn delete_some_account(
 
 #[derive(Deserialize, Debug)]
 pub(crate) struct AddSomeAccountForm {
-    bluesky: String,
-    button_bluesky: Option<String>,
-
-    linkedin: String,
-    button_linkedin: Option<String>,
-
-    x: String,
-    button_x: Option<String>,
+    network: SomeNetwork,
+    nick: String,
 }
 
 #[derive(Template)]
 #[template(path = "me.html")]
 struct MeTemplate<'a> {
+    pub me: Employee,
+    pub errors: Vec<String>,
     pub month_names: &'a [String],
     pub days: Vec<i32>,
     pub dob_year: usize,
     pub dob_month: usize,
     pub dob_day: usize,
     pub some_accounts: Vec<SomeAccount>,
+    /// The networks that accounts can be added to by hand.
+    pub manual_networks: Vec<&'static SomeNetworkDefinition>,
 
     pub slack_url: Option<String>,
+    pub github_url: Option<String>,
+    pub gitlab_url: Option<String>,
+
+    pub api_tokens: Vec<ApiToken>,
+}
+
+impl MeTemplate<'_> {
+    pub fn timestamp(timestamp: &OffsetDateTime) -> String {
+        format_timestamp(timestamp)
+    }
+
+    /// Only Bluesky accounts can be verified by posting a challenge.
+    pub fn can_verify(some_account: &SomeAccount) -> bool {
+        !some_account.authenticated && some_account.network == SomeNetwork::Bluesky
+    }
 }
 
 pub async fn add_some_account(
     State(app): State<ServerImpl>,
     session: AuthSession,
     Form(input): Form<AddSomeAccountForm>,
-) -> Result<Redirect, AppError> {
+) -> Result<Response, AppError> {
     let _span = span!(Level::INFO, "add_some_account");
 
     let user = session.user.unwrap();
 
     info!("input" = ?input, "Adding some account");
 
-    let mut network: Option<SomeNetwork> = None;
-    let mut subject: Option<String> = None;
-    let mut nick: Option<String> = None;
-    let mut url: Option<String> = None;
-
-    if input.button_bluesky.is_some() {
-        network = Some(BLUESKY.to_owned());
-        nick = Some(input.bluesky.clone());
-        subject = nick.clone();
-        url = Some(format!("https://bsky.app/profile/{}", input.bluesky.clone()).to_string());
-    } else if input.button_linkedin.is_some() {
-        network = Some(LINKED_IN.to_owned());
-        url = Some(input.linkedin);
-    } else if input.button_x.is_some() && input.x.trim().len() > 0 {
-        network = Some(X.to_owned());
-        nick = Some(input.x.clone());
-        subject = nick.clone();
-        url = Some(format!("https://x.com/{}", input.x.clone()).to_string());
+    let definition = input.network.definition();
+
+    let nick = match definition.manual {
+        true => definition.parse_nick(&input.nick),
+        false => Err(format!("{} accounts have to be connected", definition.name)),
+    };
+
+    let nick = match nick {
+        Ok(nick) => nick,
+        Err(e) => {
+            let me = app
+                .employee_dao
+                .employee_by_id(user.employee)
+                .await?
+                .context("error loading me")?;
+
+            return Ok(render_me(app, me, vec![format!("{}.", e)])
+                .await?
+                .into_response());
+        }
+    };
+
+    // The subject is set to the account's ID on the network when the account is verified
+    let some_account = app
+        .employee_dao
+        .add_some_account(
+            user.employee,
+            input.network,
+            None,
+            false,
+            None,
+            None,
+            None,
+            Some(nick.clone()),
+            definition.profile_url(&nick),
+            None,
+        )
+        .await?;
+
+    if MeTemplate::can_verify(&some_account) {
+        let verify_url = format!("/me/some_account/{}/verify", some_account.id);
+        return Ok(Redirect::to(&verify_url).into_response());
     }
 
-    if let Some(network) = network {
-        let instance = None;
-        let name = None;
-        let avatar = None;
-        let authenticated = false;
-
-        app.employee_dao
-            .add_some_account(
-                user.employee,
-                network,
-                instance,
-                authenticated,
-                None,
-                subject,
-                name,
-                nick,
-                url,
-                avatar,
-            )
-            .await?;
+    Ok(Redirect::to("/me").into_response())
+}
+
+#[derive(Deserialize, Debug)]
+pub(crate) struct ApiTokenForm {
+    name: String,
+    scope: ApiTokenScope,
+}
+
+#[derive(Template)]
+#[template(path = "api-token-created.html")]
+struct ApiTokenCreatedTemplate {
+    api_token: ApiToken,
+    token: String,
+}
+
+pub async fn add_api_token(
+    State(app): State<ServerImpl>,
+    session: AuthSession,
+    Form(input): Form<ApiTokenForm>,
+) -> Result<Response, AppError> {
+    let user = session.user.unwrap();
+
+    let name = input.name.trim().to_string();
+    if name.is_empty() {
+        return Ok(Redirect::to("/me").into_response());
     }
 
+    info!(scope = ?input.scope, "Creating API token");
+
+    let token = generate_api_token();
+    let api_token = app
+        .employee_dao
+        .insert_api_token(user.employee, name, input.scope, hash_api_token(&token))
+        .await?;
+
+    let template = ApiTokenCreatedTemplate { api_token, token };
+
+    Ok(Html(template.render()?).into_response())
+}
+
+pub async fn delete_api_token(
+    State(app): State<ServerImpl>,
+    session: AuthSession,
+    Path(api_token_id): Path<ApiTokenId>,
+) -> Result<Redirect, AppError> {
+    let user = session.user.unwrap();
+
+    info!("api_token_id" = api_token_id.0, "Revoking API token");
+
+    app.employee_dao
+        .delete_api_token(api_token_id, user.employee)
+        .await?;
+
     Ok(Redirect::to("/me"))
 }
 
 #[derive(Template)]
 #[template(path = "employee.html")]
 struct EmployeeTemplate {
+    is_admin: bool,
     employee: Employee,
     some_accounts: Vec<SomeAccount>,
+    current_assignment: Option<(Assignment, Customer)>,
+    assignments: Vec<(Assignment, Customer)>,
 }
 
 impl EmployeeTemplate {
@@ -229,28 +404,64 @@ impl EmployeeTemplate {
     }
 }
 
-#[tracing::instrument(skip(app))]
+#[tracing::instrument(skip(app, session))]
 pub async fn employee(
     State(app): State<ServerImpl>,
+    session: AuthSession,
     Path(employee_id): Path<EmployeeId>,
-) -> Result<Html<String>, AppError> {
-    let employee = app
-        .employee_dao
-        .employee_by_id(employee_id)
-        .await?
-        .context("error loading me")?;
+) -> Result<Response, AppError> {
+    let user = session.user.unwrap();
+
+    let Some(employee) = app.employee_dao.employee_by_id(employee_id).await? else {
+        return Ok(StatusCode::NOT_FOUND.into_response());
+    };
+    if !employee.active && !user.is_admin() {
+        return Ok(StatusCode::NOT_FOUND.into_response());
+    }
+    let employee = user.redact(employee);
 
     let some_accounts = app
         .employee_dao
         .some_accounts_by_employee(employee_id)
         .await?;
 
+    let today = OffsetDateTime::now_utc().date();
+    let current_assignment = match app
+        .employee_dao
+        .current_assignment(employee_id, today)
+        .await?
+    {
+        Some(assignment) => app
+            .employee_dao
+            .customer_by_id(assignment.customer)
+            .await?
+            .map(|customer| (assignment, customer)),
+        None => None,
+    };
+
+    let customers = app.employee_dao.customers().await?;
+    let assignments = app
+        .employee_dao
+        .assignments_by_employee(employee_id)
+        .await?
+        .into_iter()
+        .filter_map(|a| {
+            customers
+                .iter()
+                .find(|c| c.id == a.customer)
+                .map(|c| (a, c.clone()))
+        })
+        .collect();
+
     let template = EmployeeTemplate {
+        is_admin: user.is_admin(),
         employee,
         some_accounts,
+        current_assignment,
+        assignments,
     };
 
-    Ok(Html(template.render()?))
+    Ok(Html(template.render()?).into_response())
 }
 
 #[tracing::instrument(skip(app))]
@@ -278,6 +489,316 @@ pub async fn employee_create_message(
     Ok(Html(template.render()?))
 }
 
+#[derive(Template)]
+#[template(path = "customers.html")]
+struct CustomersTemplate {
+    is_admin: bool,
+    customers: Vec<Customer>,
+    error: Option<String>,
+}
+
+async fn render_customers(
+    app: &ServerImpl,
+    session: &AuthSession,
+    error: Option<String>,
+) -> Result<Html<String>, AppError> {
+    let customers = app.employee_dao.customers().await?;
+
+    let template = CustomersTemplate {
+        is_admin: is_admin(session),
+        customers,
+        error,
+    };
+
+    Ok(Html(template.render()?))
+}
+
+#[tracing::instrument(skip(app, session))]
+pub async fn customers(
+    State(app): State<ServerImpl>,
+    session: AuthSession,
+) -> Result<Html<String>, AppError> {
+    render_customers(&app, &session, None).await
+}
+
+#[derive(Deserialize, Debug)]
+pub(crate) struct CustomerForm {
+    name: String,
+}
+
+/// The name has to be unique, the form is shown again with an error if another customer has it.
+async fn customer_name_taken(
+    app: &ServerImpl,
+    name: &str,
+    customer_id: Option<CustomerId>,
+) -> Result<Option<String>, AppError> {
+    let other = app.employee_dao.customer_by_name(name).await?;
+
+    Ok(other
+        .filter(|other| Some(other.id) != customer_id)
+        .map(|_| format!("There already is a customer named {}.", name)))
+}
+
+#[tracing::instrument(skip(app, session))]
+pub async fn add_customer(
+    State(app): State<ServerImpl>,
+    session: AuthSession,
+    Form(input): Form<CustomerForm>,
+) -> Result<Response, AppError> {
+    let name = input.name.trim();
+    if name.is_empty() {
+        return Ok(Redirect::to("/customer").into_response());
+    }
+
+    if let Some(error) = customer_name_taken(&app, name, None).await? {
+        return Ok(render_customers(&app, &session, Some(error))
+            .await?
+            .into_response());
+    }
+
+    let customer = app.employee_dao.insert_customer(name.to_string()).await?;
+
+    info!(customer = customer.id.0, "Created customer");
+
+    Ok(Redirect::to(&format!("/customer/{}", customer.id)).into_response())
+}
+
+#[derive(Template)]
+#[template(path = "customer.html")]
+struct CustomerTemplate {
+    is_admin: bool,
+    customer: Customer,
+    assignments: Vec<Assignment>,
+    employees: Vec<Employee>,
+    error: Option<String>,
+}
+
+#[tracing::instrument(skip(app, session))]
+pub async fn customer(
+    State(app): State<ServerImpl>,
+    session: AuthSession,
+    Path(customer_id): Path<CustomerId>,
+) -> Result<Html<String>, AppError> {
+    render_customer(&app, &session, customer_id, None).await
+}
+
+/// Shows the customer, with the error from a form that couldn't be saved.
+async fn render_customer(
+    app: &ServerImpl,
+    session: &AuthSession,
+    customer_id: CustomerId,
+    error: Option<String>,
+) -> Result<Html<String>, AppError> {
+    let customer = app
+        .employee_dao
+        .customer_by_id(customer_id)
+        .await?
+        .context("error loading customer")?;
+
+    let assignments = app
+        .employee_dao
+        .assignments_by_customer(customer_id)
+        .await?;
+
+    // Old assignments can belong to employees that have left
+    let employees = app.employee_dao.all_employees().await?;
+
+    let template = CustomerTemplate {
+        is_admin: is_admin(session),
+        customer,
+        assignments,
+        employees,
+        error,
+    };
+
+    Ok(Html(template.render()?))
+}
+
+#[tracing::instrument(skip(app, session))]
+pub async fn update_customer(
+    State(app): State<ServerImpl>,
+    session: AuthSession,
+    Path(customer_id): Path<CustomerId>,
+    Form(input): Form<CustomerForm>,
+) -> Result<Response, AppError> {
+    let mut customer = app
+        .employee_dao
+        .customer_by_id(customer_id)
+        .await?
+        .context("error loading customer")?;
+
+    let name = input.name.trim();
+    if !name.is_empty() {
+        if let Some(error) = customer_name_taken(&app, name, Some(customer_id)).await? {
+            return Ok(render_customer(&app, &session, customer_id, Some(error))
+                .await?
+                .into_response());
+        }
+
+        customer.name = name.to_string();
+        app.employee_dao.update_customer(&customer).await?;
+    }
+
+    Ok(Redirect::to(&format!("/customer/{}", customer_id)).into_response())
+}
+
+/// Customers with assignments are kept, the assignments have to be removed first.
+#[tracing::instrument(skip(app, session))]
+pub async fn delete_customer(
+    State(app): State<ServerImpl>,
+    session: AuthSession,
+    Path(customer_id): Path<CustomerId>,
+) -> Result<Response, AppError> {
+    let assignments = app
+        .employee_dao
+        .assignments_by_customer(customer_id)
+        .await?;
+
+    if !assignments.is_empty() {
+        let error = "The customer has assignments, remove them before deleting it.".to_string();
+
+        return Ok(render_customer(&app, &session, customer_id, Some(error))
+            .await?
+            .into_response());
+    }
+
+    info!(customer = customer_id.0, "Deleting customer");
+
+    app.employee_dao.delete_customer(customer_id).await?;
+
+    Ok(Redirect::to("/customer").into_response())
+}
+
+#[derive(Deserialize, Debug)]
+pub(crate) struct AssignmentForm {
+    employee: EmployeeId,
+    description: String,
+    start_date: String,
+    end_date: String,
+}
+
+/// How timestamps are shown on the pages, to the minute.
+pub(crate) fn format_timestamp(timestamp: &OffsetDateTime) -> String {
+    const FORMAT: &[BorrowedFormatItem] =
+        format_description!("[year]-[month]-[day] [hour]:[minute]");
+
+    timestamp.format(FORMAT).unwrap_or_default()
+}
+
+/// Parses the value of an `<input type="date">`, which is empty when no date is given.
+pub(crate) fn parse_date_input(s: &str) -> anyhow::Result<Option<Date>> {
+    let s = s.trim();
+    if s.is_empty() {
+        return Ok(None);
+    }
+
+    let f = format_description::parse("[year]-[month]-[day]")?;
+
+    Date::parse(s, &f)
+        .map(Some)
+        .with_context(|| format!("invalid date: {}", s))
+}
+
+/// The start and end date of an assignment, or the error to show in the form.
+pub(crate) fn assignment_dates(
+    start_date: &str,
+    end_date: &str,
+) -> Result<(Date, Option<Date>), String> {
+    let start_date = parse_date_input(start_date)
+        .map_err(|_| format!("The start date {} is invalid.", start_date.trim()))?
+        .ok_or_else(|| "The start date is missing.".to_string())?;
+    let end_date = parse_date_input(end_date)
+        .map_err(|_| format!("The end date {} is invalid.", end_date.trim()))?;
+
+    if end_date.is_some_and(|end_date| end_date < start_date) {
+        return Err("The end date is before the start date.".to_string());
+    }
+
+    Ok((start_date, end_date))
+}
+
+#[tracing::instrument(skip(app, session))]
+pub async fn add_assignment(
+    State(app): State<ServerImpl>,
+    session: AuthSession,
+    Path(customer_id): Path<CustomerId>,
+    Form(input): Form<AssignmentForm>,
+) -> Result<Response, AppError> {
+    let (start_date, end_date) = match assignment_dates(&input.start_date, &input.end_date) {
+        Ok(dates) => dates,
+        Err(error) => {
+            return Ok(render_customer(&app, &session, customer_id, Some(error))
+                .await?
+                .into_response())
+        }
+    };
+
+    let assignment = app
+        .employee_dao
+        .insert_assignment(
+            customer_id,
+            input.employee,
+            input.description.trim().to_string(),
+            start_date,
+            end_date,
+        )
+        .await?;
+
+    info!(assignment = assignment.id.0, "Created assignment");
+
+    Ok(Redirect::to(&format!("/customer/{}", customer_id)).into_response())
+}
+
+#[tracing::instrument(skip(app, session))]
+pub async fn update_assignment(
+    State(app): State<ServerImpl>,
+    session: AuthSession,
+    Path((customer_id, assignment_id)): Path<(CustomerId, AssignmentId)>,
+    Form(input): Form<AssignmentForm>,
+) -> Result<Response, AppError> {
+    let mut assignment = app
+        .employee_dao
+        .assignment_by_id(assignment_id)
+        .await?
+        .filter(|a| a.customer == customer_id)
+        .context("error loading assignment")?;
+
+    assignment.employee = input.employee;
+    assignment.description = input.description.trim().to_string();
+    (assignment.start_date, assignment.end_date) =
+        match assignment_dates(&input.start_date, &input.end_date) {
+            Ok(dates) => dates,
+            Err(error) => {
+                return Ok(render_customer(&app, &session, customer_id, Some(error))
+                    .await?
+                    .into_response())
+            }
+        };
+
+    app.employee_dao.update_assignment(&assignment).await?;
+
+    Ok(Redirect::to(&format!("/customer/{}", customer_id)).into_response())
+}
+
+#[tracing::instrument(skip(app))]
+pub async fn delete_assignment(
+    State(app): State<ServerImpl>,
+    Path((customer_id, assignment_id)): Path<(CustomerId, AssignmentId)>,
+) -> Result<Redirect, AppError> {
+    let assignment = app
+        .employee_dao
+        .assignment_by_id(assignment_id)
+        .await?
+        .filter(|a| a.customer == customer_id)
+        .context("error loading assignment")?;
+
+    info!(assignment = assignment.id.0, "Deleting assignment");
+
+    app.employee_dao.delete_assignment(assignment.id).await?;
+
+    Ok(Redirect::to(&format!("/customer/{}", customer_id)))
+}
+
 #[derive(Template)]
 #[template(path = "employee-message.html")]
 struct EmployeeCreateMessageTemplate {
@@ -289,7 +810,13 @@ struct EmployeeCreateMessageTemplate {
 #[template(path = "hello.html"/*, print = "all"*/)]
 struct HelloTemplate {
     pub user: Option<SkjeraSessionData>,
-    pub employees: Option<Vec<Employee>>,
+    pub news_items: Option<Vec<NewsItem>>,
+}
+
+impl HelloTemplate {
+    pub fn publish_date(item: &NewsItem) -> String {
+        format_timestamp(&item.publish_date)
+    }
 }
 
 pub async fn hello_world(
@@ -302,43 +829,138 @@ pub async fn hello_world(
         return Ok(Redirect::to(crate::LOGIN_PATH).into_response());
     }
 
-    let mut employees = None::<Vec<Employee>>;
+    let mut news_items = None::<Vec<NewsItem>>;
     if session.user.is_some() {
-        employees = Some(app.employee_dao.employees().await?);
+        news_items = Some(app.employee_dao.news_items(50).await?);
     }
 
     let template = HelloTemplate {
         user: session.user,
-        employees,
+        news_items,
     };
 
     Ok(Html(template.render()?).into_response())
 }
 
+const EMPLOYEES_PER_PAGE: i64 = 50;
+
+/// Selects send an empty value for "any", anything else has to be valid.
+fn empty_as_none<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: FromStr,
+    T::Err: Display,
+{
+    match String::deserialize(deserializer)?.as_str() {
+        "" => Ok(None),
+        s => s.parse().map(Some).map_err(de::Error::custom),
+    }
+}
+
+#[derive(Deserialize, Debug, Default)]
+pub(crate) struct EmployeeSearchForm {
+    #[serde(default)]
+    q: String,
+    #[serde(default, deserialize_with = "empty_as_none")]
+    network: Option<SomeNetwork>,
+    #[serde(default)]
+    customer: String,
+    #[serde(default)]
+    sort: EmployeeSort,
+    page: Option<i64>,
+}
+
+#[derive(Template)]
+#[template(path = "employees.html")]
+struct EmployeesTemplate {
+    pub employees: Vec<Employee>,
+    pub form: EmployeeSearchForm,
+    pub networks: Vec<SomeNetwork>,
+    pub customers: Vec<Customer>,
+    pub total: i64,
+    pub page: i64,
+    pub pages: i64,
+}
+
+impl EmployeesTemplate {
+    /// The URL of another page of the same search.
+    pub fn page_url(&self, page: &i64) -> String {
+        let query = url::form_urlencoded::Serializer::new(String::new())
+            .append_pair("q", &self.form.q)
+            .append_pair(
+                "network",
+                &self.form.network.map(|n| n.to_string()).unwrap_or_default(),
+            )
+            .append_pair("customer", &self.form.customer)
+            .append_pair("sort", self.form.sort.as_str())
+            .append_pair("page", &page.to_string())
+            .finish();
+
+        format!("/employee?{}", query)
+    }
+
+    pub fn is_selected_network(&self, network: &SomeNetwork) -> bool {
+        self.form.network == Some(*network)
+    }
+
+    pub fn is_selected_customer(&self, customer: &Customer) -> bool {
+        self.form.customer == customer.id.to_string()
+    }
+}
+
+/// Admins also see the employees that have been deactivated, so they can be reactivated.
+#[tracing::instrument(skip(app, session))]
+pub async fn employees(
+    State(app): State<ServerImpl>,
+    session: AuthSession,
+    Query(form): Query<EmployeeSearchForm>,
+) -> Result<Html<String>, AppError> {
+    let user = session.user.unwrap();
+
+    let page = form.page.unwrap_or(1).max(1);
+
+    let query = EmployeeQuery {
+        text: Some(form.q.trim().to_string()).filter(|q| !q.is_empty()),
+        network: form.network,
+        customer: form.customer.parse().ok().map(CustomerId),
+        include_inactive: user.is_admin(),
+        sort: form.sort,
+        limit: EMPLOYEES_PER_PAGE,
+        offset: (page - 1).saturating_mul(EMPLOYEES_PER_PAGE),
+    };
+
+    let today = OffsetDateTime::now_utc().date();
+    let (employees, total) = app.employee_dao.search_employees(&query, today).await?;
+    let employees = employees.into_iter().map(|e| user.redact(e)).collect();
+
+    let template = EmployeesTemplate {
+        employees,
+        form,
+        networks: app.employee_dao.some_networks().await?,
+        customers: app.employee_dao.customers().await?,
+        total,
+        page,
+        pages: (total + EMPLOYEES_PER_PAGE - 1) / EMPLOYEES_PER_PAGE,
+    };
+
+    Ok(Html(template.render()?))
+}
+
 #[derive(Template)]
 #[template(path = "login.html")]
 pub(crate) struct LoginTemplate {
     pub google_auth_url: String,
 }
 
-#[instrument(skip(app, session))]
+#[instrument(skip(app, auth_session, session))]
 pub async fn login(
     State(app): State<ServerImpl>,
-    mut session: AuthSession,
+    mut auth_session: AuthSession,
+    session: Session,
 ) -> Result<Html<String>, AppError> {
-    let _ = session.logout().await;
+    let _ = auth_session.logout().await;
 
-    let scope = "openid profile email";
-
-    let u = Url::parse_with_params(
-        "https://accounts.google.com/o/oauth2/v2/auth",
-        &[
-            ("scope", scope),
-            ("client_id", &app.cfg.client_id),
-            ("response_type", "code"),
-            ("redirect_uri", &app.cfg.redirect_url),
-        ],
-    )?;
+    let u = app.google_login.login_begin(&session).await?;
 
     let template = LoginTemplate {
         google_auth_url: u.to_string(),
@@ -348,9 +970,7 @@ pub async fn login(
 }
 
 #[instrument(skip(session))]
-pub async fn logout(
-    mut session: AuthSession,
-) -> Result<Redirect, AppError> {
+pub async fn logout(mut session: AuthSession) -> Result<Redirect, AppError> {
     let _ = session
         .logout()
         .await
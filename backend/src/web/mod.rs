@@ -1,5 +1,17 @@
-pub mod web;
+pub(crate) mod admin;
+pub(crate) mod api;
+pub(crate) mod avatar;
+pub(crate) mod bluesky;
+pub(crate) mod calendar;
+pub(crate) mod forge;
 pub mod html;
 pub mod oauth;
 pub mod slack;
 pub(crate) mod slack_bot;
+pub(crate) mod slack_import;
+pub(crate) mod vcard;
+#[allow(clippy::module_inception)]
+pub mod web;
+
+#[cfg(test)]
+pub(crate) mod tests;
@@ -1,38 +1,108 @@
-use crate::model::{Employee, EmployeeDao};
-use crate::session::SkjeraSessionData;
+use crate::model::{
+    hash_api_token, ApiTokenDao, Employee, EmployeeDao, EmployeeRole, LoginAccessDao,
+    LoginAccessStatus,
+};
+use crate::session::{GoogleLoginData, SkjeraSessionData};
 use crate::web::html::UnauthorizedTemplate;
 use crate::{AppError, ServerImpl};
-use anyhow::anyhow;
+use anyhow::{anyhow, Result};
 use askama_axum::Template;
 use async_trait::async_trait;
 use axum::extract::Query;
-use axum::response::*;
 use axum::response::{Html, IntoResponse, Redirect, Response};
-use axum_login::{AuthnBackend, UserId};
+use axum_login::{AuthnBackend, AuthzBackend, UserId};
 use http::StatusCode;
-use oauth2::basic::{BasicClient, BasicTokenResponse};
-use oauth2::reqwest::async_http_client;
-use oauth2::{
-    AuthUrl, AuthorizationCode, ClientId, ClientSecret, RedirectUrl, TokenResponse, TokenUrl,
+use oauth2::PkceCodeVerifier;
+use openidconnect::core::{
+    CoreAuthDisplay, CoreAuthPrompt, CoreAuthenticationFlow, CoreErrorResponseType,
+    CoreGenderClaim, CoreJsonWebKey, CoreJsonWebKeyType, CoreJsonWebKeyUse,
+    CoreJweContentEncryptionAlgorithm, CoreJwsSigningAlgorithm, CoreProviderMetadata,
+    CoreRevocableToken, CoreRevocationErrorResponse, CoreTokenIntrospectionResponse, CoreTokenType,
+};
+use openidconnect::reqwest::async_http_client;
+use openidconnect::{
+    AccessTokenHash, AdditionalClaims, AuthorizationCode, Client, ClientId, ClientSecret,
+    CsrfToken, EmptyExtraTokenFields, IdTokenFields, IssuerUrl, Nonce, OAuth2TokenResponse,
+    PkceCodeChallenge, RedirectUrl, Scope, StandardErrorResponse, StandardTokenResponse,
+    TokenResponse,
 };
 use serde::{Deserialize, Serialize};
-use tracing::{debug, info, span, Level};
+use std::collections::HashSet;
+use tower_sessions::Session;
+use tracing::{debug, info, span, warn, Level};
+use url::Url;
+
+pub(crate) const GOOGLE_ISSUER_URL: &str = "https://accounts.google.com";
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub(crate) struct GoogleAdditionalClaims {
+    /// The Google Workspace domain of the account, missing for personal accounts.
+    hd: Option<String>,
+}
+impl AdditionalClaims for GoogleAdditionalClaims {}
 
-#[derive(Debug, Deserialize)]
-#[allow(dead_code)]
+type GoogleTokenResponse = StandardTokenResponse<
+    IdTokenFields<
+        GoogleAdditionalClaims,
+        EmptyExtraTokenFields,
+        CoreGenderClaim,
+        CoreJweContentEncryptionAlgorithm,
+        CoreJwsSigningAlgorithm,
+        CoreJsonWebKeyType,
+    >,
+    CoreTokenType,
+>;
+
+/// Like CoreClient, but with the Google specific claims in the ID token.
+type GoogleClient = Client<
+    GoogleAdditionalClaims,
+    CoreAuthDisplay,
+    CoreGenderClaim,
+    CoreJweContentEncryptionAlgorithm,
+    CoreJwsSigningAlgorithm,
+    CoreJsonWebKeyType,
+    CoreJsonWebKeyUse,
+    CoreJsonWebKey,
+    CoreAuthPrompt,
+    StandardErrorResponse<CoreErrorResponseType>,
+    GoogleTokenResponse,
+    CoreTokenType,
+    CoreTokenIntrospectionResponse,
+    CoreRevocableToken,
+    CoreRevocationErrorResponse,
+>;
+
+/// A response to a connect flow that wasn't started in this session, or was completed already.
+#[derive(Debug, thiserror::Error)]
+#[error("Invalid oauth state")]
+pub(crate) struct InvalidOauthState;
+
+#[derive(Clone, Debug, Deserialize)]
 pub(crate) struct OauthResponse {
     pub(crate) code: String,
+    pub(crate) state: Option<String>,
 }
 
 pub(crate) async fn oauth_google(
-    Query(OauthResponse { code }): Query<OauthResponse>,
+    Query(response): Query<OauthResponse>,
     mut auth_session: crate::AuthSession,
+    session: Session,
 ) -> Response {
     let _method = span!(Level::INFO, "oauth_google_inner");
 
-    debug!("code: {}", code);
+    let login = match session
+        .remove::<GoogleLoginData>(GoogleLoginData::SESSION_KEY)
+        .await
+    {
+        Ok(Some(login)) => login,
+        Ok(None) => {
+            info!("Not in a login process");
+            return Redirect::to(crate::LOGIN_PATH).into_response();
+        }
+        Err(_) => return StatusCode::INTERNAL_SERVER_ERROR.into_response(),
+    };
 
-    let creds = SkjeraAuthnCredentials { code };
+    let creds = SkjeraAuthnCredentials::Google { response, login };
 
     let user = match auth_session.authenticate(creds).await {
         Ok(Some(user)) => user,
@@ -54,90 +124,237 @@ pub(crate) async fn oauth_google(
     }
 }
 
+/// Loads the employee for the Google account, creating it if the account is allowed to sign up.
+/// Accounts that are not allowed are recorded as pending so an admin can approve them.
 async fn load_or_create_employee(
     app: &ServerImpl,
     user_profile: &GoogleUserProfile,
-) -> Result<Employee, anyhow::Error> {
-    let employee = app
+) -> Result<Option<Employee>, anyhow::Error> {
+    let email = user_profile.email.clone();
+
+    let access = app
         .employee_dao
-        .employee_by_email(user_profile.email.clone())
+        .login_access_by_email(email.clone())
         .await?;
 
+    if access
+        .as_ref()
+        .is_some_and(|a| a.status == LoginAccessStatus::Denied)
+    {
+        info!(email, "Login denied");
+        return Ok(None);
+    }
+
+    let employee = app.employee_dao.employee_by_email(email.clone()).await?;
+
     if let Some(e) = employee {
         info!("Loaded employee user: {:?}", e);
-        return Ok(e);
+        return Ok(Some(e));
+    }
+
+    let allowed = access
+        .as_ref()
+        .is_some_and(|a| a.status == LoginAccessStatus::Allowed)
+        || app
+            .cfg
+            .is_allowed_domain(user_profile.hosted_domain.as_deref())
+        || app.cfg.is_admin(&email);
+
+    if !allowed {
+        info!(email, hosted_domain = ?user_profile.hosted_domain, "Sign-up needs approval");
+
+        app.employee_dao
+            .insert_pending_login_access(email, user_profile.name.clone())
+            .await?;
+
+        return Ok(None);
     }
 
     let employee = app
         .employee_dao
-        .insert_employee(user_profile.email.clone(), user_profile.name.clone())
+        .insert_employee(email, user_profile.name.clone())
         .await?;
 
     info!("Created new employee: {:?}", employee);
 
-    Ok(employee)
+    Ok(Some(employee))
 }
 
-pub(crate) fn build_oauth_client(
-    redirect_url: String,
-    client_id: String,
-    client_secret: String,
-) -> BasicClient {
-    // If you're not using Google OAuth, you can use whatever the relevant auth/token URL is for your given OAuth service
-    let auth_url = AuthUrl::new("https://accounts.google.com/o/oauth2/v2/auth".to_string())
-        .expect("Invalid authorization endpoint URL");
-    let token_url = TokenUrl::new("https://www.googleapis.com/oauth2/v3/token".to_string())
-        .expect("Invalid token endpoint URL");
-
-    BasicClient::new(
-        ClientId::new(client_id),
-        Some(ClientSecret::new(client_secret)),
-        auth_url,
-        Some(token_url),
-    )
-    .set_redirect_uri(RedirectUrl::new(redirect_url).unwrap())
+/// Makes sure that everyone listed in ADMIN_EMAILS is an admin, so there is always someone that
+/// can manage the roles of the others.
+async fn promote_admin(app: &ServerImpl, mut employee: Employee) -> Result<Employee, AppError> {
+    if employee.role != EmployeeRole::Admin && app.cfg.is_admin(&employee.email) {
+        info!(email = employee.email, "Promoting employee to admin");
+
+        app.employee_dao
+            .update_employee_role(employee.id, EmployeeRole::Admin)
+            .await?;
+
+        employee.role = EmployeeRole::Admin;
+    }
+
+    Ok(employee)
 }
 
 #[derive(Debug, Deserialize, Serialize)]
 pub struct GoogleUserProfile {
-    sub: String,
-    email: String,
-    name: String,
-    // given_name: Option<String>,
-    // family_name: Option<String>,
+    pub(crate) sub: String,
+    pub(crate) email: String,
+    pub(crate) name: String,
+    pub(crate) hosted_domain: Option<String>,
 }
 
-#[derive(Debug, Clone, Deserialize)]
-pub struct SkjeraAuthnCredentials {
-    pub code: String,
-    // pub old_state: CsrfToken,
-    // pub new_state: CsrfToken,
+#[derive(Clone, Debug)]
+pub(crate) struct GoogleLogin {
+    client: GoogleClient,
 }
 
-impl ServerImpl {
-    #[tracing::instrument(skip(self, creds))]
-    async fn exchange_code(
-        self: &Self,
-        creds: SkjeraAuthnCredentials,
-    ) -> anyhow::Result<BasicTokenResponse> {
-        self.basic_client
-            .exchange_code(AuthorizationCode::new(creds.code))
+impl GoogleLogin {
+    pub(crate) async fn new(
+        issuer_url: String,
+        client_id: String,
+        client_secret: String,
+        redirect_url: String,
+    ) -> Result<GoogleLogin> {
+        let provider_metadata =
+            CoreProviderMetadata::discover_async(IssuerUrl::new(issuer_url)?, async_http_client)
+                .await?;
+
+        let client = GoogleClient::from_provider_metadata(
+            provider_metadata,
+            ClientId::new(client_id),
+            Some(ClientSecret::new(client_secret)),
+        )
+        .set_redirect_uri(RedirectUrl::new(redirect_url)?);
+
+        Ok(GoogleLogin { client })
+    }
+
+    /// Creates the URL to send the user to, and stores the data needed to verify the response in
+    /// the session.
+    pub(crate) async fn login_begin(&self, session: &Session) -> Result<Url> {
+        let (pkce_challenge, pkce_verifier) = PkceCodeChallenge::new_random_sha256();
+
+        let (auth_url, csrf_token, nonce) = self
+            .client
+            .authorize_url(
+                CoreAuthenticationFlow::AuthorizationCode,
+                CsrfToken::new_random,
+                Nonce::new_random,
+            )
+            .add_scope(Scope::new("email".to_string()))
+            .add_scope(Scope::new("profile".to_string()))
+            .set_pkce_challenge(pkce_challenge)
+            .url();
+
+        session
+            .insert(
+                GoogleLoginData::SESSION_KEY,
+                GoogleLoginData::new(csrf_token, nonce, pkce_verifier),
+            )
+            .await?;
+
+        Ok(auth_url)
+    }
+
+    /// Completes the flow started by [Self::login_begin]. Returns None if the response doesn't
+    /// belong to this login or the email address isn't verified.
+    #[tracing::instrument(skip_all)]
+    pub(crate) async fn login_continue(
+        &self,
+        login: GoogleLoginData,
+        response: OauthResponse,
+    ) -> Result<Option<GoogleUserProfile>> {
+        if response.state.as_deref() != Some(login.csrf_token.secret().as_str()) {
+            warn!("Google login response with invalid state");
+            return Ok(None);
+        }
+
+        let token_response = self
+            .client
+            .exchange_code(AuthorizationCode::new(response.code))
+            .set_pkce_verifier(PkceCodeVerifier::new(login.pkce_verifier))
             .request_async(async_http_client)
             .await
-            .map_err(|e| anyhow!("Error exchanging oauth code: {}", e))
+            .map_err(|e| anyhow!("Error exchanging oauth code: {}", e))?;
+
+        let id_token = token_response
+            .id_token()
+            .ok_or_else(|| anyhow!("Server did not return an ID token"))?;
+
+        let claims = id_token.claims(&self.client.id_token_verifier(), &login.nonce)?;
+
+        if let Some(expected_access_token_hash) = claims.access_token_hash() {
+            let actual_access_token_hash = AccessTokenHash::from_token(
+                token_response.access_token(),
+                &id_token.signing_alg()?,
+            )?;
+            if actual_access_token_hash != *expected_access_token_hash {
+                return Err(anyhow!("Invalid access token"));
+            }
+        }
+
+        if claims.email_verified() != Some(true) {
+            warn!("Google login with unverified email");
+            return Ok(None);
+        }
+
+        let email = claims
+            .email()
+            .ok_or_else(|| anyhow!("ID token without email"))?
+            .to_string();
+        let name = claims
+            .name()
+            .and_then(|name| name.get(None))
+            .map(|name| name.to_string())
+            .unwrap_or_else(|| email.clone());
+
+        Ok(Some(GoogleUserProfile {
+            sub: claims.subject().to_string(),
+            email,
+            name,
+            hosted_domain: claims.additional_claims().hd.clone(),
+        }))
     }
+}
 
+#[derive(Debug, Clone)]
+pub enum SkjeraAuthnCredentials {
+    /// The response from Google, and the login data from the session it has to match.
+    Google {
+        response: OauthResponse,
+        login: GoogleLoginData,
+    },
+    /// A personal access token from a Bearer authorization header.
+    ApiToken(String),
+}
+
+impl ServerImpl {
     #[tracing::instrument(skip(self, token))]
-    async fn user_info(
-        self: &Self,
-        token: &BasicTokenResponse,
-    ) -> anyhow::Result<reqwest::Response> {
-        self.ctx
-            .get("https://openidconnect.googleapis.com/v1/userinfo")
-            .bearer_auth(token.access_token().secret().to_owned())
-            .send()
-            .await
-            .map_err(|e| anyhow!("Could not fetch userinfo: {}", e))
+    async fn authenticate_api_token(
+        &self,
+        token: String,
+    ) -> Result<Option<SkjeraSessionData>, AppError> {
+        let Some(api_token) = self
+            .employee_dao
+            .use_api_token(hash_api_token(&token))
+            .await?
+        else {
+            info!("Unknown API token");
+            return Ok(None);
+        };
+
+        let user = self
+            .employee_dao
+            .employee_by_id(api_token.employee)
+            .await?
+            .filter(|employee| employee.active)
+            .map(|employee| SkjeraSessionData {
+                api_token_scope: Some(api_token.scope),
+                ..Self::session_data(employee)
+            });
+
+        Ok(user)
     }
 }
 
@@ -151,23 +368,28 @@ impl AuthnBackend for ServerImpl {
         &self,
         creds: Self::Credentials,
     ) -> Result<Option<Self::User>, Self::Error> {
-        let token = self.exchange_code(creds).await?;
-
-        debug!("token: {:?}", token.scopes());
+        let (response, login) = match creds {
+            SkjeraAuthnCredentials::Google { response, login } => (response, login),
+            SkjeraAuthnCredentials::ApiToken(token) => {
+                return self.authenticate_api_token(token).await;
+            }
+        };
 
-        let profile = self.user_info(&token).await?;
+        let Some(user_profile) = self.google_login.login_continue(login, response).await? else {
+            return Ok(None);
+        };
+        debug!("UserProfile: {:?}", user_profile);
 
-        // let profile_response = profile.text().await.unwrap();
-        // println!("UserProfile: {:?}", profile_response);
-        // let user_profile = serde_json::from_str::<UserProfile>(&profile_response).unwrap();
+        let Some(employee) = load_or_create_employee(self, &user_profile).await? else {
+            return Ok(None);
+        };
 
-        let user_profile = profile
-            .json::<GoogleUserProfile>()
-            .await
-            .map_err(AppError::Reqwest)?;
-        info!("UserProfile: {:?}", user_profile);
+        if !employee.active {
+            info!(email = employee.email, "Login by deactivated employee");
+            return Ok(None);
+        }
 
-        let employee = load_or_create_employee(self, &user_profile).await?;
+        let employee = promote_admin(self, employee).await?;
 
         // session
         //     .mark_logged_in(employee.id, user_profile.email, user_profile.name)
@@ -182,8 +404,24 @@ impl AuthnBackend for ServerImpl {
             .employee_dao
             .employee_by_id(*user_id)
             .await?
+            .filter(|employee| employee.active)
             .map(Self::session_data);
 
         Ok(user)
     }
 }
+
+#[async_trait]
+impl AuthzBackend for ServerImpl {
+    type Permission = EmployeeRole;
+
+    async fn get_user_permissions(
+        &self,
+        user: &Self::User,
+    ) -> Result<HashSet<Self::Permission>, Self::Error> {
+        let mut permissions = HashSet::from([EmployeeRole::Employee]);
+        permissions.insert(user.role);
+
+        Ok(permissions)
+    }
+}
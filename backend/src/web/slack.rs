@@ -1,7 +1,8 @@
+use crate::model::{EmployeeDao, SomeNetwork};
 use crate::session::SlackConnectData;
 use crate::slack_client::SlackUserProfile;
-use crate::web::oauth::OauthResponse;
-use crate::{model, slack_client, AppError, AuthSession, ServerImpl};
+use crate::web::oauth::{InvalidOauthState, OauthResponse};
+use crate::{slack_client, AppError, AuthSession, ServerImpl};
 use anyhow::{anyhow, Result};
 use axum::extract::{Query, State};
 use axum::response::Redirect;
@@ -17,22 +18,25 @@ use openidconnect::{
 };
 use serde::{Deserialize, Serialize};
 use std::fmt::Debug;
-use tracing::{debug, info, span, Level};
+use tower_sessions::Session;
+use tracing::{debug, info, span, warn, Level};
 use url::Url;
-use crate::model::EmployeeDao;
+
+pub(crate) const SLACK_ISSUER_URL: &str = "https://slack.com";
+pub(crate) const SLACK_API_URL: &str = "https://slack.com/api";
 
 type SlackUserInfoClaims = UserInfoClaims<SlackAdditionalClaims, CoreGenderClaim>;
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
-struct SlackAdditionalClaims {
+pub(crate) struct SlackAdditionalClaims {
     #[serde(rename = "https://slack.com/team_id")]
-    team_id: Option<String>,
+    pub(crate) team_id: Option<String>,
 
     #[serde(rename = "https://slack.com/team_domain")]
-    team_domain: Option<String>,
+    pub(crate) team_domain: Option<String>,
 
     #[serde(rename = "https://slack.com/team_image_230")]
-    team_image_230: Option<String>,
+    pub(crate) team_image_230: Option<String>,
 }
 impl AdditionalClaims for SlackAdditionalClaims {}
 
@@ -40,20 +44,21 @@ impl AdditionalClaims for SlackAdditionalClaims {}
 pub(crate) struct SlackConnect {
     client: CoreClient,
     http_client: reqwest::Client,
+    api_url: String,
 }
 
 impl SlackConnect {
     pub(crate) async fn new(
         http_client: reqwest::Client,
+        issuer_url: String,
+        api_url: String,
         client_id: String,
         client_secret: String,
         redirect_url: String,
     ) -> Result<SlackConnect> {
-        let provider_metadata = CoreProviderMetadata::discover_async(
-            IssuerUrl::new("https://slack.com".to_string())?,
-            async_http_client,
-        )
-        .await?;
+        let provider_metadata =
+            CoreProviderMetadata::discover_async(IssuerUrl::new(issuer_url)?, async_http_client)
+                .await?;
 
         let redirect_url = RedirectUrl::new(redirect_url.clone())?;
 
@@ -71,10 +76,13 @@ impl SlackConnect {
         Ok(SlackConnect {
             http_client,
             client,
+            api_url,
         })
     }
 
-    async fn slack_connect_begin(self: &Self) -> Result<(Url, CsrfToken, Nonce, PkceCodeVerifier)> {
+    /// Creates the URL to send the user to, and stores the data needed to verify the response in
+    /// the session.
+    pub(crate) async fn slack_connect_begin(&self, session: &Session) -> Result<Url> {
         // Generate a PKCE challenge.
         let (pkce_challenge, pkce_verifier) = PkceCodeChallenge::new_random_sha256();
 
@@ -92,7 +100,14 @@ impl SlackConnect {
             .set_pkce_challenge(pkce_challenge)
             .url();
 
-        Ok((auth_url, csrf_token, nonce, pkce_verifier))
+        session
+            .insert(
+                SlackConnectData::SESSION_KEY,
+                SlackConnectData::new(csrf_token, nonce, pkce_verifier),
+            )
+            .await?;
+
+        Ok(auth_url)
     }
 
     //noinspection RsConstantConditionIf
@@ -116,13 +131,27 @@ impl SlackConnect {
         Ok(result)
     }
 
-    async fn slack_connect_continue(
-        self: &Self,
-        session: SlackConnectData,
-        code: String,
+    /// Completes the flow started by [Self::slack_connect_begin]. The connect data is removed
+    /// from the session, so a response can only be used once.
+    pub(crate) async fn slack_connect_continue(
+        &self,
+        session: &Session,
+        response: OauthResponse,
     ) -> Result<(SlackUserInfoClaims, SlackUserProfile)> {
         let _span = span!(Level::INFO, "slack_connect_continue");
-        let pkce_verifier = PkceCodeVerifier::new(session.pkce_verifier);
+
+        let data = session
+            .remove::<SlackConnectData>(SlackConnectData::SESSION_KEY)
+            .await?
+            .ok_or(InvalidOauthState)?;
+
+        if response.state.as_deref() != Some(data.csrf_token.secret().as_str()) {
+            warn!("Slack connect response with invalid state");
+            return Err(InvalidOauthState.into());
+        }
+
+        let code = response.code;
+        let pkce_verifier = PkceCodeVerifier::new(data.pkce_verifier);
 
         let token_response = span!(Level::INFO, "exchange_token")
             .in_scope(|| async {
@@ -142,7 +171,7 @@ impl SlackConnect {
         debug!("Got Slack token");
         debug!("Got Slack token: {}", id_token.to_string());
 
-        let claims = id_token.claims(&self.client.id_token_verifier(), &session.nonce)?;
+        let claims = id_token.claims(&self.client.id_token_verifier(), &data.nonce)?;
 
         info!("claims: {:?}", claims);
 
@@ -181,6 +210,7 @@ impl SlackConnect {
 
         let user_profile = slack_client::user_profile_get(
             &self.http_client,
+            &self.api_url,
             token_response.access_token().secret(),
         )
         .await?;
@@ -193,7 +223,7 @@ impl SlackConnect {
 
 pub(crate) async fn oauth_slack_begin(
     State(app): State<ServerImpl>,
-    session: AuthSession,
+    session: Session,
 ) -> std::result::Result<Redirect, AppError> {
     let _method = span!(Level::INFO, "oauth_slack_begin");
 
@@ -201,20 +231,9 @@ pub(crate) async fn oauth_slack_begin(
         .slack_connect
         .ok_or_else(|| anyhow!("slack not enabled"))?;
 
-    let (auth_url, csrf_token, nonce, pkce_verifier) = slack_connect.slack_connect_begin().await?;
-
-    info!(
-        auth_url = auth_url.as_str(),
-        // csrf_token = csrf_token.secret(),
-        // nonce = nonce.secret(),
-        // pkce_verifier = pkce_verifier.secret(),
-        "Slack connect successful"
-    );
+    let auth_url = slack_connect.slack_connect_begin(&session).await?;
 
-    session
-        .user
-        .unwrap()
-        .with_slack_connect(csrf_token, nonce, pkce_verifier);
+    info!(auth_url = auth_url.as_str(), "Slack connect started");
 
     Ok(Redirect::to(auth_url.as_str()))
 }
@@ -222,9 +241,10 @@ pub(crate) async fn oauth_slack_begin(
 pub(crate) async fn oauth_slack(
     State(app): State<ServerImpl>,
     Query(query): Query<OauthResponse>,
-    s: AuthSession,
+    auth_session: AuthSession,
+    session: Session,
 ) -> std::result::Result<Redirect, AppError> {
-    let mut session = s.user.unwrap();
+    let user = auth_session.user.unwrap();
 
     let _method = span!(Level::INFO, "oauth_slack");
 
@@ -233,13 +253,8 @@ pub(crate) async fn oauth_slack(
         .slack_connect
         .ok_or_else(|| anyhow!("slack not enabled"))?;
 
-    let slack_connect_data = session
-        .slack_connect
-        .take()
-        .ok_or_else(|| anyhow!("Not in a oauth process"))?;
-
     let (user_info, user_profile) = slack_connect
-        .slack_connect_continue(slack_connect_data, query.code)
+        .slack_connect_continue(&session, query)
         .await?;
 
     let _team_domain = user_info
@@ -250,7 +265,7 @@ pub(crate) async fn oauth_slack(
 
     let authenticated = true;
 
-    let network = model::SLACK.to_owned();
+    let network = SomeNetwork::Slack;
     let network_instance = user_info.additional_claims().clone().team_id;
     let network_avatar = user_info.additional_claims().clone().team_image_230;
     let subject = Some(user_info.subject().to_string());
@@ -264,11 +279,7 @@ pub(crate) async fn oauth_slack(
 
     let account = app
         .employee_dao
-        .some_account_for_network(
-            session.employee,
-            network.to_string(),
-            network_instance.clone(),
-        )
+        .some_account_for_network(user.employee, network, network_instance.clone())
         .await?;
     let account = match account {
         Some(account) => {
@@ -292,8 +303,8 @@ pub(crate) async fn oauth_slack(
 
             app.employee_dao
                 .add_some_account(
-                    session.employee,
-                    network.to_owned(),
+                    user.employee,
+                    network,
                     network_instance,
                     authenticated,
                     network_avatar,
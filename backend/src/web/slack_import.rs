@@ -0,0 +1,265 @@
+use crate::model::*;
+use crate::slack_client;
+use crate::slack_client::SlackUser;
+use crate::web::slack::SLACK_API_URL;
+use crate::{AppError, ServerImpl};
+use anyhow::anyhow;
+use askama_axum::Template;
+use axum::extract::State;
+use axum::response::Html;
+use std::collections::HashSet;
+use tracing::{info, instrument, warn};
+
+/// What the import does with a user in the Slack workspace.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum SlackImportAction {
+    /// Creates an employee and links the account to it.
+    CreateEmployee,
+    /// Links the account to the employee with the same email.
+    LinkAccount(EmployeeId),
+    AlreadyLinked(EmployeeId),
+    /// The employee with the same email is already linked to another user in the workspace.
+    LinkedToOtherUser(EmployeeId),
+    Skipped(&'static str),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct SlackImportRow {
+    pub(crate) user_id: String,
+    pub(crate) email: Option<String>,
+    pub(crate) name: String,
+    pub(crate) nick: Option<String>,
+    pub(crate) avatar: Option<String>,
+    pub(crate) action: SlackImportAction,
+}
+
+impl SlackImportRow {
+    pub fn changes(&self) -> bool {
+        matches!(
+            self.action,
+            SlackImportAction::CreateEmployee | SlackImportAction::LinkAccount(_)
+        )
+    }
+
+    pub fn employee(&self) -> Option<EmployeeId> {
+        match self.action {
+            SlackImportAction::LinkAccount(employee)
+            | SlackImportAction::AlreadyLinked(employee)
+            | SlackImportAction::LinkedToOtherUser(employee) => Some(employee),
+            _ => None,
+        }
+    }
+
+    pub fn description(&self, dry_run: bool) -> String {
+        match (&self.action, dry_run) {
+            (SlackImportAction::CreateEmployee, true) => "Create employee".to_string(),
+            (SlackImportAction::CreateEmployee, false) => "Created employee".to_string(),
+            (SlackImportAction::LinkAccount(_), true) => "Link account".to_string(),
+            (SlackImportAction::LinkAccount(_), false) => "Linked account".to_string(),
+            (SlackImportAction::AlreadyLinked(_), _) => "Already linked".to_string(),
+            (SlackImportAction::LinkedToOtherUser(_), _) => {
+                "Skipped, the employee is linked to another Slack user".to_string()
+            }
+            (SlackImportAction::Skipped(reason), _) => format!("Skipped, {}", reason),
+        }
+    }
+}
+
+/// Decides what to do with each user in the `team` workspace. Employees are matched by email,
+/// `accounts` are the Slack accounts that are already linked to the workspace. Bots are left
+/// out, guests and users from other workspaces are skipped.
+pub(crate) fn plan_slack_import(
+    team: &str,
+    users: Vec<SlackUser>,
+    employees: &[Employee],
+    accounts: &[SomeAccount],
+) -> Vec<SlackImportRow> {
+    let mut emails = HashSet::new();
+
+    users
+        .into_iter()
+        .filter(|user| !user.is_bot && user.id != "USLACKBOT")
+        .map(|user| {
+            let email = user
+                .profile
+                .email
+                .map(|email| email.trim().to_lowercase())
+                .filter(|email| !email.is_empty());
+
+            let employee = email.as_ref().and_then(|email| {
+                employees
+                    .iter()
+                    .find(|e| e.email.eq_ignore_ascii_case(email))
+            });
+
+            let linked = accounts
+                .iter()
+                .find(|a| a.subject.as_deref() == Some(user.id.as_str()));
+
+            let action = match (linked, employee, &email) {
+                (Some(account), _, _) => SlackImportAction::AlreadyLinked(account.employee),
+                _ if user.deleted => SlackImportAction::Skipped("deactivated in Slack"),
+                _ if user.is_restricted || user.is_ultra_restricted => {
+                    SlackImportAction::Skipped("a guest in Slack")
+                }
+                _ if user.team_id.as_deref().is_some_and(|t| t != team) => {
+                    SlackImportAction::Skipped("from another workspace")
+                }
+                (_, _, None) => SlackImportAction::Skipped("no email address"),
+                (_, _, Some(email)) if !emails.insert(email.clone()) => {
+                    SlackImportAction::Skipped("another Slack user has the same email")
+                }
+                (_, Some(employee), _) if accounts.iter().any(|a| a.employee == employee.id) => {
+                    SlackImportAction::LinkedToOtherUser(employee.id)
+                }
+                (_, Some(employee), _) => SlackImportAction::LinkAccount(employee.id),
+                (_, None, _) => SlackImportAction::CreateEmployee,
+            };
+
+            let nick = Some(user.profile.display_name.trim().to_string()).filter(|s| !s.is_empty());
+
+            let name = [
+                Some(user.profile.real_name.trim().to_string()),
+                nick.clone(),
+                email.clone(),
+            ]
+            .into_iter()
+            .flatten()
+            .find(|s| !s.is_empty())
+            .unwrap_or_else(|| user.id.clone());
+
+            SlackImportRow {
+                user_id: user.id,
+                email,
+                name,
+                nick,
+                avatar: user.profile.image_512.or(user.profile.image_192),
+                action,
+            }
+        })
+        .collect()
+}
+
+#[derive(Template)]
+#[template(path = "admin-slack-import.html")]
+struct SlackImportTemplate {
+    dry_run: bool,
+    team: Option<String>,
+    rows: Vec<SlackImportRow>,
+    error: Option<String>,
+}
+
+impl SlackImportTemplate {
+    pub fn changes(&self) -> usize {
+        self.rows.iter().filter(|row| row.changes()).count()
+    }
+}
+
+async fn plan(app: &ServerImpl) -> anyhow::Result<(String, Vec<SlackImportRow>)> {
+    let slack_config = app
+        .cfg
+        .slack_config
+        .as_ref()
+        .ok_or_else(|| anyhow!("Slack is not configured"))?;
+    let token = &slack_config.bot_token.token_value.0;
+
+    let team = slack_client::auth_test(&app.http_client, SLACK_API_URL, token).await?;
+    let users = slack_client::users_list(&app.http_client, SLACK_API_URL, token).await?;
+
+    let employees = app.employee_dao.all_employees().await?;
+    let accounts = app
+        .employee_dao
+        .some_accounts_by_network(SomeNetwork::Slack, Some(team.clone()))
+        .await?;
+
+    let rows = plan_slack_import(&team, users, &employees, &accounts);
+
+    Ok((team, rows))
+}
+
+async fn apply(app: &ServerImpl, team: &str, rows: &[SlackImportRow]) -> Result<(), AppError> {
+    for row in rows {
+        let employee = match (&row.action, &row.email) {
+            (SlackImportAction::CreateEmployee, Some(email)) => {
+                let employee = app
+                    .employee_dao
+                    .insert_employee(email.clone(), row.name.clone())
+                    .await?;
+
+                info!(
+                    employee = employee.id.0,
+                    email, "Created employee from Slack"
+                );
+                employee.id
+            }
+            (SlackImportAction::LinkAccount(employee), _) => *employee,
+            _ => continue,
+        };
+
+        // Not authenticated until the employee connects the account themselves
+        let account = app
+            .employee_dao
+            .add_some_account(
+                employee,
+                SomeNetwork::Slack,
+                Some(team.to_string()),
+                false,
+                None,
+                Some(row.user_id.clone()),
+                Some(row.name.clone()),
+                row.nick.clone(),
+                None,
+                row.avatar.clone(),
+            )
+            .await?;
+
+        info!(
+            employee = employee.0,
+            some_account = account.id.0,
+            "Linked Slack account"
+        );
+    }
+
+    Ok(())
+}
+
+async fn slack_import(app: ServerImpl, dry_run: bool) -> Result<Html<String>, AppError> {
+    let template = match plan(&app).await {
+        Ok((team, rows)) => {
+            if !dry_run {
+                apply(&app, &team, &rows).await?;
+            }
+
+            SlackImportTemplate {
+                dry_run,
+                team: Some(team),
+                rows,
+                error: None,
+            }
+        }
+        Err(e) => {
+            warn!("Could not list the Slack users: {:#}", e);
+
+            SlackImportTemplate {
+                dry_run,
+                team: None,
+                rows: Vec::new(),
+                error: Some(format!("Could not list the Slack users: {:#}.", e)),
+            }
+        }
+    };
+
+    Ok(Html(template.render()?))
+}
+
+/// Shows what an import would do.
+#[instrument(skip(app))]
+pub async fn slack_import_preview(State(app): State<ServerImpl>) -> Result<Html<String>, AppError> {
+    slack_import(app, true).await
+}
+
+/// The users are listed again, so the import does what the preview would show now.
+#[instrument(skip(app))]
+pub async fn slack_import_run(State(app): State<ServerImpl>) -> Result<Html<String>, AppError> {
+    slack_import(app, false).await
+}
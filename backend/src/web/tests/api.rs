@@ -0,0 +1,224 @@
+use crate::model::tests::{delete_test_employee, insert_test_employee, test_dao, test_pool};
+use crate::model::*;
+use crate::web::api::{bearer_auth, with_session};
+use crate::web::bluesky::BlueskyVerifier;
+use crate::web::oauth::GoogleLogin;
+use crate::web::tests::fake_oidc::{FakeOidcProvider, CLIENT_ID, CLIENT_SECRET};
+use crate::{AuthSession, Config, ServerImpl};
+use axum::middleware::from_fn;
+use axum::routing::get;
+use axum::Router;
+use axum_login::AuthManagerLayerBuilder;
+use reqwest::{Client, StatusCode};
+use tokio::net::TcpListener;
+use tower_sessions::{MemoryStore, SessionManagerLayer};
+
+async fn server_impl(dao: Dao, provider: &FakeOidcProvider) -> ServerImpl {
+    let cfg = Config {
+        issuer_url: provider.issuer.clone(),
+        client_id: CLIENT_ID.to_string(),
+        client_secret: CLIENT_SECRET.to_string(),
+        redirect_url: "http://localhost:8080/oauth/google".to_string(),
+        allowed_domains: vec![],
+        admin_emails: vec![],
+        slack_config: None,
+        slack_profile_sync_interval: std::time::Duration::from_secs(3600),
+        github_config: None,
+        gitlab_config: None,
+        bluesky_appview_url: "http://127.0.0.1:1".to_string(),
+        birthday_schedule: None,
+    };
+
+    let google_login = GoogleLogin::new(
+        cfg.issuer_url.clone(),
+        cfg.client_id.clone(),
+        cfg.client_secret.clone(),
+        cfg.redirect_url.clone(),
+    )
+    .await
+    .unwrap();
+
+    let http_client = Client::new();
+
+    ServerImpl {
+        pool: test_pool(&dao),
+        assets_path: "assets".to_string(),
+        bluesky: BlueskyVerifier::new(http_client.clone(), cfg.bluesky_appview_url.clone()),
+        cfg,
+        google_login,
+        bot: None,
+        slack_client: None,
+        employee_dao: dao,
+        slack_connect: None,
+        github_connect: None,
+        gitlab_connect: None,
+        birthday_bot: None,
+        http_client,
+    }
+}
+
+/// Answers with the email of the user that the request is authenticated as.
+async fn whoami(session: AuthSession) -> Result<String, axum::http::StatusCode> {
+    session
+        .user
+        .map(|user| user.email)
+        .ok_or(axum::http::StatusCode::UNAUTHORIZED)
+}
+
+/// Serves `router` behind the auth layer, and returns the base URL.
+async fn serve(server_impl: ServerImpl, router: Router) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+
+    let session_layer = SessionManagerLayer::new(MemoryStore::default());
+    let auth_layer = AuthManagerLayerBuilder::new(server_impl, session_layer).build();
+
+    let app = router.layer(auth_layer);
+
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    url
+}
+
+/// Serves [whoami] behind [bearer_auth] and the auth layer, like the API is served.
+async fn start_api(server_impl: ServerImpl) -> String {
+    let router = Router::new()
+        .route("/whoami", get(whoami).post(whoami))
+        .route_layer(from_fn(bearer_auth));
+
+    format!("{}/whoami", serve(server_impl, router).await)
+}
+
+/// Serves the generated API the same way as [crate::web::web::create_router] does.
+async fn start_skjera_api(server_impl: ServerImpl) -> String {
+    let router = skjera_api::server::new(server_impl.clone())
+        .route_layer(from_fn(with_session))
+        .route_layer(from_fn(bearer_auth));
+
+    serve(server_impl, router).await
+}
+
+async fn insert_token(dao: &Dao, employee: &Employee, scope: ApiTokenScope) -> (ApiToken, String) {
+    let token = generate_api_token();
+    let api_token = dao
+        .insert_api_token(
+            employee.id,
+            "test".to_string(),
+            scope,
+            hash_api_token(&token),
+        )
+        .await
+        .unwrap();
+
+    (api_token, token)
+}
+
+#[tokio::test]
+async fn test_bearer_auth() {
+    let Some(dao) = test_dao().await else {
+        return;
+    };
+    let provider = FakeOidcProvider::start().await;
+    let employee = insert_test_employee(&dao, "Ola Nordmann").await;
+    let url = start_api(server_impl(dao.clone(), &provider).await).await;
+    let client = Client::new();
+
+    let (read_write, read_write_token) =
+        insert_token(&dao, &employee, ApiTokenScope::ReadWrite).await;
+    let (_, read_only_token) = insert_token(&dao, &employee, ApiTokenScope::ReadOnly).await;
+    let (_, calendar_token) = insert_token(&dao, &employee, ApiTokenScope::Calendar).await;
+
+    let get = |token: &str| client.get(&url).bearer_auth(token).send();
+    let post = |token: &str| client.post(&url).bearer_auth(token).send();
+
+    let response = get(&read_write_token).await.unwrap();
+    assert_eq!(StatusCode::OK, response.status());
+    assert_eq!(employee.email, response.text().await.unwrap());
+    assert_eq!(
+        StatusCode::OK,
+        post(&read_write_token).await.unwrap().status()
+    );
+
+    // Read-only tokens can only do safe requests
+    assert_eq!(
+        StatusCode::OK,
+        get(&read_only_token).await.unwrap().status()
+    );
+    assert_eq!(
+        StatusCode::FORBIDDEN,
+        post(&read_only_token).await.unwrap().status()
+    );
+
+    // Calendar tokens only work for the calendar feed
+    assert_eq!(
+        StatusCode::FORBIDDEN,
+        get(&calendar_token).await.unwrap().status()
+    );
+
+    assert_eq!(
+        StatusCode::UNAUTHORIZED,
+        get(&generate_api_token()).await.unwrap().status()
+    );
+
+    // Without a token the request goes on without a user
+    assert_eq!(
+        StatusCode::UNAUTHORIZED,
+        client.get(&url).send().await.unwrap().status()
+    );
+
+    dao.delete_api_token(read_write.id, employee.id)
+        .await
+        .unwrap();
+    assert_eq!(
+        StatusCode::UNAUTHORIZED,
+        get(&read_write_token).await.unwrap().status()
+    );
+
+    delete_test_employee(&dao, employee.id).await;
+}
+
+#[tokio::test]
+async fn test_get_inactive_employee() {
+    let Some(dao) = test_dao().await else {
+        return;
+    };
+    let provider = FakeOidcProvider::start().await;
+    let employee = insert_test_employee(&dao, "Ola Nordmann").await;
+    let admin = insert_test_employee(&dao, "Kari Nordmann").await;
+    let inactive = insert_test_employee(&dao, "Per Nordmann").await;
+    dao.update_employee_role(admin.id, EmployeeRole::Admin)
+        .await
+        .unwrap();
+    dao.deactivate_employee(inactive.id).await.unwrap();
+    let url = start_skjera_api(server_impl(dao.clone(), &provider).await).await;
+    let client = Client::new();
+
+    let (_, employee_token) = insert_token(&dao, &employee, ApiTokenScope::ReadOnly).await;
+    let (_, admin_token) = insert_token(&dao, &admin, ApiTokenScope::ReadOnly).await;
+
+    let get = |token: &str, id: EmployeeId| {
+        client
+            .get(format!("{}/api/employee/{}", url, id))
+            .bearer_auth(token)
+            .send()
+    };
+
+    assert_eq!(
+        StatusCode::OK,
+        get(&employee_token, admin.id).await.unwrap().status()
+    );
+    assert_eq!(
+        StatusCode::NOT_FOUND,
+        get(&employee_token, inactive.id).await.unwrap().status()
+    );
+    assert_eq!(
+        StatusCode::OK,
+        get(&admin_token, inactive.id).await.unwrap().status()
+    );
+
+    delete_test_employee(&dao, employee.id).await;
+    delete_test_employee(&dao, admin.id).await;
+    delete_test_employee(&dao, inactive.id).await;
+}
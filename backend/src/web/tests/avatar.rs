@@ -0,0 +1,111 @@
+use crate::model::*;
+use crate::web::avatar::*;
+use axum::http::HeaderValue;
+use image::{ImageFormat, RgbImage};
+use std::io::Cursor;
+
+fn some_account(id: i64, authenticated: bool, avatar: Option<&str>) -> SomeAccount {
+    SomeAccount {
+        id: SomeAccountId(id),
+        employee: EmployeeId(1),
+        network: SomeNetwork::Slack,
+        authenticated,
+        network_instance: None,
+        network_avatar: Some("https://example.com/workspace.png".to_string()),
+        subject: None,
+        name: None,
+        nick: None,
+        url: None,
+        avatar: avatar.map(|a| a.to_string()),
+        deactivated: false,
+    }
+}
+
+#[test]
+fn test_avatar_size() {
+    assert_eq!(64, avatar_size(None));
+    assert_eq!(32, avatar_size(Some(1)));
+    assert_eq!(32, avatar_size(Some(32)));
+    assert_eq!(64, avatar_size(Some(33)));
+    assert_eq!(256, avatar_size(Some(200)));
+    assert_eq!(256, avatar_size(Some(1000)));
+}
+
+#[test]
+fn test_best_avatar_url() {
+    assert_eq!(None, best_avatar_url(&[]));
+    assert_eq!(None, best_avatar_url(&[some_account(1, true, None)]));
+    assert_eq!(
+        None,
+        best_avatar_url(&[some_account(1, true, Some("file:///etc/passwd"))])
+    );
+
+    let accounts = [
+        some_account(1, false, Some("https://example.com/1.png")),
+        some_account(3, true, Some("https://example.com/3.png")),
+        some_account(2, true, Some("https://example.com/2.png")),
+    ];
+    assert_eq!(
+        Some("https://example.com/2.png"),
+        best_avatar_url(&accounts)
+    );
+}
+
+#[test]
+fn test_initials() {
+    assert_eq!("TT", initials("Trygve Laugstøl Trygvis"));
+    assert_eq!("ØL", initials("øystein laugstøl"));
+    assert_eq!("C", initials("Cher"));
+    assert_eq!("JD", initials("John <script> Doe"));
+    assert_eq!("?", initials(""));
+    assert_eq!("?", initials("<&>"));
+}
+
+#[test]
+fn test_initials_svg() {
+    let svg = initials_svg(EmployeeId(1), "Ola Nordmann", 32);
+
+    assert!(svg.starts_with("<svg "));
+    assert!(svg.contains(r#"width="32" height="32""#));
+    assert!(svg.contains(">ON</text>"));
+}
+
+#[test]
+fn test_resize_avatar() {
+    let mut png = Vec::new();
+    RgbImage::new(300, 200)
+        .write_to(&mut Cursor::new(&mut png), ImageFormat::Png)
+        .unwrap();
+
+    let images = resize_avatar(&png).unwrap();
+
+    let sizes: Vec<i32> = images.iter().map(|(size, _)| *size).collect();
+    assert_eq!(vec![32, 64, 128, 256], sizes);
+
+    for (size, data) in images {
+        let image = image::load_from_memory(&data).unwrap();
+        assert_eq!((size as u32, size as u32), (image.width(), image.height()));
+    }
+
+    assert!(resize_avatar(b"not an image").is_err());
+}
+
+#[test]
+fn test_etag_matches() {
+    let etag = r#""abc""#;
+
+    assert!(!etag_matches(None, etag));
+    assert!(etag_matches(
+        Some(&HeaderValue::from_static(r#""abc""#)),
+        etag
+    ));
+    assert!(etag_matches(
+        Some(&HeaderValue::from_static(r#""xyz", W/"abc""#)),
+        etag
+    ));
+    assert!(etag_matches(Some(&HeaderValue::from_static("*")), etag));
+    assert!(!etag_matches(
+        Some(&HeaderValue::from_static(r#""xyz""#)),
+        etag
+    ));
+}
@@ -0,0 +1,232 @@
+//! Runs the verification against a local stand-in for the AppView.
+
+use crate::web::bluesky::{verification_post, BlueskyProfile, BlueskyVerifier};
+use axum::extract::{Query, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use axum::{Json, Router};
+use serde::Deserialize;
+use serde_json::json;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tokio::net::TcpListener;
+use tokio::task::JoinHandle;
+
+const ALICE_DID: &str = "did:plc:alice";
+const CHALLENGE: &str = "skjera-0123456789abcdef";
+
+struct Post {
+    /// The DID of the feed that the post shows up in
+    feed: String,
+    did: String,
+    handle: String,
+    text: String,
+}
+
+#[derive(Default)]
+struct FakeState {
+    /// handle -> DID
+    handles: HashMap<String, String>,
+    posts: Vec<Post>,
+}
+
+type SharedState = Arc<Mutex<FakeState>>;
+
+struct FakeBluesky {
+    url: String,
+    state: SharedState,
+    server: JoinHandle<()>,
+}
+
+impl FakeBluesky {
+    async fn start() -> FakeBluesky {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+
+        let state = SharedState::default();
+
+        let app = Router::new()
+            .route(
+                "/xrpc/com.atproto.identity.resolveHandle",
+                get(resolve_handle),
+            )
+            .route("/xrpc/app.bsky.feed.getAuthorFeed", get(get_author_feed))
+            .with_state(state.clone());
+
+        let server = tokio::spawn(async move {
+            axum::serve(listener, app).await.unwrap();
+        });
+
+        FakeBluesky { url, state, server }
+    }
+
+    fn verifier(&self) -> BlueskyVerifier {
+        BlueskyVerifier::without_dns(reqwest::Client::new(), self.url.clone())
+    }
+
+    fn add_handle(&self, handle: &str, did: &str) {
+        let mut state = self.state.lock().unwrap();
+        state.handles.insert(handle.to_string(), did.to_string());
+    }
+
+    fn post(&self, did: &str, handle: &str, text: &str) {
+        self.repost(did, did, handle, text);
+    }
+
+    fn repost(&self, feed: &str, did: &str, handle: &str, text: &str) {
+        let mut state = self.state.lock().unwrap();
+        state.posts.push(Post {
+            feed: feed.to_string(),
+            did: did.to_string(),
+            handle: handle.to_string(),
+            text: text.to_string(),
+        });
+    }
+}
+
+impl Drop for FakeBluesky {
+    fn drop(&mut self) {
+        self.server.abort();
+    }
+}
+
+#[derive(Deserialize)]
+struct ResolveHandleQuery {
+    handle: String,
+}
+
+async fn resolve_handle(
+    State(state): State<SharedState>,
+    Query(query): Query<ResolveHandleQuery>,
+) -> Response {
+    match state.lock().unwrap().handles.get(&query.handle) {
+        Some(did) => Json(json!({ "did": did })).into_response(),
+        None => (
+            StatusCode::BAD_REQUEST,
+            Json(json!({"error": "InvalidRequest", "message": "Unable to resolve handle"})),
+        )
+            .into_response(),
+    }
+}
+
+#[derive(Deserialize)]
+struct AuthorFeedQuery {
+    actor: String,
+}
+
+/// The feed contains the actor's posts and everything they have reposted.
+async fn get_author_feed(
+    State(state): State<SharedState>,
+    Query(query): Query<AuthorFeedQuery>,
+) -> Json<serde_json::Value> {
+    let state = state.lock().unwrap();
+
+    let feed: Vec<_> = state
+        .posts
+        .iter()
+        .rev()
+        .filter(|post| post.feed == query.actor)
+        .map(|post| {
+            json!({
+                "post": {
+                    "author": {
+                        "did": post.did,
+                        "handle": post.handle,
+                        "displayName": format!("Name of {}", post.handle),
+                        "avatar": format!("https://cdn.example.com/{}.jpg", post.did),
+                    },
+                    "record": {
+                        "$type": "app.bsky.feed.post",
+                        "text": post.text,
+                    },
+                },
+            })
+        })
+        .collect();
+
+    Json(json!({ "feed": feed }))
+}
+
+#[tokio::test]
+async fn test_resolve_handle() {
+    let bluesky = FakeBluesky::start().await;
+    bluesky.add_handle("alice.example.com", ALICE_DID);
+
+    let verifier = bluesky.verifier();
+
+    assert_eq!(
+        ALICE_DID,
+        verifier.resolve_handle("alice.example.com").await.unwrap()
+    );
+    assert!(verifier.resolve_handle("bob.example.com").await.is_err());
+
+    bluesky.add_handle("mallory.example.com", "not a did");
+    assert!(verifier
+        .resolve_handle("mallory.example.com")
+        .await
+        .is_err());
+}
+
+#[tokio::test]
+async fn test_verify() {
+    let bluesky = FakeBluesky::start().await;
+    bluesky.add_handle("alice.example.com", ALICE_DID);
+    bluesky.post(ALICE_DID, "alice.example.com", "Hello world");
+    bluesky.post(
+        ALICE_DID,
+        "alice.example.com",
+        &verification_post(CHALLENGE),
+    );
+
+    let profile = bluesky
+        .verifier()
+        .verify("alice.example.com", CHALLENGE)
+        .await
+        .unwrap();
+
+    assert_eq!(
+        BlueskyProfile {
+            did: ALICE_DID.to_string(),
+            display_name: Some("Name of alice.example.com".to_string()),
+            avatar: Some("https://cdn.example.com/did:plc:alice.jpg".to_string()),
+        },
+        profile
+    );
+}
+
+#[tokio::test]
+async fn test_verify_requires_the_challenge() {
+    let bluesky = FakeBluesky::start().await;
+    bluesky.add_handle("alice.example.com", ALICE_DID);
+    bluesky.post(ALICE_DID, "alice.example.com", "Hello world");
+
+    let verifier = bluesky.verifier();
+    assert!(verifier
+        .verify("alice.example.com", CHALLENGE)
+        .await
+        .is_err());
+
+    // Someone else posting the challenge, and Alice reposting it, is not enough
+    bluesky.post("did:plc:bob", "bob.example.com", CHALLENGE);
+    bluesky.repost(ALICE_DID, "did:plc:bob", "bob.example.com", CHALLENGE);
+    assert!(verifier
+        .verify("alice.example.com", CHALLENGE)
+        .await
+        .is_err());
+}
+
+#[tokio::test]
+async fn test_verify_rejects_a_changed_handle() {
+    let bluesky = FakeBluesky::start().await;
+
+    // The handle still points to the DID, but the account has moved to another handle
+    bluesky.add_handle("alice.example.com", ALICE_DID);
+    bluesky.post(ALICE_DID, "alice.example.org", CHALLENGE);
+
+    assert!(bluesky
+        .verifier()
+        .verify("alice.example.com", CHALLENGE)
+        .await
+        .is_err());
+}
@@ -0,0 +1,98 @@
+use crate::model::tests::test_employee;
+use crate::model::*;
+use crate::web::calendar::birthday_calendar;
+use time::macros::{date, datetime};
+use time::Date;
+
+fn employee(id: i64, name: &str, dob: Date) -> Employee {
+    Employee {
+        name: name.to_string(),
+        dob: Some(dob),
+        ..test_employee(id)
+    }
+}
+
+#[test]
+fn test_birthday_calendar() {
+    let mut ola = employee(1, "Ola Nordmann", date!(1980 - 12 - 09));
+    ola.birthday_calendar_year = true;
+
+    let calendar = birthday_calendar(&[ola], datetime!(2026-10-18 12:30:05 UTC));
+
+    assert_eq!(
+        "BEGIN:VCALENDAR\r\n\
+         VERSION:2.0\r\n\
+         PRODID:-//Skjera//Birthdays//EN\r\n\
+         CALSCALE:GREGORIAN\r\n\
+         X-WR-CALNAME:Birthdays\r\n\
+         BEGIN:VEVENT\r\n\
+         UID:birthday-1@skjera\r\n\
+         DTSTAMP:20261018T123005Z\r\n\
+         DTSTART;VALUE=DATE:19801209\r\n\
+         DTEND;VALUE=DATE:19801210\r\n\
+         RRULE:FREQ=YEARLY\r\n\
+         SUMMARY:Ola Nordmann's birthday\r\n\
+         DESCRIPTION:Born 1980\r\n\
+         TRANSP:TRANSPARENT\r\n\
+         END:VEVENT\r\n\
+         END:VCALENDAR\r\n",
+        calendar
+    );
+}
+
+#[test]
+fn test_birthday_calendar_hides_year() {
+    let ola = employee(1, "Ola Nordmann", date!(1980 - 12 - 09));
+
+    let calendar = birthday_calendar(&[ola], datetime!(2026-10-18 12:00 UTC));
+
+    assert!(calendar.contains("DTSTART;VALUE=DATE:20261209\r\n"));
+    assert!(!calendar.contains("1980"));
+}
+
+#[test]
+fn test_birthday_calendar_leap_day() {
+    let mut kari = employee(2, "Kari Nordmann", date!(1984 - 02 - 29));
+    let now = datetime!(2026-10-18 12:00 UTC);
+
+    let calendar = birthday_calendar(&[kari.clone()], now);
+    assert!(calendar.contains("DTSTART;VALUE=DATE:20260228\r\n"));
+    assert!(calendar.contains("DTEND;VALUE=DATE:20260301\r\n"));
+    assert!(calendar.contains("RRULE:FREQ=YEARLY;BYMONTH=2;BYMONTHDAY=-1\r\n"));
+
+    kari.birthday_calendar_year = true;
+    let calendar = birthday_calendar(&[kari], now);
+    assert!(calendar.contains("DTSTART;VALUE=DATE:19840229\r\n"));
+    assert!(calendar.contains("RRULE:FREQ=YEARLY;BYMONTH=2;BYMONTHDAY=-1\r\n"));
+}
+
+#[test]
+fn test_birthday_calendar_skips_employees() {
+    let mut per = employee(3, "Per Hansen", date!(1990 - 05 - 17));
+    per.active = false;
+
+    // Hidden from the subscriber
+    let mut lise = employee(4, "Lise Hansen", date!(1991 - 06 - 01));
+    lise.dob_visibility = FieldVisibility::Hidden;
+    let lise = lise.redacted(EmployeeId(1), EmployeeRole::Employee);
+
+    let calendar = birthday_calendar(&[per, lise], datetime!(2026-10-18 12:00 UTC));
+
+    assert!(!calendar.contains("BEGIN:VEVENT"));
+}
+
+#[test]
+fn test_birthday_calendar_escaping_and_folding() {
+    let name = "Nordmann, Ola; Jr. ".repeat(5);
+    let calendar = birthday_calendar(
+        &[employee(1, &name, date!(1980 - 12 - 09))],
+        datetime!(2026-10-18 12:00 UTC),
+    );
+
+    for line in calendar.split("\r\n") {
+        assert!(line.len() <= 75, "too long: {}", line);
+    }
+
+    let unfolded = calendar.replace("\r\n ", "");
+    assert!(unfolded.contains("SUMMARY:Nordmann\\, Ola\\; Jr. Nordmann\\, Ola\\;"));
+}
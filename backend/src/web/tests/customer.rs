@@ -0,0 +1,31 @@
+use crate::web::html::assignment_dates;
+use time::macros::date;
+
+#[test]
+fn test_assignment_dates() {
+    assert_eq!(
+        Ok((date!(2025 - 01 - 01), None)),
+        assignment_dates("2025-01-01", "")
+    );
+    assert_eq!(
+        Ok((date!(2025 - 01 - 01), Some(date!(2025 - 01 - 01)))),
+        assignment_dates(" 2025-01-01 ", "2025-01-01")
+    );
+
+    assert_eq!(
+        Err("The start date is missing.".to_string()),
+        assignment_dates("", "2025-01-01")
+    );
+    assert_eq!(
+        Err("The start date 2025-13-01 is invalid.".to_string()),
+        assignment_dates("2025-13-01", "")
+    );
+    assert_eq!(
+        Err("The end date tomorrow is invalid.".to_string()),
+        assignment_dates("2025-01-01", "tomorrow")
+    );
+    assert_eq!(
+        Err("The end date is before the start date.".to_string()),
+        assignment_dates("2025-01-02", "2025-01-01")
+    );
+}
@@ -0,0 +1,303 @@
+//! A minimal OpenID Connect provider that runs on localhost, so the oauth flows can be tested
+//! without talking to Slack or Google. It also answers Slack's `users.profile.get`, and plays
+//! GitHub and GitLab with their token endpoints and `/user` APIs.
+
+use axum::extract::State;
+use axum::http::header::AUTHORIZATION;
+use axum::http::{HeaderMap, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Form, Json, Router};
+use chrono::{Duration, Utc};
+use oauth2::{PkceCodeChallenge, PkceCodeVerifier};
+use openidconnect::core::{
+    CoreGenderClaim, CoreJsonWebKeySet, CoreJsonWebKeyType, CoreJweContentEncryptionAlgorithm,
+    CoreJwsSigningAlgorithm, CoreRsaPrivateSigningKey,
+};
+use openidconnect::{
+    AccessToken, AdditionalClaims, Audience, EndUserEmail, EndUserName, IdToken, IdTokenClaims,
+    IssuerUrl, JsonWebKeyId, Nonce, PrivateSigningKey, StandardClaims, SubjectIdentifier,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tokio::net::TcpListener;
+use tokio::task::JoinHandle;
+use url::Url;
+
+pub(crate) const CLIENT_ID: &str = "test-client";
+pub(crate) const CLIENT_SECRET: &str = "test-secret";
+
+const SIGNING_KEY: &str = include_str!("test-signing-key.pem");
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+struct FakeAdditionalClaims {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    hd: Option<String>,
+}
+impl AdditionalClaims for FakeAdditionalClaims {}
+
+type FakeIdToken = IdToken<
+    FakeAdditionalClaims,
+    CoreGenderClaim,
+    CoreJweContentEncryptionAlgorithm,
+    CoreJwsSigningAlgorithm,
+    CoreJsonWebKeyType,
+>;
+
+struct Authorization {
+    subject: String,
+    email: String,
+    hosted_domain: Option<String>,
+    nonce: Option<String>,
+    code_challenge: Option<String>,
+}
+
+#[derive(Default)]
+struct ProviderState {
+    issuer: String,
+    authorizations: HashMap<String, Authorization>,
+    access_tokens: HashMap<String, String>,
+    token_requests: usize,
+}
+
+type SharedState = Arc<Mutex<ProviderState>>;
+
+pub(crate) struct FakeOidcProvider {
+    pub(crate) issuer: String,
+    state: SharedState,
+    server: JoinHandle<()>,
+}
+
+impl FakeOidcProvider {
+    pub(crate) async fn start() -> FakeOidcProvider {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let issuer = format!("http://{}", listener.local_addr().unwrap());
+
+        let state = Arc::new(Mutex::new(ProviderState {
+            issuer: issuer.clone(),
+            ..Default::default()
+        }));
+
+        let app = Router::new()
+            .route("/.well-known/openid-configuration", get(discovery))
+            .route("/jwks", get(jwks))
+            .route("/token", post(token))
+            .route("/userinfo", get(userinfo))
+            .route("/api/users.profile.get", get(users_profile_get))
+            .route("/login/oauth/access_token", post(token))
+            .route("/oauth/token", post(token))
+            .route("/user", get(forge_user))
+            .route("/api/v4/user", get(forge_user))
+            .with_state(state.clone());
+
+        let server = tokio::spawn(async move {
+            axum::serve(listener, app).await.unwrap();
+        });
+
+        FakeOidcProvider {
+            issuer,
+            state,
+            server,
+        }
+    }
+
+    /// Plays the part of the user logging in at the provider after being sent to `auth_url`.
+    /// Returns the code that the provider would redirect back with. The user is in the
+    /// scienta.no Workspace domain.
+    pub(crate) fn authorize(&self, auth_url: &Url, subject: &str) -> String {
+        self.authorize_as(auth_url, subject, "scienta.no", Some("scienta.no"))
+    }
+
+    /// Like [Self::authorize], but for a personal account that isn't in any Workspace domain.
+    pub(crate) fn authorize_personal(&self, auth_url: &Url, subject: &str) -> String {
+        self.authorize_as(auth_url, subject, "gmail.com", None)
+    }
+
+    fn authorize_as(
+        &self,
+        auth_url: &Url,
+        subject: &str,
+        email_domain: &str,
+        hosted_domain: Option<&str>,
+    ) -> String {
+        let params: HashMap<_, _> = auth_url.query_pairs().into_owned().collect();
+
+        let code = format!("code-{}", subject);
+
+        self.state.lock().unwrap().authorizations.insert(
+            code.clone(),
+            Authorization {
+                subject: subject.to_string(),
+                email: format!("{}@{}", subject, email_domain),
+                hosted_domain: hosted_domain.map(|hd| hd.to_string()),
+                nonce: params.get("nonce").cloned(),
+                code_challenge: params.get("code_challenge").cloned(),
+            },
+        );
+
+        code
+    }
+
+    pub(crate) fn token_requests(&self) -> usize {
+        self.state.lock().unwrap().token_requests
+    }
+}
+
+impl Drop for FakeOidcProvider {
+    fn drop(&mut self) {
+        self.server.abort();
+    }
+}
+
+fn signing_key() -> CoreRsaPrivateSigningKey {
+    CoreRsaPrivateSigningKey::from_pem(SIGNING_KEY, Some(JsonWebKeyId::new("test".to_string())))
+        .unwrap()
+}
+
+async fn discovery(State(state): State<SharedState>) -> Json<serde_json::Value> {
+    let issuer = state.lock().unwrap().issuer.clone();
+
+    Json(json!({
+        "issuer": issuer,
+        "authorization_endpoint": format!("{}/authorize", issuer),
+        "token_endpoint": format!("{}/token", issuer),
+        "userinfo_endpoint": format!("{}/userinfo", issuer),
+        "jwks_uri": format!("{}/jwks", issuer),
+        "response_types_supported": ["code"],
+        "subject_types_supported": ["public"],
+        "id_token_signing_alg_values_supported": ["RS256"],
+    }))
+}
+
+async fn jwks() -> Json<CoreJsonWebKeySet> {
+    Json(CoreJsonWebKeySet::new(vec![
+        signing_key().as_verification_key()
+    ]))
+}
+
+#[derive(Deserialize)]
+struct TokenForm {
+    code: String,
+    code_verifier: Option<String>,
+}
+
+async fn token(State(state): State<SharedState>, Form(form): Form<TokenForm>) -> Response {
+    let mut state = state.lock().unwrap();
+    state.token_requests += 1;
+
+    let Some(authorization) = state.authorizations.remove(&form.code) else {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(json!({"error": "invalid_grant"})),
+        )
+            .into_response();
+    };
+
+    if let Some(code_challenge) = &authorization.code_challenge {
+        let verifier = PkceCodeVerifier::new(form.code_verifier.unwrap_or_default());
+        if PkceCodeChallenge::from_code_verifier_sha256(&verifier).as_str() != code_challenge {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(json!({"error": "invalid_grant"})),
+            )
+                .into_response();
+        }
+    }
+
+    let access_token = AccessToken::new(format!("access-{}", authorization.subject));
+    state
+        .access_tokens
+        .insert(access_token.secret().clone(), authorization.subject.clone());
+
+    let claims = IdTokenClaims::new(
+        IssuerUrl::new(state.issuer.clone()).unwrap(),
+        vec![Audience::new(CLIENT_ID.to_string())],
+        Utc::now() + Duration::hours(1),
+        Utc::now(),
+        StandardClaims::new(SubjectIdentifier::new(authorization.subject.clone()))
+            .set_email(Some(EndUserEmail::new(authorization.email)))
+            .set_email_verified(Some(true))
+            .set_name(Some(
+                EndUserName::new(format!("Name of {}", authorization.subject)).into(),
+            )),
+        FakeAdditionalClaims {
+            hd: authorization.hosted_domain,
+        },
+    )
+    .set_nonce(authorization.nonce.map(Nonce::new));
+
+    let id_token = FakeIdToken::new(
+        claims,
+        &signing_key(),
+        CoreJwsSigningAlgorithm::RsaSsaPkcs1V15Sha256,
+        Some(&access_token),
+        None,
+    )
+    .unwrap();
+
+    Json(json!({
+        "access_token": access_token.secret(),
+        "token_type": "Bearer",
+        "expires_in": 3600,
+        "id_token": id_token.to_string(),
+    }))
+    .into_response()
+}
+
+fn subject(state: &SharedState, headers: &HeaderMap) -> Option<String> {
+    let token = headers
+        .get(AUTHORIZATION)?
+        .to_str()
+        .ok()?
+        .strip_prefix("Bearer ")?;
+
+    state.lock().unwrap().access_tokens.get(token).cloned()
+}
+
+async fn userinfo(State(state): State<SharedState>, headers: HeaderMap) -> Response {
+    let Some(subject) = subject(&state, &headers) else {
+        return StatusCode::UNAUTHORIZED.into_response();
+    };
+
+    Json(json!({
+        "sub": subject,
+        "name": format!("Name of {}", subject),
+        "picture": format!("https://example.com/{}.png", subject),
+        "https://slack.com/team_id": "T0001",
+        "https://slack.com/team_domain": "scienta",
+    }))
+    .into_response()
+}
+
+async fn users_profile_get(State(state): State<SharedState>, headers: HeaderMap) -> Response {
+    let Some(subject) = subject(&state, &headers) else {
+        return Json(json!({"ok": false, "error": "invalid_auth"})).into_response();
+    };
+
+    Json(json!({
+        "ok": true,
+        "profile": {
+            "display_name": format!("{}-nick", subject),
+        },
+    }))
+    .into_response()
+}
+
+/// GitHub's `/user` uses `login` and `html_url` where GitLab's `/api/v4/user` uses `username` and
+/// `web_url`, this answers with GitHub's names.
+async fn forge_user(State(state): State<SharedState>, headers: HeaderMap) -> Response {
+    let Some(subject) = subject(&state, &headers) else {
+        return StatusCode::UNAUTHORIZED.into_response();
+    };
+
+    Json(json!({
+        "id": 4242,
+        "login": format!("{}-login", subject),
+        "name": format!("Name of {}", subject),
+        "html_url": format!("https://example.com/{}", subject),
+        "avatar_url": format!("https://example.com/{}.png", subject),
+    }))
+    .into_response()
+}
@@ -0,0 +1,304 @@
+//! A local stand-in for the parts of the Slack Web API that are called with the bot token.
+
+use axum::extract::{Query, State};
+use axum::http::{HeaderMap, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use serde::Deserialize;
+use serde_json::json;
+use std::sync::{Arc, Mutex};
+use tokio::net::TcpListener;
+use tokio::task::JoinHandle;
+
+pub(crate) const TOKEN: &str = "xoxb-test";
+pub(crate) const TEAM: &str = "T0001";
+
+#[derive(Default)]
+struct FakeState {
+    /// In the order that users.list returns them
+    users: Vec<serde_json::Value>,
+    /// The number of requests to users.info and users.list to reject with a 429
+    rate_limited: usize,
+    retry_after: &'static str,
+    /// The requests to chat.postMessage and chat.update, in order
+    posted: Vec<serde_json::Value>,
+    updated: Vec<serde_json::Value>,
+    /// The error that chat.postMessage fails with
+    post_message_error: Option<String>,
+}
+
+type SharedState = Arc<Mutex<FakeState>>;
+
+pub(crate) struct FakeSlackApi {
+    pub(crate) api_url: String,
+    state: SharedState,
+    server: JoinHandle<()>,
+}
+
+impl FakeSlackApi {
+    pub(crate) async fn start() -> FakeSlackApi {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let api_url = format!("http://{}/api", listener.local_addr().unwrap());
+
+        let state = SharedState::default();
+
+        let app = Router::new()
+            .route("/api/auth.test", post(auth_test))
+            .route("/api/users.info", get(users_info))
+            .route("/api/users.list", get(users_list))
+            .route("/api/chat.postMessage", post(chat_post_message))
+            .route("/api/chat.update", post(chat_update))
+            .with_state(state.clone());
+
+        let server = tokio::spawn(async move {
+            axum::serve(listener, app).await.unwrap();
+        });
+
+        FakeSlackApi {
+            api_url,
+            state,
+            server,
+        }
+    }
+
+    pub(crate) fn add_user(&self, id: &str, deleted: bool, real_name: &str, display_name: &str) {
+        self.add_user_json(json!({
+            "id": id,
+            "team_id": TEAM,
+            "name": id.to_lowercase(),
+            "deleted": deleted,
+            "profile": {
+                "real_name": real_name,
+                "display_name": display_name,
+                "image_192": format!("https://avatars.example.com/{}_192.png", id),
+                "image_512": format!("https://avatars.example.com/{}_512.png", id),
+                "email": format!("{}@example.com", real_name.to_lowercase().replace(' ', ".")),
+            },
+        }));
+    }
+
+    /// A multi-channel guest, or a single-channel guest if `ultra`.
+    pub(crate) fn add_guest(&self, id: &str, real_name: &str, ultra: bool) {
+        self.add_user_json(json!({
+            "id": id,
+            "team_id": TEAM,
+            "is_restricted": !ultra,
+            "is_ultra_restricted": ultra,
+            "profile": {
+                "real_name": real_name,
+                "display_name": "",
+                "email": format!("{}@example.com", real_name.to_lowercase().replace(' ', ".")),
+            },
+        }));
+    }
+
+    /// A user in another workspace, shared through Slack Connect.
+    pub(crate) fn add_external_user(&self, id: &str, team: &str, real_name: &str) {
+        self.add_user_json(json!({
+            "id": id,
+            "team_id": team,
+            "profile": {
+                "real_name": real_name,
+                "display_name": "",
+                "email": format!("{}@example.com", real_name.to_lowercase().replace(' ', ".")),
+            },
+        }));
+    }
+
+    pub(crate) fn add_bot(&self, id: &str, real_name: &str) {
+        self.add_user_json(json!({
+            "id": id,
+            "team_id": TEAM,
+            "is_bot": true,
+            "profile": {
+                "real_name": real_name,
+                "display_name": "",
+            },
+        }));
+    }
+
+    fn add_user_json(&self, user: serde_json::Value) {
+        self.state.lock().unwrap().users.push(user);
+    }
+
+    pub(crate) fn set_rate_limited(&self) {
+        self.rate_limit(usize::MAX, "30");
+    }
+
+    /// Rejects the next `requests` requests, with a Retry-After that doesn't make the tests wait.
+    pub(crate) fn rate_limit_next(&self, requests: usize) {
+        self.rate_limit(requests, "0");
+    }
+
+    fn rate_limit(&self, requests: usize, retry_after: &'static str) {
+        let mut state = self.state.lock().unwrap();
+        state.rate_limited = requests;
+        state.retry_after = retry_after;
+    }
+
+    pub(crate) fn set_post_message_error(&self, error: Option<&str>) {
+        self.state.lock().unwrap().post_message_error = error.map(String::from);
+    }
+
+    pub(crate) fn posted(&self) -> Vec<serde_json::Value> {
+        self.state.lock().unwrap().posted.clone()
+    }
+
+    pub(crate) fn updated(&self) -> Vec<serde_json::Value> {
+        self.state.lock().unwrap().updated.clone()
+    }
+}
+
+impl Drop for FakeSlackApi {
+    fn drop(&mut self) {
+        self.server.abort();
+    }
+}
+
+fn is_authorized(headers: &HeaderMap) -> bool {
+    headers
+        .get("authorization")
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value == format!("Bearer {}", TOKEN))
+}
+
+async fn auth_test(headers: HeaderMap) -> Json<serde_json::Value> {
+    match is_authorized(&headers) {
+        true => Json(json!({"ok": true, "team_id": TEAM, "user_id": "UBOT"})),
+        false => Json(json!({"ok": false, "error": "invalid_auth"})),
+    }
+}
+
+fn rate_limited(state: &mut FakeState) -> Option<Response> {
+    if state.rate_limited == 0 {
+        return None;
+    }
+
+    if state.rate_limited != usize::MAX {
+        state.rate_limited -= 1;
+    }
+
+    Some(
+        (
+            StatusCode::TOO_MANY_REQUESTS,
+            [("retry-after", state.retry_after)],
+        )
+            .into_response(),
+    )
+}
+
+#[derive(Deserialize)]
+struct UsersInfoQuery {
+    user: String,
+}
+
+/// Like Slack, errors are reported with `"ok": false` and a 200 status.
+async fn users_info(
+    State(state): State<SharedState>,
+    headers: HeaderMap,
+    Query(query): Query<UsersInfoQuery>,
+) -> Response {
+    let mut state = state.lock().unwrap();
+
+    if let Some(response) = rate_limited(&mut state) {
+        return response;
+    }
+
+    if !is_authorized(&headers) {
+        return Json(json!({"ok": false, "error": "invalid_auth"})).into_response();
+    }
+
+    match state.users.iter().find(|user| user["id"] == query.user) {
+        Some(user) => Json(json!({"ok": true, "user": user})).into_response(),
+        None => Json(json!({"ok": false, "error": "user_not_found"})).into_response(),
+    }
+}
+
+#[derive(Deserialize)]
+struct UsersListQuery {
+    limit: usize,
+    #[serde(default)]
+    cursor: String,
+}
+
+/// The cursor is the index of the next user, so paging can be tested with a small limit.
+async fn users_list(
+    State(state): State<SharedState>,
+    headers: HeaderMap,
+    Query(query): Query<UsersListQuery>,
+) -> Response {
+    let mut state = state.lock().unwrap();
+
+    if let Some(response) = rate_limited(&mut state) {
+        return response;
+    }
+
+    if !is_authorized(&headers) {
+        return Json(json!({"ok": false, "error": "invalid_auth"})).into_response();
+    }
+
+    let start: usize = query.cursor.parse().unwrap_or(0);
+    let end = (start + query.limit).min(state.users.len());
+
+    let next_cursor = match end < state.users.len() {
+        true => end.to_string(),
+        false => String::new(),
+    };
+
+    Json(json!({
+        "ok": true,
+        "members": state.users[start..end],
+        "response_metadata": {"next_cursor": next_cursor},
+    }))
+    .into_response()
+}
+
+/// The messages get a timestamp from the number of messages posted so far.
+async fn chat_post_message(
+    State(state): State<SharedState>,
+    headers: HeaderMap,
+    Json(request): Json<serde_json::Value>,
+) -> Json<serde_json::Value> {
+    let mut state = state.lock().unwrap();
+
+    if !is_authorized(&headers) {
+        return Json(json!({"ok": false, "error": "invalid_auth"}));
+    }
+
+    if let Some(error) = &state.post_message_error {
+        return Json(json!({"ok": false, "error": error}));
+    }
+
+    state.posted.push(request.clone());
+
+    let ts = format!("1700000000.{:06}", state.posted.len());
+
+    Json(json!({
+        "ok": true,
+        "channel": request["channel"],
+        "ts": ts,
+        "message": {"ts": ts, "text": request["text"]},
+    }))
+}
+
+async fn chat_update(
+    State(state): State<SharedState>,
+    headers: HeaderMap,
+    Json(request): Json<serde_json::Value>,
+) -> Json<serde_json::Value> {
+    let mut state = state.lock().unwrap();
+
+    if !is_authorized(&headers) {
+        return Json(json!({"ok": false, "error": "invalid_auth"}));
+    }
+
+    state.updated.push(request.clone());
+
+    Json(json!({
+        "ok": true,
+        "channel": request["channel"],
+        "ts": request["ts"],
+        "message": {"text": request["text"]},
+    }))
+}
@@ -0,0 +1,188 @@
+use crate::web::forge::{github_api_url, gitlab_instance, ForgeConnect, ForgeUser};
+use crate::web::oauth::{InvalidOauthState, OauthResponse};
+use crate::web::tests::fake_oidc::{FakeOidcProvider, CLIENT_ID, CLIENT_SECRET};
+use crate::AppError;
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+use std::sync::Arc;
+use tower_sessions::{MemoryStore, Session};
+use url::Url;
+
+fn github_connect(provider: &FakeOidcProvider) -> ForgeConnect {
+    ForgeConnect::github(
+        reqwest::Client::new(),
+        &provider.issuer,
+        provider.issuer.clone(),
+        CLIENT_ID.to_string(),
+        CLIENT_SECRET.to_string(),
+        "http://localhost:8080/oauth/github".to_string(),
+    )
+    .unwrap()
+}
+
+fn gitlab_connect(provider: &FakeOidcProvider) -> ForgeConnect {
+    ForgeConnect::gitlab(
+        reqwest::Client::new(),
+        &provider.issuer,
+        CLIENT_ID.to_string(),
+        CLIENT_SECRET.to_string(),
+        "http://localhost:8080/oauth/gitlab".to_string(),
+    )
+    .unwrap()
+}
+
+fn new_session() -> Session {
+    Session::new(None, Arc::new(MemoryStore::default()), None)
+}
+
+fn state_of(auth_url: &Url) -> Option<String> {
+    auth_url
+        .query_pairs()
+        .find(|(key, _)| key == "state")
+        .map(|(_, value)| value.to_string())
+}
+
+#[tokio::test]
+async fn test_github_connect() {
+    let provider = FakeOidcProvider::start().await;
+    let github_connect = github_connect(&provider);
+    let session = new_session();
+
+    let auth_url = github_connect.connect_begin(&session).await.unwrap();
+    assert!(auth_url
+        .as_str()
+        .starts_with(&format!("{}/login/oauth/authorize", provider.issuer)));
+
+    let response = OauthResponse {
+        code: provider.authorize(&auth_url, "octocat"),
+        state: state_of(&auth_url),
+    };
+
+    let user = github_connect
+        .connect_continue(&session, response)
+        .await
+        .unwrap();
+
+    assert_eq!(
+        ForgeUser {
+            subject: "4242".to_string(),
+            nick: "octocat-login".to_string(),
+            name: Some("Name of octocat".to_string()),
+            url: Some("https://example.com/octocat".to_string()),
+            avatar: Some("https://example.com/octocat.png".to_string()),
+        },
+        user
+    );
+
+    // The connect data is consumed, so the same response can't be used twice.
+    let response = OauthResponse {
+        code: provider.authorize(&auth_url, "octocat"),
+        state: state_of(&auth_url),
+    };
+    assert!(github_connect
+        .connect_continue(&session, response)
+        .await
+        .is_err());
+}
+
+#[tokio::test]
+async fn test_gitlab_connect() {
+    let provider = FakeOidcProvider::start().await;
+    let gitlab_connect = gitlab_connect(&provider);
+    let session = new_session();
+
+    let auth_url = gitlab_connect.connect_begin(&session).await.unwrap();
+    assert!(auth_url
+        .as_str()
+        .starts_with(&format!("{}/oauth/authorize", provider.issuer)));
+
+    let response = OauthResponse {
+        code: provider.authorize(&auth_url, "tanuki"),
+        state: state_of(&auth_url),
+    };
+
+    let user = gitlab_connect
+        .connect_continue(&session, response)
+        .await
+        .unwrap();
+
+    assert_eq!("4242", user.subject);
+    assert_eq!("tanuki-login", user.nick);
+}
+
+#[tokio::test]
+async fn test_connect_rejects_other_network() {
+    let provider = FakeOidcProvider::start().await;
+    let session = new_session();
+
+    // The flow was started for GitHub, but the response is sent to GitLab's callback
+    let auth_url = github_connect(&provider)
+        .connect_begin(&session)
+        .await
+        .unwrap();
+
+    let response = OauthResponse {
+        code: provider.authorize(&auth_url, "octocat"),
+        state: state_of(&auth_url),
+    };
+    assert!(gitlab_connect(&provider)
+        .connect_continue(&session, response)
+        .await
+        .unwrap_err()
+        .is::<InvalidOauthState>());
+
+    assert_eq!(provider.token_requests(), 0);
+}
+
+#[tokio::test]
+async fn test_connect_rejects_invalid_state() {
+    let provider = FakeOidcProvider::start().await;
+    let github_connect = github_connect(&provider);
+    let session = new_session();
+
+    let auth_url = github_connect.connect_begin(&session).await.unwrap();
+
+    let response = OauthResponse {
+        code: provider.authorize(&auth_url, "octocat"),
+        state: Some("not-the-state".to_string()),
+    };
+    let error = github_connect
+        .connect_continue(&session, response)
+        .await
+        .unwrap_err();
+    assert!(error.is::<InvalidOauthState>());
+
+    // The user gets a 400, it's not an error in the server
+    assert_eq!(
+        StatusCode::BAD_REQUEST,
+        AppError::from(error).into_response().status()
+    );
+
+    assert_eq!(provider.token_requests(), 0);
+}
+
+#[test]
+fn test_gitlab_instance() {
+    assert_eq!(None, gitlab_instance("https://gitlab.com").unwrap());
+    assert_eq!(
+        Some("gitlab.example.com".to_string()),
+        gitlab_instance("https://gitlab.example.com").unwrap()
+    );
+    assert_eq!(
+        Some("127.0.0.1:8929".to_string()),
+        gitlab_instance("http://127.0.0.1:8929").unwrap()
+    );
+    assert!(gitlab_instance("not a url").is_err());
+}
+
+#[test]
+fn test_github_api_url() {
+    assert_eq!(
+        "https://api.github.com",
+        github_api_url("https://github.com")
+    );
+    assert_eq!(
+        "https://github.example.com/api/v3",
+        github_api_url("https://github.example.com")
+    );
+}
@@ -0,0 +1,123 @@
+use crate::session::GoogleLoginData;
+use crate::web::oauth::{GoogleLogin, OauthResponse};
+use crate::web::tests::fake_oidc::{FakeOidcProvider, CLIENT_ID, CLIENT_SECRET};
+use std::sync::Arc;
+use tower_sessions::{MemoryStore, Session};
+use url::Url;
+
+async fn google_login(provider: &FakeOidcProvider) -> GoogleLogin {
+    GoogleLogin::new(
+        provider.issuer.clone(),
+        CLIENT_ID.to_string(),
+        CLIENT_SECRET.to_string(),
+        "http://localhost:8080/oauth/google".to_string(),
+    )
+    .await
+    .unwrap()
+}
+
+/// Starts a login, returning the URL the user is sent to and the data stored in the session.
+async fn begin(google_login: &GoogleLogin) -> (Url, GoogleLoginData) {
+    let session = Session::new(None, Arc::new(MemoryStore::default()), None);
+
+    let auth_url = google_login.login_begin(&session).await.unwrap();
+
+    let login = session
+        .get::<GoogleLoginData>(GoogleLoginData::SESSION_KEY)
+        .await
+        .unwrap()
+        .unwrap();
+
+    (auth_url, login)
+}
+
+fn state_of(auth_url: &Url) -> Option<String> {
+    auth_url
+        .query_pairs()
+        .find(|(key, _)| key == "state")
+        .map(|(_, value)| value.to_string())
+}
+
+#[tokio::test]
+async fn test_google_login() {
+    let provider = FakeOidcProvider::start().await;
+    let google_login = google_login(&provider).await;
+
+    let (auth_url, login) = begin(&google_login).await;
+
+    let response = OauthResponse {
+        code: provider.authorize(&auth_url, "trygvis"),
+        state: state_of(&auth_url),
+    };
+
+    let profile = google_login
+        .login_continue(login, response)
+        .await
+        .unwrap()
+        .unwrap();
+
+    assert_eq!(profile.sub, "trygvis");
+    assert_eq!(profile.email, "trygvis@scienta.no");
+    assert_eq!(profile.name, "Name of trygvis");
+    assert_eq!(profile.hosted_domain.as_deref(), Some("scienta.no"));
+}
+
+#[tokio::test]
+async fn test_google_login_personal_account() {
+    let provider = FakeOidcProvider::start().await;
+    let google_login = google_login(&provider).await;
+
+    let (auth_url, login) = begin(&google_login).await;
+
+    let response = OauthResponse {
+        code: provider.authorize_personal(&auth_url, "someone"),
+        state: state_of(&auth_url),
+    };
+
+    let profile = google_login
+        .login_continue(login, response)
+        .await
+        .unwrap()
+        .unwrap();
+
+    assert_eq!(profile.email, "someone@gmail.com");
+    assert_eq!(profile.hosted_domain, None);
+}
+
+#[tokio::test]
+async fn test_google_login_rejects_invalid_state() {
+    let provider = FakeOidcProvider::start().await;
+    let google_login = google_login(&provider).await;
+
+    let (auth_url, login) = begin(&google_login).await;
+
+    let response = OauthResponse {
+        code: provider.authorize(&auth_url, "trygvis"),
+        state: Some("not-the-state".to_string()),
+    };
+
+    let profile = google_login.login_continue(login, response).await.unwrap();
+
+    assert!(profile.is_none());
+    assert_eq!(provider.token_requests(), 0);
+}
+
+#[tokio::test]
+async fn test_google_login_rejects_other_nonce() {
+    let provider = FakeOidcProvider::start().await;
+    let google_login = google_login(&provider).await;
+
+    // The code was issued for the first login, but is used with the state of the second.
+    let (auth_url, _) = begin(&google_login).await;
+    let (other_auth_url, other_login) = begin(&google_login).await;
+
+    let response = OauthResponse {
+        code: provider.authorize(&auth_url, "trygvis"),
+        state: state_of(&other_auth_url),
+    };
+
+    assert!(google_login
+        .login_continue(other_login, response)
+        .await
+        .is_err());
+}
@@ -0,0 +1,12 @@
+mod api;
+mod avatar;
+mod bluesky;
+mod calendar;
+mod customer;
+mod fake_oidc;
+pub(crate) mod fake_slack;
+mod forge_connect;
+mod google_login;
+mod slack_connect;
+mod slack_import;
+mod vcard;
@@ -0,0 +1,120 @@
+use crate::web::oauth::{InvalidOauthState, OauthResponse};
+use crate::web::slack::SlackConnect;
+use crate::web::tests::fake_oidc::{FakeOidcProvider, CLIENT_ID, CLIENT_SECRET};
+use std::sync::Arc;
+use tower_sessions::{MemoryStore, Session};
+use url::Url;
+
+async fn slack_connect(provider: &FakeOidcProvider) -> SlackConnect {
+    SlackConnect::new(
+        reqwest::Client::new(),
+        provider.issuer.clone(),
+        format!("{}/api", provider.issuer),
+        CLIENT_ID.to_string(),
+        CLIENT_SECRET.to_string(),
+        "http://localhost:8080/oauth/slack".to_string(),
+    )
+    .await
+    .unwrap()
+}
+
+fn new_session() -> Session {
+    Session::new(None, Arc::new(MemoryStore::default()), None)
+}
+
+fn state_of(auth_url: &Url) -> Option<String> {
+    auth_url
+        .query_pairs()
+        .find(|(key, _)| key == "state")
+        .map(|(_, value)| value.to_string())
+}
+
+#[tokio::test]
+async fn test_slack_connect() {
+    let provider = FakeOidcProvider::start().await;
+    let slack_connect = slack_connect(&provider).await;
+    let session = new_session();
+
+    let auth_url = slack_connect.slack_connect_begin(&session).await.unwrap();
+    assert!(auth_url.as_str().starts_with(&provider.issuer));
+
+    let response = OauthResponse {
+        code: provider.authorize(&auth_url, "U0001"),
+        state: state_of(&auth_url),
+    };
+
+    let (user_info, user_profile) = slack_connect
+        .slack_connect_continue(&session, response)
+        .await
+        .unwrap();
+
+    assert_eq!(user_info.subject().as_str(), "U0001");
+    assert_eq!(
+        user_info.additional_claims().team_id.as_deref(),
+        Some("T0001")
+    );
+    assert_eq!(user_profile.display_name, "U0001-nick");
+
+    // The connect data is consumed, so the same response can't be used twice.
+    let response = OauthResponse {
+        code: provider.authorize(&auth_url, "U0001"),
+        state: state_of(&auth_url),
+    };
+    assert!(slack_connect
+        .slack_connect_continue(&session, response)
+        .await
+        .is_err());
+}
+
+#[tokio::test]
+async fn test_slack_connect_rejects_invalid_state() {
+    let provider = FakeOidcProvider::start().await;
+    let slack_connect = slack_connect(&provider).await;
+    let session = new_session();
+
+    let auth_url = slack_connect.slack_connect_begin(&session).await.unwrap();
+    let code = provider.authorize(&auth_url, "U0001");
+
+    let response = OauthResponse {
+        code: code.clone(),
+        state: Some("not-the-state".to_string()),
+    };
+    assert!(slack_connect
+        .slack_connect_continue(&session, response)
+        .await
+        .unwrap_err()
+        .is::<InvalidOauthState>());
+
+    // The connect data was removed by the first response
+    let response = OauthResponse { code, state: None };
+    assert!(slack_connect
+        .slack_connect_continue(&session, response)
+        .await
+        .unwrap_err()
+        .is::<InvalidOauthState>());
+
+    assert_eq!(provider.token_requests(), 0);
+}
+
+#[tokio::test]
+async fn test_slack_connect_requires_begin() {
+    let provider = FakeOidcProvider::start().await;
+    let slack_connect = slack_connect(&provider).await;
+
+    // The flow was started in another session
+    let auth_url = slack_connect
+        .slack_connect_begin(&new_session())
+        .await
+        .unwrap();
+
+    let response = OauthResponse {
+        code: provider.authorize(&auth_url, "U0001"),
+        state: state_of(&auth_url),
+    };
+    assert!(slack_connect
+        .slack_connect_continue(&new_session(), response)
+        .await
+        .is_err());
+
+    assert_eq!(provider.token_requests(), 0);
+}
@@ -0,0 +1,122 @@
+use crate::model::tests::test_employee;
+use crate::model::*;
+use crate::slack_client::users_list;
+use crate::web::slack_import::{plan_slack_import, SlackImportAction};
+use crate::web::tests::fake_slack::{FakeSlackApi, TEAM, TOKEN};
+
+fn employee(id: i64, email: &str) -> Employee {
+    Employee {
+        email: email.to_string(),
+        name: "Someone".to_string(),
+        ..test_employee(id)
+    }
+}
+
+fn slack_account(employee: i64, subject: &str) -> SomeAccount {
+    SomeAccount {
+        id: SomeAccountId(employee),
+        employee: EmployeeId(employee),
+        network: SomeNetwork::Slack,
+        authenticated: true,
+        network_instance: Some(TEAM.to_string()),
+        network_avatar: None,
+        subject: Some(subject.to_string()),
+        name: None,
+        nick: None,
+        url: None,
+        avatar: None,
+        deactivated: false,
+    }
+}
+
+#[tokio::test]
+async fn test_users_list() {
+    let slack = FakeSlackApi::start().await;
+    for i in 0..250 {
+        slack.add_user(&format!("U{:04}", i), false, &format!("User {}", i), "");
+    }
+    slack.add_bot("B0001", "Skjera");
+
+    let client = reqwest::Client::new();
+
+    let users = users_list(&client, &slack.api_url, TOKEN).await.unwrap();
+    assert_eq!(251, users.len());
+    assert_eq!("U0000", users[0].id);
+    assert_eq!(
+        Some("user.249@example.com"),
+        users[249].profile.email.as_deref()
+    );
+    assert!(users[250].is_bot);
+
+    assert!(users_list(&client, &slack.api_url, "xoxb-wrong")
+        .await
+        .is_err());
+
+    slack.set_rate_limited();
+    assert!(users_list(&client, &slack.api_url, TOKEN).await.is_err());
+}
+
+#[tokio::test]
+async fn test_plan_slack_import() {
+    let slack = FakeSlackApi::start().await;
+    slack.add_user("U0001", false, "Ola Nordmann", "ola");
+    slack.add_user("U0002", false, "Kari Nordmann", "");
+    slack.add_user("U0003", false, "Per Hansen", "per");
+    slack.add_user("U0004", false, "Lise Hansen", "");
+    slack.add_user("U0005", true, "Old Timer", "");
+    slack.add_user("U0006", false, "Ola Nordmann", "ola2");
+    slack.add_guest("U0007", "Guest Multi", false);
+    slack.add_guest("U0008", "Guest Single", true);
+    slack.add_external_user("U0009", "T0002", "Partner Person");
+    slack.add_bot("B0001", "Skjera");
+
+    let client = reqwest::Client::new();
+    let users = users_list(&client, &slack.api_url, TOKEN).await.unwrap();
+
+    let employees = vec![
+        employee(1, "Ola.Nordmann@example.com"),
+        employee(2, "per.hansen@example.com"),
+        employee(3, "lise.hansen@example.com"),
+    ];
+
+    // Lise is linked to another user, Per already linked
+    let accounts = vec![slack_account(2, "U0003"), slack_account(3, "U0099")];
+
+    let rows = plan_slack_import(TEAM, users, &employees, &accounts);
+
+    let actions: Vec<_> = rows
+        .iter()
+        .map(|row| (row.user_id.as_str(), row.action.clone()))
+        .collect();
+
+    assert_eq!(
+        vec![
+            ("U0001", SlackImportAction::LinkAccount(EmployeeId(1))),
+            ("U0002", SlackImportAction::CreateEmployee),
+            ("U0003", SlackImportAction::AlreadyLinked(EmployeeId(2))),
+            ("U0004", SlackImportAction::LinkedToOtherUser(EmployeeId(3))),
+            ("U0005", SlackImportAction::Skipped("deactivated in Slack")),
+            (
+                "U0006",
+                SlackImportAction::Skipped("another Slack user has the same email")
+            ),
+            ("U0007", SlackImportAction::Skipped("a guest in Slack")),
+            ("U0008", SlackImportAction::Skipped("a guest in Slack")),
+            (
+                "U0009",
+                SlackImportAction::Skipped("from another workspace")
+            ),
+        ],
+        actions
+    );
+
+    let kari = &rows[1];
+    assert_eq!("Kari Nordmann", kari.name);
+    assert_eq!(Some("kari.nordmann@example.com"), kari.email.as_deref());
+    assert_eq!(None, kari.nick);
+    assert_eq!(
+        Some("https://avatars.example.com/U0002_512.png"),
+        kari.avatar.as_deref()
+    );
+    assert_eq!(Some("ola"), rows[0].nick.as_deref());
+}
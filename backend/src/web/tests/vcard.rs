@@ -0,0 +1,117 @@
+use crate::model::tests::test_employee;
+use crate::model::*;
+use crate::web::vcard::vcard;
+
+fn employee() -> Employee {
+    Employee {
+        name: "Ola Johan Nordmann".to_string(),
+        ..test_employee(1)
+    }
+}
+
+fn account(id: i64, network: SomeNetwork, nick: &str, url: Option<&str>) -> SomeAccount {
+    SomeAccount {
+        id: SomeAccountId(id),
+        employee: EmployeeId(1),
+        network,
+        authenticated: true,
+        network_instance: None,
+        network_avatar: None,
+        subject: None,
+        name: None,
+        nick: Some(nick.to_string()),
+        url: url.map(|url| url.to_string()),
+        avatar: None,
+        deactivated: false,
+    }
+}
+
+#[test]
+fn test_vcard() {
+    let mut slack = account(3, SomeNetwork::Slack, "ola", None);
+    slack.deactivated = true;
+
+    let accounts = vec![
+        account(
+            1,
+            SomeNetwork::Github,
+            "ola",
+            Some("https://github.com/ola"),
+        ),
+        account(2, SomeNetwork::Bluesky, "ola.bsky.social", None),
+        slack,
+    ];
+
+    let card = vcard(&employee(), &accounts, None);
+
+    assert_eq!(
+        "BEGIN:VCARD\r\n\
+         VERSION:3.0\r\n\
+         UID:urn:skjera:employee:1\r\n\
+         FN:Ola Johan Nordmann\r\n\
+         N:Nordmann;Ola Johan;;;\r\n\
+         EMAIL;TYPE=INTERNET,WORK:ola@scienta.no\r\n\
+         EMAIL;TYPE=INTERNET,HOME:ola@example.com\r\n\
+         TEL;TYPE=CELL:+4790000000\r\n\
+         BDAY:1980-12-09\r\n\
+         URL;TYPE=github:https://github.com/ola\r\n\
+         URL;TYPE=bluesky:https://bsky.app/profile/ola.bsky.social\r\n\
+         END:VCARD\r\n",
+        card
+    );
+}
+
+#[test]
+fn test_vcard_redacted() {
+    let employee = employee().redacted(EmployeeId(2), EmployeeRole::Employee);
+
+    let card = vcard(&employee, &[], None);
+
+    assert!(card.contains("BDAY:1980-12-09"));
+    assert!(!card.contains("ola@example.com"));
+    assert!(!card.contains("TEL"));
+}
+
+#[test]
+fn test_vcard_escaping_and_folding() {
+    let mut employee = employee();
+    employee.name = "Nordmann, Ola; Jr.".to_string();
+
+    let photo = vec![0u8; 300];
+    let card = vcard(&employee, &[], Some(&photo));
+
+    assert!(card.contains("FN:Nordmann\\, Ola\\; Jr.\r\n"));
+    assert!(card.contains("N:Jr.;Nordmann\\, Ola\\;;;;\r\n"));
+    assert!(card.contains("PHOTO;ENCODING=b;TYPE=PNG:AAAA"));
+
+    for line in card.split("\r\n") {
+        assert!(line.len() <= 75, "too long: {}", line);
+    }
+
+    let unfolded = card.replace("\r\n ", "");
+    assert!(unfolded.contains(&format!("TYPE=PNG:{}\r\n", "AAAA".repeat(100))));
+}
+
+#[test]
+fn test_vcard_urls() {
+    let accounts = vec![
+        account(
+            1,
+            SomeNetwork::Github,
+            "ola",
+            Some("https://example.com/a,b;c"),
+        ),
+        account(
+            2,
+            SomeNetwork::Gitlab,
+            "ola",
+            Some("https://example.com/\r\nTEL:+4799999999"),
+        ),
+    ];
+
+    let card = vcard(&employee(), &accounts, None);
+
+    assert!(card.contains("URL;TYPE=github:https://example.com/a,b;c\r\n"));
+    assert!(!card.contains("TYPE=gitlab"));
+    assert!(!card.contains("99999999"));
+}
@@ -0,0 +1,214 @@
+use crate::model::*;
+use crate::web::avatar::{best_avatar_url, cached_avatar};
+use crate::web::html;
+use crate::{AppError, AuthSession, ServerImpl};
+use axum::extract::{Path, State};
+use axum::http::header::{CONTENT_DISPOSITION, CONTENT_TYPE};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use base64::Engine;
+use tracing::instrument;
+
+const VCARD_CONTENT_TYPE: &str = "text/vcard; charset=utf-8";
+
+/// The size of the embedded photos.
+const PHOTO_SIZE: u32 = 128;
+
+/// Escapes a text value, `;` and `,` separate the components of structured values. iCalendar
+/// uses the same rules.
+pub(crate) fn escape(s: &str) -> String {
+    s.replace('\\', "\\\\")
+        .replace(',', "\\,")
+        .replace(';', "\\;")
+        .replace("\r\n", "\\n")
+        .replace('\n', "\\n")
+}
+
+/// Lines are folded at 75 octets, without splitting UTF-8 characters.
+pub(crate) fn fold(line: &str) -> String {
+    let mut folded = String::with_capacity(line.len() + line.len() / 74 * 3);
+    let mut length = 0;
+
+    for c in line.chars() {
+        if length + c.len_utf8() > 75 {
+            folded.push_str("\r\n ");
+            length = 1;
+        }
+        folded.push(c);
+        length += c.len_utf8();
+    }
+
+    folded.push_str("\r\n");
+    folded
+}
+
+/// The family name is the last word of the name, everything before it is the given names.
+fn structured_name(name: &str) -> String {
+    match name.trim().rsplit_once(' ') {
+        Some((given, family)) => format!("{};{};;;", escape(family), escape(given.trim())),
+        None => format!("{};;;;", escape(name.trim())),
+    }
+}
+
+/// Creates a vCard 3.0, the version that most phones and mail clients can import. The employee
+/// has to be redacted for the viewer already, fields that are hidden are left out.
+pub(crate) fn vcard(employee: &Employee, accounts: &[SomeAccount], photo: Option<&[u8]>) -> String {
+    let mut lines = vec![
+        "BEGIN:VCARD".to_string(),
+        "VERSION:3.0".to_string(),
+        format!("UID:urn:skjera:employee:{}", employee.id),
+        format!("FN:{}", escape(&employee.name)),
+        format!("N:{}", structured_name(&employee.name)),
+        format!("EMAIL;TYPE=INTERNET,WORK:{}", escape(&employee.email)),
+    ];
+
+    if let Some(personal_email) = &employee.personal_email {
+        lines.push(format!(
+            "EMAIL;TYPE=INTERNET,HOME:{}",
+            escape(personal_email)
+        ));
+    }
+    if let Some(phone_number) = &employee.phone_number {
+        lines.push(format!("TEL;TYPE=CELL:{}", escape(phone_number)));
+    }
+    if let Some(dob) = employee.dob {
+        lines.push(format!("BDAY:{}", dob));
+    }
+
+    for account in accounts.iter().filter(|a| !a.deactivated) {
+        let definition = account.network.definition();
+
+        let url = account.url.clone().or_else(|| {
+            account
+                .nick
+                .as_deref()
+                .and_then(|nick| definition.profile_url(nick))
+        });
+
+        // A URL is not text, so it is not escaped. A line break would start a new property.
+        if let Some(url) = url.filter(|url| !url.contains(['\r', '\n'])) {
+            lines.push(format!("URL;TYPE={}:{}", definition.id, url));
+        }
+    }
+
+    if let Some(photo) = photo {
+        lines.push(format!(
+            "PHOTO;ENCODING=b;TYPE=PNG:{}",
+            base64::engine::general_purpose::STANDARD.encode(photo)
+        ));
+    }
+
+    lines.push("END:VCARD".to_string());
+
+    lines.iter().map(|line| fold(line)).collect()
+}
+
+fn vcard_response(filename: &str, body: String) -> Response {
+    (
+        [
+            (CONTENT_TYPE, VCARD_CONTENT_TYPE.to_string()),
+            (
+                CONTENT_DISPOSITION,
+                format!("attachment; filename=\"{}\"", filename),
+            ),
+        ],
+        body,
+    )
+        .into_response()
+}
+
+/// Serves both the profile page and `/employee/{employee_id}.vcf`, the router can't match
+/// parameters with a suffix.
+#[instrument(skip(app, session))]
+pub async fn employee(
+    State(app): State<ServerImpl>,
+    session: AuthSession,
+    Path(employee_id): Path<String>,
+) -> Result<Response, AppError> {
+    let (employee_id, is_vcard) = match employee_id.strip_suffix(".vcf") {
+        Some(employee_id) => (employee_id, true),
+        None => (employee_id.as_str(), false),
+    };
+
+    let Ok(employee_id) = employee_id.parse().map(EmployeeId) else {
+        return Ok(StatusCode::BAD_REQUEST.into_response());
+    };
+
+    if !is_vcard {
+        return Ok(html::employee(State(app), session, Path(employee_id))
+            .await?
+            .into_response());
+    }
+
+    let user = session.user.unwrap();
+
+    let Some(employee) = app.employee_dao.employee_by_id(employee_id).await? else {
+        return Ok(StatusCode::NOT_FOUND.into_response());
+    };
+    if !employee.active && !user.is_admin() {
+        return Ok(StatusCode::NOT_FOUND.into_response());
+    }
+    let employee = user.redact(employee);
+
+    let accounts = app
+        .employee_dao
+        .some_accounts_by_employee(employee_id)
+        .await?;
+
+    let photo = match best_avatar_url(&accounts) {
+        Some(url) => cached_avatar(&app, url, PHOTO_SIZE).await?,
+        None => None,
+    };
+
+    let body = vcard(&employee, &accounts, photo.as_deref());
+
+    Ok(vcard_response(&format!("{}.vcf", employee_id), body))
+}
+
+/// All active employees in one file.
+///
+/// Fetching every avatar would make the export too slow, so only the photos that are cached
+/// already are included. The avatars are cached when they are shown in the employee list.
+#[instrument(skip(app, session))]
+pub async fn employees(
+    State(app): State<ServerImpl>,
+    session: AuthSession,
+) -> Result<Response, AppError> {
+    let user = session.user.unwrap();
+
+    let employees: Vec<_> = app
+        .employee_dao
+        .all_employees()
+        .await?
+        .into_iter()
+        .filter(|e| e.active)
+        .collect();
+
+    let ids: Vec<_> = employees.iter().map(|e| e.id).collect();
+    let accounts = app.employee_dao.some_accounts_by_employees(&ids).await?;
+
+    let mut body = String::new();
+    for employee in employees {
+        let accounts: Vec<_> = accounts
+            .iter()
+            .filter(|a| a.employee == employee.id)
+            .cloned()
+            .collect();
+
+        let photo = match best_avatar_url(&accounts) {
+            Some(url) => match app.employee_dao.avatar_by_url(url).await? {
+                Some(avatar) => {
+                    app.employee_dao
+                        .avatar_image(avatar.id, PHOTO_SIZE as i32)
+                        .await?
+                }
+                None => None,
+            },
+            None => None,
+        };
+
+        body.push_str(&vcard(&user.redact(employee), &accounts, photo.as_deref()));
+    }
+
+    Ok(vcard_response("employees.vcf", body))
+}
@@ -1,19 +1,23 @@
 use crate::web::oauth::oauth_google;
 use crate::web::slack_bot::*;
-use crate::web::{html, slack};
+use crate::web::{admin, api, avatar, bluesky, calendar, forge, html, slack, slack_import, vcard};
 use crate::ServerImpl;
 use anyhow::Result;
+use axum::middleware::from_fn;
 use axum::routing::{get, post};
 use axum::Router;
 use slack_morphism::prelude::*;
 use std::sync::Arc;
 
-pub(crate) fn create_router(app: &ServerImpl) -> Result<(Router<ServerImpl>, Router<ServerImpl>)> {
+pub(crate) fn create_router(
+    app: &ServerImpl,
+) -> Result<(Router<ServerImpl>, Router<ServerImpl>, Router)> {
     let mut public = Router::new()
         .route("/", get(html::hello_world))
         .route("/login", get(html::login))
         .route("/logout", get(html::logout))
-        .route("/oauth/google", get(oauth_google));
+        .route("/oauth/google", get(oauth_google))
+        .route("/calendar/birthdays.ics", get(calendar::birthdays));
 
     if app.slack_client.is_some() {
         let slack: Router<ServerImpl> = create_slack(app)?;
@@ -21,7 +25,64 @@ pub(crate) fn create_router(app: &ServerImpl) -> Result<(Router<ServerImpl>, Rou
         public = public.merge(slack);
     }
 
-    let private = Router::new()
+    let admin = Router::new()
+        .route("/admin/login-access", get(admin::login_access))
+        .route("/admin/login-access", post(admin::add_login_access))
+        .route(
+            "/admin/login-access/{login_access_id}",
+            post(admin::update_login_access),
+        )
+        .route(
+            "/admin/login-access/{login_access_id}/delete",
+            post(admin::delete_login_access),
+        )
+        .route("/admin/employee/{employee_id}", get(admin::employee))
+        .route(
+            "/admin/employee/{employee_id}",
+            post(admin::update_employee),
+        )
+        .route(
+            "/admin/employee/{employee_id}/deactivate",
+            post(admin::deactivate_employee),
+        )
+        .route(
+            "/admin/employee/{employee_id}/reactivate",
+            post(admin::reactivate_employee),
+        )
+        .route(
+            "/admin/slack-import",
+            get(slack_import::slack_import_preview),
+        )
+        .route("/admin/slack-import", post(slack_import::slack_import_run))
+        .route("/admin/directory", get(admin::directory))
+        .route("/admin/directory/export", get(admin::export_directory))
+        .route("/admin/directory/import", post(admin::import_directory))
+        .route(
+            "/employee/{employee_id}/create-message",
+            get(html::employee_create_message),
+        )
+        .route("/customer", post(html::add_customer))
+        .route("/customer/{customer_id}", post(html::update_customer))
+        .route(
+            "/customer/{customer_id}/delete",
+            post(html::delete_customer),
+        )
+        .route(
+            "/customer/{customer_id}/assignment",
+            post(html::add_assignment),
+        )
+        .route(
+            "/customer/{customer_id}/assignment/{assignment_id}",
+            post(html::update_assignment),
+        )
+        .route(
+            "/customer/{customer_id}/assignment/{assignment_id}/delete",
+            post(html::delete_assignment),
+        )
+        .route_layer(from_fn(admin::admin_required));
+
+    let mut private = Router::new()
+        .merge(admin)
         .route("/me", get(html::get_me))
         .route("/me", post(html::post_me))
         .route("/me/some_account/add", post(html::add_some_account))
@@ -29,15 +90,50 @@ pub(crate) fn create_router(app: &ServerImpl) -> Result<(Router<ServerImpl>, Rou
             "/me/some_account/{some_account_id}/delete",
             post(html::delete_some_account),
         )
-        .route("/employee/{employee_id}", get(html::employee))
         .route(
-            "/employee/{employee_id}/create-message",
-            get(html::employee_create_message),
+            "/me/some_account/{some_account_id}/verify",
+            get(bluesky::bluesky_verify),
+        )
+        .route(
+            "/me/some_account/{some_account_id}/verify",
+            post(bluesky::bluesky_verify_check),
         )
-        .route("/oauth/slack-begin", get(slack::oauth_slack_begin))
-        .route("/oauth/slack", get(slack::oauth_slack));
+        .route("/me/api-token", post(html::add_api_token))
+        .route(
+            "/me/api-token/{api_token_id}/delete",
+            post(html::delete_api_token),
+        )
+        .route("/employee", get(html::employees))
+        .route("/employee.vcf", get(vcard::employees))
+        .route("/employee/{employee_id}", get(vcard::employee))
+        .route("/avatar/{employee_id}", get(avatar::avatar))
+        .route("/customer", get(html::customers))
+        .route("/customer/{customer_id}", get(html::customer));
+
+    // Accounts can only be connected to the networks that are configured
+    if app.slack_connect.is_some() {
+        private = private
+            .route("/oauth/slack-begin", get(slack::oauth_slack_begin))
+            .route("/oauth/slack", get(slack::oauth_slack));
+    }
+
+    if app.github_connect.is_some() {
+        private = private
+            .route("/oauth/github-begin", get(forge::oauth_github_begin))
+            .route("/oauth/github", get(forge::oauth_github));
+    }
+
+    if app.gitlab_connect.is_some() {
+        private = private
+            .route("/oauth/gitlab-begin", get(forge::oauth_gitlab_begin))
+            .route("/oauth/gitlab", get(forge::oauth_gitlab));
+    }
+
+    let api = skjera_api::server::new(app.clone())
+        .route_layer(from_fn(api::with_session))
+        .route_layer(from_fn(api::bearer_auth));
 
-    Ok((public, private))
+    Ok((public, private, api))
 }
 
 fn create_slack(app: &ServerImpl) -> Result<Router<ServerImpl>> {
@@ -62,7 +158,7 @@ fn create_slack(app: &ServerImpl) -> Result<Router<ServerImpl>> {
             "/api/slack-push",
             post(slack_push_event).layer(
                 listener
-                    .events_layer(&signing_secret)
+                    .events_layer(signing_secret)
                     .with_event_extractor(SlackEventsExtractors::push_event()),
             ),
         )
@@ -70,7 +166,7 @@ fn create_slack(app: &ServerImpl) -> Result<Router<ServerImpl>> {
             "/api/slack-interaction",
             post(slack_interaction_event).layer(
                 listener
-                    .events_layer(&signing_secret)
+                    .events_layer(signing_secret)
                     .with_event_extractor(SlackEventsExtractors::interaction_event()),
             ),
         );
@@ -0,0 +1,7 @@
+CREATE TABLE skjera.birthday_announcement
+(
+    employee BIGINT NOT NULL REFERENCES skjera.employee,
+    date     DATE   NOT NULL,
+
+    PRIMARY KEY (employee, date)
+);
@@ -0,0 +1,15 @@
+CREATE TABLE skjera.birthday_conversation
+(
+    id                   BIGINT DEFAULT NEXTVAL('id_seq'),
+    team                 VARCHAR     NOT NULL,
+    channel              VARCHAR     NOT NULL,
+    ts                   VARCHAR     NOT NULL,
+    who                  VARCHAR     NOT NULL,
+    employee             BIGINT REFERENCES skjera.employee,
+    birthday_message     TEXT,
+    generate_interaction UUID,
+    send_interaction     UUID,
+    expires_at           TIMESTAMPTZ NOT NULL,
+
+    PRIMARY KEY (id)
+);
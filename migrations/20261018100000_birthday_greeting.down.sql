@@ -0,0 +1,4 @@
+DROP TABLE IF EXISTS skjera.birthday_greeting;
+
+ALTER TABLE skjera.birthday_conversation
+    DROP COLUMN IF EXISTS send_dm_interaction;
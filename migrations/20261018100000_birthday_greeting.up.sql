@@ -0,0 +1,16 @@
+ALTER TABLE skjera.birthday_conversation
+    ADD COLUMN send_dm_interaction UUID;
+
+CREATE TABLE skjera.birthday_greeting
+(
+    id       BIGINT               DEFAULT NEXTVAL('id_seq'),
+    employee BIGINT REFERENCES skjera.employee,
+    who      VARCHAR     NOT NULL,
+    channel  VARCHAR     NOT NULL,
+    ts       VARCHAR     NOT NULL,
+    message  TEXT        NOT NULL,
+    sent_by  VARCHAR,
+    sent_at  TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    PRIMARY KEY (id)
+);
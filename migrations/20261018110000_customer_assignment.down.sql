@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS skjera.assignment;
+DROP TABLE IF EXISTS skjera.customer;
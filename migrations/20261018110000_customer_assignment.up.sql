@@ -0,0 +1,21 @@
+CREATE TABLE skjera.customer
+(
+    id   BIGINT  NOT NULL DEFAULT NEXTVAL('id_seq'),
+    name VARCHAR NOT NULL,
+
+    PRIMARY KEY (id),
+    CONSTRAINT uq_customer_name UNIQUE (name)
+);
+
+CREATE TABLE skjera.assignment
+(
+    id          BIGINT  NOT NULL DEFAULT NEXTVAL('id_seq'),
+    customer    BIGINT  NOT NULL REFERENCES skjera.customer,
+    employee    BIGINT  NOT NULL REFERENCES skjera.employee,
+    description VARCHAR NOT NULL,
+    start_date  DATE    NOT NULL,
+    end_date    DATE,
+
+    PRIMARY KEY (id),
+    CONSTRAINT ck_assignment_dates CHECK (end_date IS NULL OR end_date >= start_date)
+);
@@ -0,0 +1,15 @@
+CREATE TABLE skjera.news_item
+(
+    id           BIGINT      NOT NULL DEFAULT NEXTVAL('id_seq'),
+    kind         VARCHAR     NOT NULL,
+    publish_date TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    title        VARCHAR     NOT NULL,
+    body         TEXT        NOT NULL,
+    employee     BIGINT REFERENCES skjera.employee ON DELETE SET NULL,
+    customer     BIGINT REFERENCES skjera.customer ON DELETE SET NULL,
+    assignment   BIGINT REFERENCES skjera.assignment ON DELETE SET NULL,
+
+    PRIMARY KEY (id)
+);
+
+CREATE INDEX ix_news_item_publish_date ON skjera.news_item (publish_date DESC);
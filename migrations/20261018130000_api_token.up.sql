@@ -0,0 +1,14 @@
+CREATE TABLE skjera.api_token
+(
+    id           BIGINT      NOT NULL DEFAULT NEXTVAL('id_seq'),
+    employee     BIGINT      NOT NULL REFERENCES skjera.employee ON DELETE CASCADE,
+    name         VARCHAR     NOT NULL,
+    scope        VARCHAR     NOT NULL,
+    token_hash   BYTEA       NOT NULL,
+    created_at   TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    last_used_at TIMESTAMPTZ,
+
+    PRIMARY KEY (id),
+    CONSTRAINT uq_api_token_token_hash UNIQUE (token_hash),
+    CONSTRAINT ck_api_token_scope CHECK (scope IN ('read-only', 'read-write'))
+);
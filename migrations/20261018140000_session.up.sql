@@ -0,0 +1,10 @@
+CREATE TABLE skjera.session
+(
+    id          VARCHAR     NOT NULL,
+    data        JSONB       NOT NULL,
+    expiry_date TIMESTAMPTZ NOT NULL,
+
+    PRIMARY KEY (id)
+);
+
+CREATE INDEX ix_session_expiry_date ON skjera.session (expiry_date);
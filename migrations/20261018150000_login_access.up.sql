@@ -0,0 +1,13 @@
+CREATE TABLE skjera.login_access
+(
+    id         BIGINT      NOT NULL DEFAULT NEXTVAL('id_seq'),
+    email      VARCHAR     NOT NULL,
+    name       VARCHAR,
+    status     VARCHAR     NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    decided_at TIMESTAMPTZ,
+
+    PRIMARY KEY (id),
+    CONSTRAINT uq_login_access_email UNIQUE (email),
+    CONSTRAINT ck_login_access_status CHECK (status IN ('pending', 'allowed', 'denied'))
+);
@@ -0,0 +1,4 @@
+ALTER TABLE skjera.employee
+    DROP CONSTRAINT ck_employee_role,
+    DROP COLUMN active,
+    DROP COLUMN role;
@@ -0,0 +1,4 @@
+ALTER TABLE skjera.employee
+    ADD COLUMN role   VARCHAR NOT NULL DEFAULT 'employee',
+    ADD COLUMN active BOOLEAN NOT NULL DEFAULT TRUE,
+    ADD CONSTRAINT ck_employee_role CHECK (role IN ('employee', 'admin'));
@@ -0,0 +1,5 @@
+ALTER TABLE skjera.employee
+    DROP COLUMN noa_slack_name,
+    DROP COLUMN scienta_slack_name,
+    DROP COLUMN phone_number,
+    DROP COLUMN personal_email;
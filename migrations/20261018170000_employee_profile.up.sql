@@ -0,0 +1,5 @@
+ALTER TABLE skjera.employee
+    ADD COLUMN personal_email     VARCHAR,
+    ADD COLUMN phone_number       VARCHAR,
+    ADD COLUMN scienta_slack_name VARCHAR,
+    ADD COLUMN noa_slack_name     VARCHAR;
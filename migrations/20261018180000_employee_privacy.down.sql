@@ -0,0 +1,8 @@
+ALTER TABLE skjera.employee
+    DROP CONSTRAINT ck_employee_phone_number_visibility,
+    DROP CONSTRAINT ck_employee_personal_email_visibility,
+    DROP CONSTRAINT ck_employee_dob_visibility,
+    DROP COLUMN birthday_announcement,
+    DROP COLUMN phone_number_visibility,
+    DROP COLUMN personal_email_visibility,
+    DROP COLUMN dob_visibility;
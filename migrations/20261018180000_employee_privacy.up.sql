@@ -0,0 +1,11 @@
+ALTER TABLE skjera.employee
+    ADD COLUMN dob_visibility            VARCHAR NOT NULL DEFAULT 'everyone',
+    ADD COLUMN personal_email_visibility VARCHAR NOT NULL DEFAULT 'admins',
+    ADD COLUMN phone_number_visibility   VARCHAR NOT NULL DEFAULT 'everyone',
+    ADD COLUMN birthday_announcement     BOOLEAN NOT NULL DEFAULT TRUE,
+    ADD CONSTRAINT ck_employee_dob_visibility
+        CHECK (dob_visibility IN ('everyone', 'admins', 'hidden')),
+    ADD CONSTRAINT ck_employee_personal_email_visibility
+        CHECK (personal_email_visibility IN ('everyone', 'admins', 'hidden')),
+    ADD CONSTRAINT ck_employee_phone_number_visibility
+        CHECK (phone_number_visibility IN ('everyone', 'admins', 'hidden'));
@@ -0,0 +1,5 @@
+DROP INDEX skjera.ix_some_account_nick_trgm;
+DROP INDEX skjera.ix_employee_email_trgm;
+DROP INDEX skjera.ix_employee_name_trgm;
+
+DROP EXTENSION IF EXISTS pg_trgm;
@@ -0,0 +1,6 @@
+-- Trigram indexes make the ILIKE '%...%' searches on the directory page fast
+CREATE EXTENSION IF NOT EXISTS pg_trgm SCHEMA skjera;
+
+CREATE INDEX ix_employee_name_trgm ON skjera.employee USING GIN (name skjera.gin_trgm_ops);
+CREATE INDEX ix_employee_email_trgm ON skjera.employee USING GIN (email skjera.gin_trgm_ops);
+CREATE INDEX ix_some_account_nick_trgm ON skjera.some_account USING GIN (nick skjera.gin_trgm_ops);
@@ -0,0 +1,2 @@
+DROP TABLE skjera.avatar_image;
+DROP TABLE skjera.avatar;
@@ -0,0 +1,22 @@
+-- Avatars fetched from the SoMe networks, keyed by their upstream URL
+CREATE TABLE skjera.avatar
+(
+    id         BIGINT      NOT NULL DEFAULT NEXTVAL('id_seq'),
+    url        VARCHAR     NOT NULL,
+    -- The ETag that the upstream server sent, used when revalidating
+    etag       VARCHAR,
+    fetched_at TIMESTAMPTZ NOT NULL,
+
+    PRIMARY KEY (id),
+    CONSTRAINT uq_avatar_url UNIQUE (url)
+);
+
+-- The avatar resized to each of the standard sizes, as PNG
+CREATE TABLE skjera.avatar_image
+(
+    avatar BIGINT  NOT NULL REFERENCES skjera.avatar ON DELETE CASCADE,
+    size   INTEGER NOT NULL,
+    data   BYTEA   NOT NULL,
+
+    PRIMARY KEY (avatar, size)
+);
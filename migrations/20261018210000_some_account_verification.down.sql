@@ -0,0 +1 @@
+DROP TABLE skjera.some_account_verification;
@@ -0,0 +1,9 @@
+-- The challenges that employees post on a network to prove that they own the account
+CREATE TABLE skjera.some_account_verification
+(
+    some_account BIGINT      NOT NULL REFERENCES skjera.some_account ON DELETE CASCADE,
+    challenge    VARCHAR     NOT NULL,
+    created_at   TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    PRIMARY KEY (some_account)
+);
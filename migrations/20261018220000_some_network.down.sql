@@ -0,0 +1,3 @@
+ALTER TABLE skjera.some_account
+    DROP CONSTRAINT ck_some_account_network_instance,
+    DROP CONSTRAINT ck_some_account_network;
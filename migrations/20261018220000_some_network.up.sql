@@ -0,0 +1,12 @@
+-- The networks are defined in the code, see SomeNetworkDefinition
+UPDATE skjera.some_account
+SET network='linked-in'
+WHERE network = 'linkedin';
+
+UPDATE skjera.some_account
+SET network='x'
+WHERE network = 'twitter';
+
+ALTER TABLE skjera.some_account
+    ADD CONSTRAINT ck_some_account_network CHECK (network IN ('bluesky', 'github', 'gitlab', 'linked-in', 'slack', 'x')),
+    ADD CONSTRAINT ck_some_account_network_instance CHECK (network_instance IS NULL OR network IN ('gitlab', 'slack'));
@@ -0,0 +1,2 @@
+ALTER TABLE skjera.some_account
+    DROP COLUMN deactivated;
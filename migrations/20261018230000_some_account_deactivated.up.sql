@@ -0,0 +1,3 @@
+-- Set when the account has been deactivated on the network, see SlackProfileSyncActor
+ALTER TABLE skjera.some_account
+    ADD COLUMN deactivated BOOLEAN NOT NULL DEFAULT FALSE;
@@ -0,0 +1,4 @@
+ALTER TABLE skjera.some_account
+    DROP CONSTRAINT uq_some_account_subject,
+    DROP CONSTRAINT uq_some_account_network,
+    ADD CONSTRAINT uq_some_account_network UNIQUE (network, network_instance);
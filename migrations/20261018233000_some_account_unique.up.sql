@@ -0,0 +1,6 @@
+-- The old constraint allowed only one account per network instance across all employees, so a
+-- Slack workspace could only be linked to one employee.
+ALTER TABLE skjera.some_account
+    DROP CONSTRAINT uq_some_account_network,
+    ADD CONSTRAINT uq_some_account_network UNIQUE (employee, network, network_instance),
+    ADD CONSTRAINT uq_some_account_subject UNIQUE (network, network_instance, subject);
@@ -0,0 +1,8 @@
+DELETE FROM skjera.api_token WHERE scope = 'calendar';
+
+ALTER TABLE skjera.api_token
+    DROP CONSTRAINT ck_api_token_scope,
+    ADD CONSTRAINT ck_api_token_scope CHECK (scope IN ('read-only', 'read-write'));
+
+ALTER TABLE skjera.employee
+    DROP COLUMN birthday_calendar_year;
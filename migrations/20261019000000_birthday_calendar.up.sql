@@ -0,0 +1,6 @@
+ALTER TABLE skjera.employee
+    ADD COLUMN birthday_calendar_year BOOLEAN NOT NULL DEFAULT FALSE;
+
+ALTER TABLE skjera.api_token
+    DROP CONSTRAINT ck_api_token_scope,
+    ADD CONSTRAINT ck_api_token_scope CHECK (scope IN ('read-only', 'read-write', 'calendar'));
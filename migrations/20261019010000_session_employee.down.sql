@@ -0,0 +1,2 @@
+ALTER TABLE skjera.session
+    DROP COLUMN employee;
@@ -0,0 +1,9 @@
+ALTER TABLE skjera.session
+    ADD COLUMN employee BIGINT REFERENCES skjera.employee ON DELETE CASCADE;
+
+UPDATE skjera.session s
+SET employee = e.id
+FROM skjera.employee e
+WHERE e.id = (s.data -> 'axum-login.data' ->> 'user_id')::BIGINT;
+
+CREATE INDEX ix_session_employee ON skjera.session (employee);
@@ -0,0 +1,6 @@
+DROP INDEX skjera.uq_some_account_subject;
+
+ALTER TABLE skjera.some_account
+    DROP CONSTRAINT uq_some_account_network,
+    ADD CONSTRAINT uq_some_account_network UNIQUE (employee, network, network_instance),
+    ADD CONSTRAINT uq_some_account_subject UNIQUE (network, network_instance, subject);
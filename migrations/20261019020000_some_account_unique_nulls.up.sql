@@ -0,0 +1,16 @@
+-- Most networks have no network_instance, and NULLs are distinct in a plain UNIQUE constraint, so
+-- neither constraint held for them. Keep the oldest account of any duplicates before adding them
+-- back. A NULL subject (manually added accounts) is still allowed any number of times.
+DELETE FROM skjera.some_account a
+    USING skjera.some_account b
+WHERE a.id > b.id
+  AND a.network = b.network
+  AND a.network_instance IS NOT DISTINCT FROM b.network_instance
+  AND (a.employee = b.employee OR a.subject = b.subject);
+
+ALTER TABLE skjera.some_account
+    DROP CONSTRAINT uq_some_account_network,
+    DROP CONSTRAINT uq_some_account_subject,
+    ADD CONSTRAINT uq_some_account_network UNIQUE NULLS NOT DISTINCT (employee, network, network_instance);
+
+CREATE UNIQUE INDEX uq_some_account_subject ON skjera.some_account (network, COALESCE(network_instance, ''), subject);
@@ -0,0 +1,2 @@
+ALTER TABLE skjera.employee
+    ALTER COLUMN phone_number_visibility SET DEFAULT 'everyone';
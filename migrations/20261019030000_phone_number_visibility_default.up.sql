@@ -0,0 +1,8 @@
+-- Phone numbers are as private as personal emails. The column was added with 'everyone' as the
+-- default, so existing rows with that value were not chosen by the employee.
+ALTER TABLE skjera.employee
+    ALTER COLUMN phone_number_visibility SET DEFAULT 'admins';
+
+UPDATE skjera.employee
+SET phone_number_visibility = 'admins'
+WHERE phone_number_visibility = 'everyone';
@@ -1,6 +1,6 @@
 use async_trait::async_trait;
 use axum::extract::*;
-use axum_extra::extract::{CookieJar, Multipart};
+use axum_extra::extract::{CookieJar, Host, Multipart};
 use bytes::Bytes;
 use http::Method;
 use serde::{Deserialize, Serialize};
@@ -22,12 +22,12 @@ pub enum MetaHealthzResponse {
 /// Meta
 #[async_trait]
 #[allow(clippy::ptr_arg)]
-pub trait Meta {
+pub trait Meta<E: std::fmt::Debug + Send + Sync + 'static = ()>: super::ErrorHandler<E> {
     /// MetaHealthz - GET /meta/healthz
     async fn meta_healthz(
     &self,
-    method: Method,
-    host: Host,
-    cookies: CookieJar,
-    ) -> Result<MetaHealthzResponse, String>;
+    method: &Method,
+    host: &Host,
+    cookies: &CookieJar,
+    ) -> Result<MetaHealthzResponse, E>;
 }
@@ -1,4 +1,34 @@
-pub mod html;
 pub mod meta;
 pub mod skjera;
 
+/// API Key Authentication - Header.
+#[async_trait::async_trait]
+pub trait ApiKeyAuthHeader {
+    type Claims;
+
+    /// Extracting Claims from Header. Return None if the Claims are invalid.
+    async fn extract_claims_from_header(&self, headers: &axum::http::header::HeaderMap, key: &str) -> Option<Self::Claims>;
+}
+
+/// Cookie Authentication.
+#[async_trait::async_trait]
+pub trait CookieAuthentication {
+    type Claims;
+
+    /// Extracting Claims from Cookie. Return None if the Claims are invalid.
+    async fn extract_claims_from_cookie(&self, cookies: &axum_extra::extract::CookieJar, key: &str) -> Option<Self::Claims>;
+}
+
+// Error handler for unhandled errors.
+#[async_trait::async_trait]
+pub trait ErrorHandler<E: std::fmt::Debug + Send + Sync + 'static = ()> {
+    #[allow(unused_variables)]
+    #[tracing::instrument(skip_all)]
+    async fn handle_error(&self, method: &::http::Method, host: &axum_extra::extract::Host, cookies: &axum_extra::extract::CookieJar, error: E) -> Result<axum::response::Response, http::StatusCode> {
+        tracing::error!("Unhandled error: {:?}", error);
+        axum::response::Response::builder()
+            .status(http::StatusCode::INTERNAL_SERVER_ERROR)
+            .body(axum::body::Body::empty())
+            .map_err(|_| http::StatusCode::INTERNAL_SERVER_ERROR)
+    }
+}
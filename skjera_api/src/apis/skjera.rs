@@ -1,31 +1,147 @@
 use async_trait::async_trait;
 use axum::extract::*;
-use axum_extra::extract::{CookieJar, Multipart};
+use axum_extra::extract::{CookieJar, Host, Multipart};
 use bytes::Bytes;
 use http::Method;
 use serde::{Deserialize, Serialize};
 
 use crate::{models, types::*};
 
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[must_use]
+#[allow(clippy::large_enum_variant)]
+pub enum AddSomeAccountResponse {
+    /// The created account
+    Status201_TheCreatedAccount
+    (models::SomeAccount)
+    ,
+    /// Bad request
+    Status400_BadRequest
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[must_use]
+#[allow(clippy::large_enum_variant)]
+pub enum DeleteSomeAccountResponse {
+    /// Deleted
+    Status204_Deleted
+    ,
+    /// Not found
+    Status404_NotFound
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[must_use]
+#[allow(clippy::large_enum_variant)]
+pub enum GetEmployeeResponse {
+    /// The employee
+    Status200_TheEmployee
+    (models::Employee)
+    ,
+    /// Not found
+    Status404_NotFound
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[must_use]
+#[allow(clippy::large_enum_variant)]
+pub enum GetMeResponse {
+    /// The logged in employee
+    Status200_TheLoggedInEmployee
+    (models::Employee)
+}
+
 #[derive(Debug, PartialEq, Serialize, Deserialize)]
 #[must_use]
 #[allow(clippy::large_enum_variant)]
 pub enum ListEmployeesResponse {
     /// List of employees
     Status200_ListOfEmployees
-    (Vec<models::Employee>)
+    {
+        body: Vec<models::Employee>,
+        x_total_count:
+        Option<
+        i64
+        >
+    }
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[must_use]
+#[allow(clippy::large_enum_variant)]
+pub enum UpdateMeResponse {
+    /// The updated employee
+    Status200_TheUpdatedEmployee
+    (models::Employee)
 }
 
 
 /// Skjera
 #[async_trait]
 #[allow(clippy::ptr_arg)]
-pub trait Skjera {
+pub trait Skjera<E: std::fmt::Debug + Send + Sync + 'static = ()>: super::ErrorHandler<E> {
+    type Claims;
+
+    /// AddSomeAccount - POST /api/me/some-account
+    async fn add_some_account(
+    &self,
+    method: &Method,
+    host: &Host,
+    cookies: &CookieJar,
+        claims: &Self::Claims,
+            body: &models::NewSomeAccount,
+    ) -> Result<AddSomeAccountResponse, E>;
+
+    /// DeleteSomeAccount - DELETE /api/me/some-account/{someAccountId}
+    async fn delete_some_account(
+    &self,
+    method: &Method,
+    host: &Host,
+    cookies: &CookieJar,
+        claims: &Self::Claims,
+      path_params: &models::DeleteSomeAccountPathParams,
+    ) -> Result<DeleteSomeAccountResponse, E>;
+
+    /// GetEmployee - GET /api/employee/{employeeId}
+    async fn get_employee(
+    &self,
+    method: &Method,
+    host: &Host,
+    cookies: &CookieJar,
+        claims: &Self::Claims,
+      path_params: &models::GetEmployeePathParams,
+    ) -> Result<GetEmployeeResponse, E>;
+
+    /// GetMe - GET /api/me
+    async fn get_me(
+    &self,
+    method: &Method,
+    host: &Host,
+    cookies: &CookieJar,
+        claims: &Self::Claims,
+    ) -> Result<GetMeResponse, E>;
+
+    /// Searches the active employees, the total number of matches is returned in X-Total-Count..
+    ///
     /// ListEmployees - GET /api/employee
     async fn list_employees(
     &self,
-    method: Method,
-    host: Host,
-    cookies: CookieJar,
-    ) -> Result<ListEmployeesResponse, String>;
+    method: &Method,
+    host: &Host,
+    cookies: &CookieJar,
+        claims: &Self::Claims,
+      query_params: &models::ListEmployeesQueryParams,
+    ) -> Result<ListEmployeesResponse, E>;
+
+    /// Replaces the editable parts of the logged in employee's profile..
+    ///
+    /// UpdateMe - PUT /api/me
+    async fn update_me(
+    &self,
+    method: &Method,
+    host: &Host,
+    cookies: &CookieJar,
+        claims: &Self::Claims,
+            body: &models::UpdateMeRequest,
+    ) -> Result<UpdateMeResponse, E>;
 }
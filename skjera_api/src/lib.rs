@@ -11,7 +11,8 @@
 #![allow(
     clippy::derive_partial_eq_without_eq,
     clippy::disallowed_names,
-    clippy::too_many_arguments
+    clippy::too_many_arguments,
+    clippy::needless_lifetimes
 )]
 
 pub const BASE_PATH: &str = "";
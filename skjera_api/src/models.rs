@@ -9,7 +9,53 @@ use crate::{models, types::*};
 
       
       
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize, validator::Validate)]
+#[cfg_attr(feature = "conversion", derive(frunk::LabelledGeneric))] 
+pub struct DeleteSomeAccountPathParams {
+    pub some_account_id: i64,
+}
+
+      
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize, validator::Validate)]
+#[cfg_attr(feature = "conversion", derive(frunk::LabelledGeneric))] 
+pub struct GetEmployeePathParams {
+    pub employee_id: i64,
+}
+
+
       
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize, validator::Validate)]
+#[cfg_attr(feature = "conversion", derive(frunk::LabelledGeneric))] 
+pub struct ListEmployeesQueryParams {
+    /// Matches a part of the name, the email or a SoMe nick.
+    #[serde(rename = "q")]
+    #[serde(skip_serializing_if="Option::is_none")]
+    pub q: Option<String>,
+    /// Only employees with an account on this network.
+    #[serde(rename = "network")]
+    #[serde(skip_serializing_if="Option::is_none")]
+    pub network: Option<models::SomeNetwork>,
+    /// Only employees currently assigned to this customer.
+    #[serde(rename = "customer")]
+    #[serde(skip_serializing_if="Option::is_none")]
+    pub customer: Option<i64>,
+    #[serde(rename = "sort")]
+    #[serde(skip_serializing_if="Option::is_none")]
+    pub sort: Option<String>,
+    #[serde(rename = "page")]
+    #[validate(
+          range(min = 1i32),
+    )]
+    #[serde(skip_serializing_if="Option::is_none")]
+    pub page: Option<i32>,
+    #[serde(rename = "perPage")]
+    #[validate(
+          range(min = 1i32, max = 100i32),
+    )]
+    #[serde(skip_serializing_if="Option::is_none")]
+    pub per_page: Option<i32>,
+}
+
 
 
 
@@ -18,6 +64,9 @@ use crate::{models, types::*};
 #[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize, validator::Validate)]
 #[cfg_attr(feature = "conversion", derive(frunk::LabelledGeneric))]
 pub struct Employee {
+    #[serde(rename = "id")]
+    pub id: i64,
+
     #[serde(rename = "name")]
     pub name: String,
 
@@ -28,6 +77,10 @@ pub struct Employee {
     #[serde(skip_serializing_if="Option::is_none")]
     pub nick: Option<String>,
 
+    #[serde(rename = "dob")]
+    #[serde(skip_serializing_if="Option::is_none")]
+    pub dob: Option<chrono::naive::NaiveDate>,
+
     #[serde(rename = "someAccounts")]
     pub some_accounts: Vec<models::SomeAccount>,
 
@@ -36,11 +89,13 @@ pub struct Employee {
 
 impl Employee {
     #[allow(clippy::new_without_default, clippy::too_many_arguments)]
-    pub fn new(name: String, email: String, some_accounts: Vec<models::SomeAccount>, ) -> Employee {
+    pub fn new(id: i64, name: String, email: String, some_accounts: Vec<models::SomeAccount>, ) -> Employee {
         Employee {
+            id,
             name,
             email,
             nick: None,
+            dob: None,
             some_accounts,
         }
     }
@@ -53,6 +108,10 @@ impl std::fmt::Display for Employee {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let params: Vec<Option<String>> = vec![
 
+            Some("id".to_string()),
+            Some(self.id.to_string()),
+
+
             Some("name".to_string()),
             Some(self.name.to_string()),
 
@@ -68,6 +127,15 @@ impl std::fmt::Display for Employee {
                 ].join(",")
             }),
 
+
+            self.dob.as_ref().map(|dob| {
+                [
+                    "dob".to_string(),
+                    dob.to_string(),
+                ].join(",")
+            }),
+
+
             // Skipping someAccounts in query parameter serialization
 
         ];
@@ -87,9 +155,11 @@ impl std::str::FromStr for Employee {
         #[derive(Default)]
         #[allow(dead_code)]
         struct IntermediateRep {
+            pub id: Vec<i64>,
             pub name: Vec<String>,
             pub email: Vec<String>,
             pub nick: Vec<String>,
+            pub dob: Vec<chrono::naive::NaiveDate>,
             pub some_accounts: Vec<Vec<models::SomeAccount>>,
         }
 
@@ -108,12 +178,16 @@ impl std::str::FromStr for Employee {
             if let Some(key) = key_result {
                 #[allow(clippy::match_single_binding)]
                 match key {
+                    #[allow(clippy::redundant_clone)]
+                    "id" => intermediate_rep.id.push(<i64 as std::str::FromStr>::from_str(val).map_err(|x| x.to_string())?),
                     #[allow(clippy::redundant_clone)]
                     "name" => intermediate_rep.name.push(<String as std::str::FromStr>::from_str(val).map_err(|x| x.to_string())?),
                     #[allow(clippy::redundant_clone)]
                     "email" => intermediate_rep.email.push(<String as std::str::FromStr>::from_str(val).map_err(|x| x.to_string())?),
                     #[allow(clippy::redundant_clone)]
                     "nick" => intermediate_rep.nick.push(<String as std::str::FromStr>::from_str(val).map_err(|x| x.to_string())?),
+                    #[allow(clippy::redundant_clone)]
+                    "dob" => intermediate_rep.dob.push(<chrono::naive::NaiveDate as std::str::FromStr>::from_str(val).map_err(|x| x.to_string())?),
                     "someAccounts" => return std::result::Result::Err("Parsing a container in this style is not supported in Employee".to_string()),
                     _ => return std::result::Result::Err("Unexpected key while parsing Employee".to_string())
                 }
@@ -125,9 +199,11 @@ impl std::str::FromStr for Employee {
 
         // Use the intermediate representation to return the struct
         std::result::Result::Ok(Employee {
+            id: intermediate_rep.id.into_iter().next().ok_or_else(|| "id missing in Employee".to_string())?,
             name: intermediate_rep.name.into_iter().next().ok_or_else(|| "name missing in Employee".to_string())?,
             email: intermediate_rep.email.into_iter().next().ok_or_else(|| "email missing in Employee".to_string())?,
             nick: intermediate_rep.nick.into_iter().next(),
+            dob: intermediate_rep.dob.into_iter().next(),
             some_accounts: intermediate_rep.some_accounts.into_iter().next().ok_or_else(|| "someAccounts missing in Employee".to_string())?,
         })
     }
@@ -176,6 +252,162 @@ impl std::convert::TryFrom<HeaderValue> for header::IntoHeaderValue<Employee> {
 
 
 
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize, validator::Validate)]
+#[cfg_attr(feature = "conversion", derive(frunk::LabelledGeneric))]
+pub struct NewSomeAccount {
+    #[serde(rename = "network")]
+    pub network: models::SomeNetwork,
+
+    #[serde(rename = "nick")]
+    #[serde(skip_serializing_if="Option::is_none")]
+    pub nick: Option<String>,
+
+    #[serde(rename = "url")]
+    #[serde(skip_serializing_if="Option::is_none")]
+    pub url: Option<String>,
+
+}
+
+
+impl NewSomeAccount {
+    #[allow(clippy::new_without_default, clippy::too_many_arguments)]
+    pub fn new(network: models::SomeNetwork, ) -> NewSomeAccount {
+        NewSomeAccount {
+            network,
+            nick: None,
+            url: None,
+        }
+    }
+}
+
+/// Converts the NewSomeAccount value to the Query Parameters representation (style=form, explode=false)
+/// specified in https://swagger.io/docs/specification/serialization/
+/// Should be implemented in a serde serializer
+impl std::fmt::Display for NewSomeAccount {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let params: Vec<Option<String>> = vec![
+
+            Some("network".to_string()),
+            Some(self.network.to_string()),
+
+
+            self.nick.as_ref().map(|nick| {
+                [
+                    "nick".to_string(),
+                    nick.to_string(),
+                ].join(",")
+            }),
+
+
+            self.url.as_ref().map(|url| {
+                [
+                    "url".to_string(),
+                    url.to_string(),
+                ].join(",")
+            }),
+
+        ];
+
+        write!(f, "{}", params.into_iter().flatten().collect::<Vec<_>>().join(","))
+    }
+}
+
+/// Converts Query Parameters representation (style=form, explode=false) to a NewSomeAccount value
+/// as specified in https://swagger.io/docs/specification/serialization/
+/// Should be implemented in a serde deserializer
+impl std::str::FromStr for NewSomeAccount {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        /// An intermediate representation of the struct to use for parsing.
+        #[derive(Default)]
+        #[allow(dead_code)]
+        struct IntermediateRep {
+            pub network: Vec<models::SomeNetwork>,
+            pub nick: Vec<String>,
+            pub url: Vec<String>,
+        }
+
+        let mut intermediate_rep = IntermediateRep::default();
+
+        // Parse into intermediate representation
+        let mut string_iter = s.split(',');
+        let mut key_result = string_iter.next();
+
+        while key_result.is_some() {
+            let val = match string_iter.next() {
+                Some(x) => x,
+                None => return std::result::Result::Err("Missing value while parsing NewSomeAccount".to_string())
+            };
+
+            if let Some(key) = key_result {
+                #[allow(clippy::match_single_binding)]
+                match key {
+                    #[allow(clippy::redundant_clone)]
+                    "network" => intermediate_rep.network.push(<models::SomeNetwork as std::str::FromStr>::from_str(val).map_err(|x| x.to_string())?),
+                    #[allow(clippy::redundant_clone)]
+                    "nick" => intermediate_rep.nick.push(<String as std::str::FromStr>::from_str(val).map_err(|x| x.to_string())?),
+                    #[allow(clippy::redundant_clone)]
+                    "url" => intermediate_rep.url.push(<String as std::str::FromStr>::from_str(val).map_err(|x| x.to_string())?),
+                    _ => return std::result::Result::Err("Unexpected key while parsing NewSomeAccount".to_string())
+                }
+            }
+
+            // Get the next key
+            key_result = string_iter.next();
+        }
+
+        // Use the intermediate representation to return the struct
+        std::result::Result::Ok(NewSomeAccount {
+            network: intermediate_rep.network.into_iter().next().ok_or_else(|| "network missing in NewSomeAccount".to_string())?,
+            nick: intermediate_rep.nick.into_iter().next(),
+            url: intermediate_rep.url.into_iter().next(),
+        })
+    }
+}
+
+// Methods for converting between header::IntoHeaderValue<NewSomeAccount> and HeaderValue
+
+#[cfg(feature = "server")]
+impl std::convert::TryFrom<header::IntoHeaderValue<NewSomeAccount>> for HeaderValue {
+    type Error = String;
+
+    fn try_from(hdr_value: header::IntoHeaderValue<NewSomeAccount>) -> std::result::Result<Self, Self::Error> {
+        let hdr_value = hdr_value.to_string();
+        match HeaderValue::from_str(&hdr_value) {
+             std::result::Result::Ok(value) => std::result::Result::Ok(value),
+             std::result::Result::Err(e) => std::result::Result::Err(
+                 format!("Invalid header value for NewSomeAccount - value: {} is invalid {}",
+                     hdr_value, e))
+        }
+    }
+}
+
+#[cfg(feature = "server")]
+impl std::convert::TryFrom<HeaderValue> for header::IntoHeaderValue<NewSomeAccount> {
+    type Error = String;
+
+    fn try_from(hdr_value: HeaderValue) -> std::result::Result<Self, Self::Error> {
+        match hdr_value.to_str() {
+             std::result::Result::Ok(value) => {
+                    match <NewSomeAccount as std::str::FromStr>::from_str(value) {
+                        std::result::Result::Ok(value) => std::result::Result::Ok(header::IntoHeaderValue(value)),
+                        std::result::Result::Err(err) => std::result::Result::Err(
+                            format!("Unable to convert header value '{}' into NewSomeAccount - {}",
+                                value, err))
+                    }
+             },
+             std::result::Result::Err(e) => std::result::Result::Err(
+                 format!("Unable to convert header: {:?} to string: {}",
+                     hdr_value, e))
+        }
+    }
+}
+
+
+
+
+
 
 #[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize, validator::Validate)]
 #[cfg_attr(feature = "conversion", derive(frunk::LabelledGeneric))]
@@ -184,25 +416,46 @@ pub struct SomeAccount {
     pub id: i64,
 
     #[serde(rename = "network")]
-    pub network: String,
+    pub network: models::SomeNetwork,
 
-    #[serde(rename = "url")]
-    pub url: String,
+    #[serde(rename = "networkInstance")]
+    #[serde(skip_serializing_if="Option::is_none")]
+    pub network_instance: Option<String>,
+
+    #[serde(rename = "authenticated")]
+    pub authenticated: bool,
+
+    #[serde(rename = "name")]
+    #[serde(skip_serializing_if="Option::is_none")]
+    pub name: Option<String>,
 
     #[serde(rename = "nick")]
-    pub nick: String,
+    #[serde(skip_serializing_if="Option::is_none")]
+    pub nick: Option<String>,
+
+    #[serde(rename = "url")]
+    #[serde(skip_serializing_if="Option::is_none")]
+    pub url: Option<String>,
+
+    #[serde(rename = "avatar")]
+    #[serde(skip_serializing_if="Option::is_none")]
+    pub avatar: Option<String>,
 
 }
 
 
 impl SomeAccount {
     #[allow(clippy::new_without_default, clippy::too_many_arguments)]
-    pub fn new(id: i64, network: String, url: String, nick: String, ) -> SomeAccount {
+    pub fn new(id: i64, network: models::SomeNetwork, authenticated: bool, ) -> SomeAccount {
         SomeAccount {
             id,
             network,
-            url,
-            nick,
+            network_instance: None,
+            authenticated,
+            name: None,
+            nick: None,
+            url: None,
+            avatar: None,
         }
     }
 }
@@ -222,12 +475,48 @@ impl std::fmt::Display for SomeAccount {
             Some(self.network.to_string()),
 
 
-            Some("url".to_string()),
-            Some(self.url.to_string()),
+            self.network_instance.as_ref().map(|network_instance| {
+                [
+                    "networkInstance".to_string(),
+                    network_instance.to_string(),
+                ].join(",")
+            }),
+
+
+            Some("authenticated".to_string()),
+            Some(self.authenticated.to_string()),
+
+
+            self.name.as_ref().map(|name| {
+                [
+                    "name".to_string(),
+                    name.to_string(),
+                ].join(",")
+            }),
+
 
+            self.nick.as_ref().map(|nick| {
+                [
+                    "nick".to_string(),
+                    nick.to_string(),
+                ].join(",")
+            }),
 
-            Some("nick".to_string()),
-            Some(self.nick.to_string()),
+
+            self.url.as_ref().map(|url| {
+                [
+                    "url".to_string(),
+                    url.to_string(),
+                ].join(",")
+            }),
+
+
+            self.avatar.as_ref().map(|avatar| {
+                [
+                    "avatar".to_string(),
+                    avatar.to_string(),
+                ].join(",")
+            }),
 
         ];
 
@@ -247,9 +536,13 @@ impl std::str::FromStr for SomeAccount {
         #[allow(dead_code)]
         struct IntermediateRep {
             pub id: Vec<i64>,
-            pub network: Vec<String>,
-            pub url: Vec<String>,
+            pub network: Vec<models::SomeNetwork>,
+            pub network_instance: Vec<String>,
+            pub authenticated: Vec<bool>,
+            pub name: Vec<String>,
             pub nick: Vec<String>,
+            pub url: Vec<String>,
+            pub avatar: Vec<String>,
         }
 
         let mut intermediate_rep = IntermediateRep::default();
@@ -270,11 +563,19 @@ impl std::str::FromStr for SomeAccount {
                     #[allow(clippy::redundant_clone)]
                     "id" => intermediate_rep.id.push(<i64 as std::str::FromStr>::from_str(val).map_err(|x| x.to_string())?),
                     #[allow(clippy::redundant_clone)]
-                    "network" => intermediate_rep.network.push(<String as std::str::FromStr>::from_str(val).map_err(|x| x.to_string())?),
+                    "network" => intermediate_rep.network.push(<models::SomeNetwork as std::str::FromStr>::from_str(val).map_err(|x| x.to_string())?),
                     #[allow(clippy::redundant_clone)]
-                    "url" => intermediate_rep.url.push(<String as std::str::FromStr>::from_str(val).map_err(|x| x.to_string())?),
+                    "networkInstance" => intermediate_rep.network_instance.push(<String as std::str::FromStr>::from_str(val).map_err(|x| x.to_string())?),
+                    #[allow(clippy::redundant_clone)]
+                    "authenticated" => intermediate_rep.authenticated.push(<bool as std::str::FromStr>::from_str(val).map_err(|x| x.to_string())?),
+                    #[allow(clippy::redundant_clone)]
+                    "name" => intermediate_rep.name.push(<String as std::str::FromStr>::from_str(val).map_err(|x| x.to_string())?),
                     #[allow(clippy::redundant_clone)]
                     "nick" => intermediate_rep.nick.push(<String as std::str::FromStr>::from_str(val).map_err(|x| x.to_string())?),
+                    #[allow(clippy::redundant_clone)]
+                    "url" => intermediate_rep.url.push(<String as std::str::FromStr>::from_str(val).map_err(|x| x.to_string())?),
+                    #[allow(clippy::redundant_clone)]
+                    "avatar" => intermediate_rep.avatar.push(<String as std::str::FromStr>::from_str(val).map_err(|x| x.to_string())?),
                     _ => return std::result::Result::Err("Unexpected key while parsing SomeAccount".to_string())
                 }
             }
@@ -287,8 +588,12 @@ impl std::str::FromStr for SomeAccount {
         std::result::Result::Ok(SomeAccount {
             id: intermediate_rep.id.into_iter().next().ok_or_else(|| "id missing in SomeAccount".to_string())?,
             network: intermediate_rep.network.into_iter().next().ok_or_else(|| "network missing in SomeAccount".to_string())?,
-            url: intermediate_rep.url.into_iter().next().ok_or_else(|| "url missing in SomeAccount".to_string())?,
-            nick: intermediate_rep.nick.into_iter().next().ok_or_else(|| "nick missing in SomeAccount".to_string())?,
+            network_instance: intermediate_rep.network_instance.into_iter().next(),
+            authenticated: intermediate_rep.authenticated.into_iter().next().ok_or_else(|| "authenticated missing in SomeAccount".to_string())?,
+            name: intermediate_rep.name.into_iter().next(),
+            nick: intermediate_rep.nick.into_iter().next(),
+            url: intermediate_rep.url.into_iter().next(),
+            avatar: intermediate_rep.avatar.into_iter().next(),
         })
     }
 }
@@ -333,7 +638,6 @@ impl std::convert::TryFrom<HeaderValue> for header::IntoHeaderValue<SomeAccount>
 
 
 
-
 /// Enumeration of values.
 /// Since this enum's variants do not hold data, we can easily define them as `#[repr(C)]`
 /// which helps with FFI.
@@ -342,26 +646,29 @@ impl std::convert::TryFrom<HeaderValue> for header::IntoHeaderValue<SomeAccount>
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, serde::Serialize, serde::Deserialize)]
 #[cfg_attr(feature = "conversion", derive(frunk_enum_derive::LabelledGenericEnum))]
 pub enum SomeNetwork {
-    #[serde(rename = "Twitter")]
-    Twitter,
-    #[serde(rename = "LinkedIn")]
+    #[serde(rename = "bluesky")]
+    Bluesky,
+    #[serde(rename = "github")]
+    Github,
+    #[serde(rename = "gitlab")]
+    Gitlab,
+    #[serde(rename = "linked-in")]
     LinkedIn,
-    #[serde(rename = "Slack")]
+    #[serde(rename = "slack")]
     Slack,
-    #[serde(rename = "GitHub")]
-    GitHub,
-    #[serde(rename = "GitLab")]
-    GitLab,
+    #[serde(rename = "x")]
+    X,
 }
 
 impl std::fmt::Display for SomeNetwork {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match *self {
-            SomeNetwork::Twitter => write!(f, "Twitter"),
-            SomeNetwork::LinkedIn => write!(f, "LinkedIn"),
-            SomeNetwork::Slack => write!(f, "Slack"),
-            SomeNetwork::GitHub => write!(f, "GitHub"),
-            SomeNetwork::GitLab => write!(f, "GitLab"),
+            SomeNetwork::Bluesky => write!(f, "bluesky"),
+            SomeNetwork::Github => write!(f, "github"),
+            SomeNetwork::Gitlab => write!(f, "gitlab"),
+            SomeNetwork::LinkedIn => write!(f, "linked-in"),
+            SomeNetwork::Slack => write!(f, "slack"),
+            SomeNetwork::X => write!(f, "x"),
         }
     }
 }
@@ -371,13 +678,138 @@ impl std::str::FromStr for SomeNetwork {
 
     fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
         match s {
-            "Twitter" => std::result::Result::Ok(SomeNetwork::Twitter),
-            "LinkedIn" => std::result::Result::Ok(SomeNetwork::LinkedIn),
-            "Slack" => std::result::Result::Ok(SomeNetwork::Slack),
-            "GitHub" => std::result::Result::Ok(SomeNetwork::GitHub),
-            "GitLab" => std::result::Result::Ok(SomeNetwork::GitLab),
+            "bluesky" => std::result::Result::Ok(SomeNetwork::Bluesky),
+            "github" => std::result::Result::Ok(SomeNetwork::Github),
+            "gitlab" => std::result::Result::Ok(SomeNetwork::Gitlab),
+            "linked-in" => std::result::Result::Ok(SomeNetwork::LinkedIn),
+            "slack" => std::result::Result::Ok(SomeNetwork::Slack),
+            "x" => std::result::Result::Ok(SomeNetwork::X),
             _ => std::result::Result::Err(format!("Value not valid: {}", s)),
         }
     }
 }
 
+
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize, validator::Validate)]
+#[cfg_attr(feature = "conversion", derive(frunk::LabelledGeneric))]
+pub struct UpdateMeRequest {
+    #[serde(rename = "dob")]
+    #[serde(skip_serializing_if="Option::is_none")]
+    pub dob: Option<chrono::naive::NaiveDate>,
+
+}
+
+
+impl UpdateMeRequest {
+    #[allow(clippy::new_without_default, clippy::too_many_arguments)]
+    pub fn new() -> UpdateMeRequest {
+        UpdateMeRequest {
+            dob: None,
+        }
+    }
+}
+
+/// Converts the UpdateMeRequest value to the Query Parameters representation (style=form, explode=false)
+/// specified in https://swagger.io/docs/specification/serialization/
+/// Should be implemented in a serde serializer
+impl std::fmt::Display for UpdateMeRequest {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let params: Vec<Option<String>> = vec![
+
+            self.dob.as_ref().map(|dob| {
+                [
+                    "dob".to_string(),
+                    dob.to_string(),
+                ].join(",")
+            }),
+
+        ];
+
+        write!(f, "{}", params.into_iter().flatten().collect::<Vec<_>>().join(","))
+    }
+}
+
+/// Converts Query Parameters representation (style=form, explode=false) to a UpdateMeRequest value
+/// as specified in https://swagger.io/docs/specification/serialization/
+/// Should be implemented in a serde deserializer
+impl std::str::FromStr for UpdateMeRequest {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        /// An intermediate representation of the struct to use for parsing.
+        #[derive(Default)]
+        #[allow(dead_code)]
+        struct IntermediateRep {
+            pub dob: Vec<chrono::naive::NaiveDate>,
+        }
+
+        let mut intermediate_rep = IntermediateRep::default();
+
+        // Parse into intermediate representation
+        let mut string_iter = s.split(',');
+        let mut key_result = string_iter.next();
+
+        while key_result.is_some() {
+            let val = match string_iter.next() {
+                Some(x) => x,
+                None => return std::result::Result::Err("Missing value while parsing UpdateMeRequest".to_string())
+            };
+
+            if let Some(key) = key_result {
+                #[allow(clippy::match_single_binding)]
+                match key {
+                    #[allow(clippy::redundant_clone)]
+                    "dob" => intermediate_rep.dob.push(<chrono::naive::NaiveDate as std::str::FromStr>::from_str(val).map_err(|x| x.to_string())?),
+                    _ => return std::result::Result::Err("Unexpected key while parsing UpdateMeRequest".to_string())
+                }
+            }
+
+            // Get the next key
+            key_result = string_iter.next();
+        }
+
+        // Use the intermediate representation to return the struct
+        std::result::Result::Ok(UpdateMeRequest {
+            dob: intermediate_rep.dob.into_iter().next(),
+        })
+    }
+}
+
+// Methods for converting between header::IntoHeaderValue<UpdateMeRequest> and HeaderValue
+
+#[cfg(feature = "server")]
+impl std::convert::TryFrom<header::IntoHeaderValue<UpdateMeRequest>> for HeaderValue {
+    type Error = String;
+
+    fn try_from(hdr_value: header::IntoHeaderValue<UpdateMeRequest>) -> std::result::Result<Self, Self::Error> {
+        let hdr_value = hdr_value.to_string();
+        match HeaderValue::from_str(&hdr_value) {
+             std::result::Result::Ok(value) => std::result::Result::Ok(value),
+             std::result::Result::Err(e) => std::result::Result::Err(
+                 format!("Invalid header value for UpdateMeRequest - value: {} is invalid {}",
+                     hdr_value, e))
+        }
+    }
+}
+
+#[cfg(feature = "server")]
+impl std::convert::TryFrom<HeaderValue> for header::IntoHeaderValue<UpdateMeRequest> {
+    type Error = String;
+
+    fn try_from(hdr_value: HeaderValue) -> std::result::Result<Self, Self::Error> {
+        match hdr_value.to_str() {
+             std::result::Result::Ok(value) => {
+                    match <UpdateMeRequest as std::str::FromStr>::from_str(value) {
+                        std::result::Result::Ok(value) => std::result::Result::Ok(header::IntoHeaderValue(value)),
+                        std::result::Result::Err(err) => std::result::Result::Err(
+                            format!("Unable to convert header value '{}' into UpdateMeRequest - {}",
+                                value, err))
+                    }
+             },
+             std::result::Result::Err(e) => std::result::Result::Err(
+                 format!("Unable to convert header: {:?} to string: {}",
+                     hdr_value, e))
+        }
+    }
+}
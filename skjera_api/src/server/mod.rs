@@ -1,7 +1,7 @@
 use std::collections::HashMap;
 
 use axum::{body::Body, extract::*, response::Response, routing::*};
-use axum_extra::extract::{CookieJar, Multipart};
+use axum_extra::extract::{CookieJar, Host, Multipart};
 use bytes::Bytes;
 use http::{header::CONTENT_TYPE, HeaderMap, HeaderName, HeaderValue, Method, StatusCode};
 use tracing::error;
@@ -14,28 +14,39 @@ use crate::{apis, models};
 
 
 /// Setup API Server.
-pub fn new<I, A>(api_impl: I) -> Router
+pub fn new<I, A, E, C>(api_impl: I) -> Router
 where
     I: AsRef<A> + Clone + Send + Sync + 'static,
-    A: apis::html::Html + apis::meta::Meta + apis::skjera::Skjera + 'static,
+    A: apis::meta::Meta<E> + apis::skjera::Skjera<E, Claims = C> + apis::ApiKeyAuthHeader<Claims = C> + apis::CookieAuthentication<Claims = C> + Send + Sync + 'static,
+    E: std::fmt::Debug + Send + Sync + 'static,
+    C: Send + Sync + 'static,
 {
     // build our application with a route
     Router::new()
-        .route("/",
-            get(hello_world::<I, A>)
-        )
         .route("/api/employee",
-            get(list_employees::<I, A>)
+            get(list_employees::<I, A, E, C>)
+        )
+        .route("/api/employee/{employee_id}",
+            get(get_employee::<I, A, E, C>)
+        )
+        .route("/api/me",
+            get(get_me::<I, A, E, C>).put(update_me::<I, A, E, C>)
+        )
+        .route("/api/me/some-account",
+            post(add_some_account::<I, A, E, C>)
+        )
+        .route("/api/me/some-account/{some_account_id}",
+            delete(delete_some_account::<I, A, E, C>)
         )
         .route("/meta/healthz",
-            get(meta_healthz::<I, A>)
+            get(meta_healthz::<I, A, E>)
         )
         .with_state(api_impl)
 }
 
 
 #[tracing::instrument(skip_all)]
-fn hello_world_validation(
+fn meta_healthz_validation(
 ) -> std::result::Result<(
 ), ValidationErrors>
 {
@@ -43,9 +54,9 @@ fn hello_world_validation(
 Ok((
 ))
 }
-/// HelloWorld - GET /
+/// MetaHealthz - GET /meta/healthz
 #[tracing::instrument(skip_all)]
-async fn hello_world<I, A>(
+async fn meta_healthz<I, A, E>(
   method: Method,
   host: Host,
   cookies: CookieJar,
@@ -53,16 +64,112 @@ async fn hello_world<I, A>(
 ) -> Result<Response, StatusCode>
 where
     I: AsRef<A> + Send + Sync,
-    A: apis::html::Html,
+    A: apis::meta::Meta<E> + Send + Sync,
+    E: std::fmt::Debug + Send + Sync + 'static,
+        {
+      #[allow(clippy::redundant_closure)]
+      let validation = tokio::task::spawn_blocking(move ||
+    meta_healthz_validation(
+    )
+  ).await.unwrap();
+
+  let Ok((
+  )) = validation else {
+    return Response::builder()
+            .status(StatusCode::BAD_REQUEST)
+            .body(Body::from(validation.unwrap_err().to_string()))
+            .map_err(|_| StatusCode::BAD_REQUEST);
+  };
+
+  let result = api_impl.as_ref().meta_healthz(
+      &method,
+      &host,
+      &cookies,
+  ).await;
+
+  let mut response = Response::builder();
+
+  let resp = match result {
+                                            Ok(rsp) => match rsp {
+                                                apis::meta::MetaHealthzResponse::Status200_Healthy
+                                                => {
+                                                  let mut response = response.status(200);
+                                                  response.body(Body::empty())
+                                                },
+                                                apis::meta::MetaHealthzResponse::Status503_Unhealthy
+                                                => {
+                                                  let mut response = response.status(503);
+                                                  response.body(Body::empty())
+                                                },
+                                            },
+                                            Err(why) => {
+                                                // Application code returned an error. This should not happen, as the implementation should
+                                                // return a valid response.
+                                                return api_impl.as_ref().handle_error(&method, &host, &cookies, why).await;
+                                            },
+                                        };
+
+                                        resp.map_err(|e| { error!(error = ?e); StatusCode::INTERNAL_SERVER_ERROR })
+}
+
+
+    #[derive(validator::Validate)]
+    #[allow(dead_code)]
+    struct AddSomeAccountBodyValidator<'a> {
+            #[validate(nested)]
+          body: &'a models::NewSomeAccount,
+    }
+
+
+#[tracing::instrument(skip_all)]
+fn add_some_account_validation(
+  body: models::NewSomeAccount,
+) -> std::result::Result<(
+  models::NewSomeAccount,
+), ValidationErrors>
 {
+              let b = AddSomeAccountBodyValidator { body: &body };
+              b.validate()?;
+
+Ok((
+  body,
+))
+}
+/// AddSomeAccount - POST /api/me/some-account
+#[tracing::instrument(skip_all)]
+async fn add_some_account<I, A, E, C>(
+  method: Method,
+  host: Host,
+  cookies: CookieJar,
+  headers: HeaderMap,
+ State(api_impl): State<I>,
+          Json(body): Json<models::NewSomeAccount>,
+) -> Result<Response, StatusCode>
+where
+    I: AsRef<A> + Send + Sync,
+    A: apis::skjera::Skjera<E, Claims = C>+ apis::ApiKeyAuthHeader<Claims = C>+ apis::CookieAuthentication<Claims = C> + Send + Sync,
+    E: std::fmt::Debug + Send + Sync + 'static,
+        {
+    // Authentication
+    let claims_in_cookie = api_impl.as_ref().extract_claims_from_cookie(&cookies, "id").await;
+    let claims_in_header = api_impl.as_ref().extract_claims_from_header(&headers, "Authorization").await;
+    let claims = None
+             .or(claims_in_cookie)
+             .or(claims_in_header)
+          ;
+    let Some(claims) = claims else {
+        return response_with_status_code_only(StatusCode::UNAUTHORIZED);
+    };
 
       #[allow(clippy::redundant_closure)]
       let validation = tokio::task::spawn_blocking(move ||
-    hello_world_validation(
+    add_some_account_validation(
+        body,
     )
   ).await.unwrap();
 
   let Ok((
+    body,
   )) = validation else {
     return Response::builder()
             .status(StatusCode::BAD_REQUEST)
@@ -70,35 +177,46 @@ where
             .map_err(|_| StatusCode::BAD_REQUEST);
   };
 
-  let result = api_impl.as_ref().hello_world(
-      method,
-      host,
-      cookies,
+  let result = api_impl.as_ref().add_some_account(
+      &method,
+      &host,
+      &cookies,
+        &claims,
+        &body,
   ).await;
 
   let mut response = Response::builder();
 
   let resp = match result {
                                             Ok(rsp) => match rsp {
-                                                apis::html::HelloWorldResponse::Status200_HelloWorld
+                                                apis::skjera::AddSomeAccountResponse::Status201_TheCreatedAccount
                                                     (body)
                                                 => {
-                                                  let mut response = response.status(200);
+                                                  let mut response = response.status(201);
                                                   {
                                                     let mut response_headers = response.headers_mut().unwrap();
                                                     response_headers.insert(
                                                         CONTENT_TYPE,
-                                                        HeaderValue::from_str("text/html").map_err(|e| { error!(error = ?e); StatusCode::INTERNAL_SERVER_ERROR })?);
+                                                        HeaderValue::from_str("application/json").map_err(|e| { error!(error = ?e); StatusCode::INTERNAL_SERVER_ERROR })?);
                                                   }
 
-                                                  let body_content = body;
+                                                  let body_content =  tokio::task::spawn_blocking(move ||
+                                                      serde_json::to_vec(&body).map_err(|e| {
+                                                        error!(error = ?e);
+                                                        StatusCode::INTERNAL_SERVER_ERROR
+                                                      })).await.unwrap()?;
                                                   response.body(Body::from(body_content))
                                                 },
+                                                apis::skjera::AddSomeAccountResponse::Status400_BadRequest
+                                                => {
+                                                  let mut response = response.status(400);
+                                                  response.body(Body::empty())
+                                                },
                                             },
-                                            Err(_) => {
+                                            Err(why) => {
                                                 // Application code returned an error. This should not happen, as the implementation should
                                                 // return a valid response.
-                                                response.status(500).body(Body::empty())
+                                                return api_impl.as_ref().handle_error(&method, &host, &cookies, why).await;
                                             },
                                         };
 
@@ -107,34 +225,142 @@ where
 
 
 #[tracing::instrument(skip_all)]
-fn meta_healthz_validation(
+fn delete_some_account_validation(
+  path_params: models::DeleteSomeAccountPathParams,
 ) -> std::result::Result<(
+  models::DeleteSomeAccountPathParams,
 ), ValidationErrors>
 {
+  path_params.validate()?;
 
 Ok((
+  path_params,
 ))
 }
-/// MetaHealthz - GET /meta/healthz
+/// DeleteSomeAccount - DELETE /api/me/some-account/{someAccountId}
 #[tracing::instrument(skip_all)]
-async fn meta_healthz<I, A>(
+async fn delete_some_account<I, A, E, C>(
   method: Method,
   host: Host,
   cookies: CookieJar,
+  headers: HeaderMap,
+  Path(path_params): Path<models::DeleteSomeAccountPathParams>,
  State(api_impl): State<I>,
 ) -> Result<Response, StatusCode>
 where
     I: AsRef<A> + Send + Sync,
-    A: apis::meta::Meta,
+    A: apis::skjera::Skjera<E, Claims = C>+ apis::ApiKeyAuthHeader<Claims = C>+ apis::CookieAuthentication<Claims = C> + Send + Sync,
+    E: std::fmt::Debug + Send + Sync + 'static,
+        {
+    // Authentication
+    let claims_in_cookie = api_impl.as_ref().extract_claims_from_cookie(&cookies, "id").await;
+    let claims_in_header = api_impl.as_ref().extract_claims_from_header(&headers, "Authorization").await;
+    let claims = None
+             .or(claims_in_cookie)
+             .or(claims_in_header)
+          ;
+    let Some(claims) = claims else {
+        return response_with_status_code_only(StatusCode::UNAUTHORIZED);
+    };
+
+      #[allow(clippy::redundant_closure)]
+      let validation = tokio::task::spawn_blocking(move ||
+    delete_some_account_validation(
+        path_params,
+    )
+  ).await.unwrap();
+
+  let Ok((
+    path_params,
+  )) = validation else {
+    return Response::builder()
+            .status(StatusCode::BAD_REQUEST)
+            .body(Body::from(validation.unwrap_err().to_string()))
+            .map_err(|_| StatusCode::BAD_REQUEST);
+  };
+
+  let result = api_impl.as_ref().delete_some_account(
+      &method,
+      &host,
+      &cookies,
+        &claims,
+        &path_params,
+  ).await;
+
+  let mut response = Response::builder();
+
+  let resp = match result {
+                                            Ok(rsp) => match rsp {
+                                                apis::skjera::DeleteSomeAccountResponse::Status204_Deleted
+                                                => {
+                                                  let mut response = response.status(204);
+                                                  response.body(Body::empty())
+                                                },
+                                                apis::skjera::DeleteSomeAccountResponse::Status404_NotFound
+                                                => {
+                                                  let mut response = response.status(404);
+                                                  response.body(Body::empty())
+                                                },
+                                            },
+                                            Err(why) => {
+                                                // Application code returned an error. This should not happen, as the implementation should
+                                                // return a valid response.
+                                                return api_impl.as_ref().handle_error(&method, &host, &cookies, why).await;
+                                            },
+                                        };
+
+                                        resp.map_err(|e| { error!(error = ?e); StatusCode::INTERNAL_SERVER_ERROR })
+}
+
+
+#[tracing::instrument(skip_all)]
+fn get_employee_validation(
+  path_params: models::GetEmployeePathParams,
+) -> std::result::Result<(
+  models::GetEmployeePathParams,
+), ValidationErrors>
 {
+  path_params.validate()?;
+
+Ok((
+  path_params,
+))
+}
+/// GetEmployee - GET /api/employee/{employeeId}
+#[tracing::instrument(skip_all)]
+async fn get_employee<I, A, E, C>(
+  method: Method,
+  host: Host,
+  cookies: CookieJar,
+  headers: HeaderMap,
+  Path(path_params): Path<models::GetEmployeePathParams>,
+ State(api_impl): State<I>,
+) -> Result<Response, StatusCode>
+where
+    I: AsRef<A> + Send + Sync,
+    A: apis::skjera::Skjera<E, Claims = C>+ apis::ApiKeyAuthHeader<Claims = C>+ apis::CookieAuthentication<Claims = C> + Send + Sync,
+    E: std::fmt::Debug + Send + Sync + 'static,
+        {
+    // Authentication
+    let claims_in_cookie = api_impl.as_ref().extract_claims_from_cookie(&cookies, "id").await;
+    let claims_in_header = api_impl.as_ref().extract_claims_from_header(&headers, "Authorization").await;
+    let claims = None
+             .or(claims_in_cookie)
+             .or(claims_in_header)
+          ;
+    let Some(claims) = claims else {
+        return response_with_status_code_only(StatusCode::UNAUTHORIZED);
+    };
 
       #[allow(clippy::redundant_closure)]
       let validation = tokio::task::spawn_blocking(move ||
-    meta_healthz_validation(
+    get_employee_validation(
+        path_params,
     )
   ).await.unwrap();
 
   let Ok((
+    path_params,
   )) = validation else {
     return Response::builder()
             .status(StatusCode::BAD_REQUEST)
@@ -142,31 +368,135 @@ where
             .map_err(|_| StatusCode::BAD_REQUEST);
   };
 
-  let result = api_impl.as_ref().meta_healthz(
-      method,
-      host,
-      cookies,
+  let result = api_impl.as_ref().get_employee(
+      &method,
+      &host,
+      &cookies,
+        &claims,
+        &path_params,
   ).await;
 
   let mut response = Response::builder();
 
   let resp = match result {
                                             Ok(rsp) => match rsp {
-                                                apis::meta::MetaHealthzResponse::Status200_Healthy
+                                                apis::skjera::GetEmployeeResponse::Status200_TheEmployee
+                                                    (body)
                                                 => {
                                                   let mut response = response.status(200);
-                                                  response.body(Body::empty())
+                                                  {
+                                                    let mut response_headers = response.headers_mut().unwrap();
+                                                    response_headers.insert(
+                                                        CONTENT_TYPE,
+                                                        HeaderValue::from_str("application/json").map_err(|e| { error!(error = ?e); StatusCode::INTERNAL_SERVER_ERROR })?);
+                                                  }
+
+                                                  let body_content =  tokio::task::spawn_blocking(move ||
+                                                      serde_json::to_vec(&body).map_err(|e| {
+                                                        error!(error = ?e);
+                                                        StatusCode::INTERNAL_SERVER_ERROR
+                                                      })).await.unwrap()?;
+                                                  response.body(Body::from(body_content))
                                                 },
-                                                apis::meta::MetaHealthzResponse::Status503_Unhealthy
+                                                apis::skjera::GetEmployeeResponse::Status404_NotFound
                                                 => {
-                                                  let mut response = response.status(503);
+                                                  let mut response = response.status(404);
                                                   response.body(Body::empty())
                                                 },
                                             },
-                                            Err(_) => {
+                                            Err(why) => {
                                                 // Application code returned an error. This should not happen, as the implementation should
                                                 // return a valid response.
-                                                response.status(500).body(Body::empty())
+                                                return api_impl.as_ref().handle_error(&method, &host, &cookies, why).await;
+                                            },
+                                        };
+
+                                        resp.map_err(|e| { error!(error = ?e); StatusCode::INTERNAL_SERVER_ERROR })
+}
+
+
+#[tracing::instrument(skip_all)]
+fn get_me_validation(
+) -> std::result::Result<(
+), ValidationErrors>
+{
+
+Ok((
+))
+}
+/// GetMe - GET /api/me
+#[tracing::instrument(skip_all)]
+async fn get_me<I, A, E, C>(
+  method: Method,
+  host: Host,
+  cookies: CookieJar,
+  headers: HeaderMap,
+ State(api_impl): State<I>,
+) -> Result<Response, StatusCode>
+where
+    I: AsRef<A> + Send + Sync,
+    A: apis::skjera::Skjera<E, Claims = C>+ apis::ApiKeyAuthHeader<Claims = C>+ apis::CookieAuthentication<Claims = C> + Send + Sync,
+    E: std::fmt::Debug + Send + Sync + 'static,
+        {
+    // Authentication
+    let claims_in_cookie = api_impl.as_ref().extract_claims_from_cookie(&cookies, "id").await;
+    let claims_in_header = api_impl.as_ref().extract_claims_from_header(&headers, "Authorization").await;
+    let claims = None
+             .or(claims_in_cookie)
+             .or(claims_in_header)
+          ;
+    let Some(claims) = claims else {
+        return response_with_status_code_only(StatusCode::UNAUTHORIZED);
+    };
+
+      #[allow(clippy::redundant_closure)]
+      let validation = tokio::task::spawn_blocking(move ||
+    get_me_validation(
+    )
+  ).await.unwrap();
+
+  let Ok((
+  )) = validation else {
+    return Response::builder()
+            .status(StatusCode::BAD_REQUEST)
+            .body(Body::from(validation.unwrap_err().to_string()))
+            .map_err(|_| StatusCode::BAD_REQUEST);
+  };
+
+  let result = api_impl.as_ref().get_me(
+      &method,
+      &host,
+      &cookies,
+        &claims,
+  ).await;
+
+  let mut response = Response::builder();
+
+  let resp = match result {
+                                            Ok(rsp) => match rsp {
+                                                apis::skjera::GetMeResponse::Status200_TheLoggedInEmployee
+                                                    (body)
+                                                => {
+                                                  let mut response = response.status(200);
+                                                  {
+                                                    let mut response_headers = response.headers_mut().unwrap();
+                                                    response_headers.insert(
+                                                        CONTENT_TYPE,
+                                                        HeaderValue::from_str("application/json").map_err(|e| { error!(error = ?e); StatusCode::INTERNAL_SERVER_ERROR })?);
+                                                  }
+
+                                                  let body_content =  tokio::task::spawn_blocking(move ||
+                                                      serde_json::to_vec(&body).map_err(|e| {
+                                                        error!(error = ?e);
+                                                        StatusCode::INTERNAL_SERVER_ERROR
+                                                      })).await.unwrap()?;
+                                                  response.body(Body::from(body_content))
+                                                },
+                                            },
+                                            Err(why) => {
+                                                // Application code returned an error. This should not happen, as the implementation should
+                                                // return a valid response.
+                                                return api_impl.as_ref().handle_error(&method, &host, &cookies, why).await;
                                             },
                                         };
 
@@ -176,33 +506,52 @@ where
 
 #[tracing::instrument(skip_all)]
 fn list_employees_validation(
+  query_params: models::ListEmployeesQueryParams,
 ) -> std::result::Result<(
+  models::ListEmployeesQueryParams,
 ), ValidationErrors>
 {
+  query_params.validate()?;
 
 Ok((
+  query_params,
 ))
 }
 /// ListEmployees - GET /api/employee
 #[tracing::instrument(skip_all)]
-async fn list_employees<I, A>(
+async fn list_employees<I, A, E, C>(
   method: Method,
   host: Host,
   cookies: CookieJar,
+  headers: HeaderMap,
+  Query(query_params): Query<models::ListEmployeesQueryParams>,
  State(api_impl): State<I>,
 ) -> Result<Response, StatusCode>
 where
     I: AsRef<A> + Send + Sync,
-    A: apis::skjera::Skjera,
-{
+    A: apis::skjera::Skjera<E, Claims = C>+ apis::ApiKeyAuthHeader<Claims = C>+ apis::CookieAuthentication<Claims = C> + Send + Sync,
+    E: std::fmt::Debug + Send + Sync + 'static,
+        {
+    // Authentication
+    let claims_in_cookie = api_impl.as_ref().extract_claims_from_cookie(&cookies, "id").await;
+    let claims_in_header = api_impl.as_ref().extract_claims_from_header(&headers, "Authorization").await;
+    let claims = None
+             .or(claims_in_cookie)
+             .or(claims_in_header)
+          ;
+    let Some(claims) = claims else {
+        return response_with_status_code_only(StatusCode::UNAUTHORIZED);
+    };
 
       #[allow(clippy::redundant_closure)]
       let validation = tokio::task::spawn_blocking(move ||
     list_employees_validation(
+        query_params,
     )
   ).await.unwrap();
 
   let Ok((
+    query_params,
   )) = validation else {
     return Response::builder()
             .status(StatusCode::BAD_REQUEST)
@@ -211,9 +560,11 @@ where
   };
 
   let result = api_impl.as_ref().list_employees(
-      method,
-      host,
-      cookies,
+      &method,
+      &host,
+      &cookies,
+        &claims,
+        &query_params,
   ).await;
 
   let mut response = Response::builder();
@@ -221,6 +572,134 @@ where
   let resp = match result {
                                             Ok(rsp) => match rsp {
                                                 apis::skjera::ListEmployeesResponse::Status200_ListOfEmployees
+                                                    {
+                                                        body,
+                                                        x_total_count
+                                                    }
+                                                => {
+                                                    if let Some(x_total_count) = x_total_count {
+                                                    let x_total_count = match header::IntoHeaderValue(x_total_count).try_into() {
+                                                        Ok(val) => val,
+                                                        Err(e) => {
+                                                            return Response::builder()
+                                                                    .status(StatusCode::INTERNAL_SERVER_ERROR)
+                                                                    .body(Body::from(format!("An internal server error occurred handling x_total_count header - {}", e))).map_err(|e| { error!(error = ?e); StatusCode::INTERNAL_SERVER_ERROR });
+                                                        }
+                                                    };
+
+
+                                                    {
+                                                      let mut response_headers = response.headers_mut().unwrap();
+                                                      response_headers.insert(
+                                                          HeaderName::from_static("x-total-count"),
+                                                          x_total_count
+                                                      );
+                                                    }
+                                                    }
+                                                  let mut response = response.status(200);
+                                                  {
+                                                    let mut response_headers = response.headers_mut().unwrap();
+                                                    response_headers.insert(
+                                                        CONTENT_TYPE,
+                                                        HeaderValue::from_str("application/json").map_err(|e| { error!(error = ?e); StatusCode::INTERNAL_SERVER_ERROR })?);
+                                                  }
+
+                                                  let body_content =  tokio::task::spawn_blocking(move ||
+                                                      serde_json::to_vec(&body).map_err(|e| {
+                                                        error!(error = ?e);
+                                                        StatusCode::INTERNAL_SERVER_ERROR
+                                                      })).await.unwrap()?;
+                                                  response.body(Body::from(body_content))
+                                                },
+                                            },
+                                            Err(why) => {
+                                                // Application code returned an error. This should not happen, as the implementation should
+                                                // return a valid response.
+                                                return api_impl.as_ref().handle_error(&method, &host, &cookies, why).await;
+                                            },
+                                        };
+
+                                        resp.map_err(|e| { error!(error = ?e); StatusCode::INTERNAL_SERVER_ERROR })
+}
+
+
+    #[derive(validator::Validate)]
+    #[allow(dead_code)]
+    struct UpdateMeBodyValidator<'a> {
+            #[validate(nested)]
+          body: &'a models::UpdateMeRequest,
+    }
+
+
+#[tracing::instrument(skip_all)]
+fn update_me_validation(
+  body: models::UpdateMeRequest,
+) -> std::result::Result<(
+  models::UpdateMeRequest,
+), ValidationErrors>
+{
+              let b = UpdateMeBodyValidator { body: &body };
+              b.validate()?;
+
+Ok((
+  body,
+))
+}
+/// UpdateMe - PUT /api/me
+#[tracing::instrument(skip_all)]
+async fn update_me<I, A, E, C>(
+  method: Method,
+  host: Host,
+  cookies: CookieJar,
+  headers: HeaderMap,
+ State(api_impl): State<I>,
+          Json(body): Json<models::UpdateMeRequest>,
+) -> Result<Response, StatusCode>
+where
+    I: AsRef<A> + Send + Sync,
+    A: apis::skjera::Skjera<E, Claims = C>+ apis::ApiKeyAuthHeader<Claims = C>+ apis::CookieAuthentication<Claims = C> + Send + Sync,
+    E: std::fmt::Debug + Send + Sync + 'static,
+        {
+    // Authentication
+    let claims_in_cookie = api_impl.as_ref().extract_claims_from_cookie(&cookies, "id").await;
+    let claims_in_header = api_impl.as_ref().extract_claims_from_header(&headers, "Authorization").await;
+    let claims = None
+             .or(claims_in_cookie)
+             .or(claims_in_header)
+          ;
+    let Some(claims) = claims else {
+        return response_with_status_code_only(StatusCode::UNAUTHORIZED);
+    };
+
+      #[allow(clippy::redundant_closure)]
+      let validation = tokio::task::spawn_blocking(move ||
+    update_me_validation(
+        body,
+    )
+  ).await.unwrap();
+
+  let Ok((
+    body,
+  )) = validation else {
+    return Response::builder()
+            .status(StatusCode::BAD_REQUEST)
+            .body(Body::from(validation.unwrap_err().to_string()))
+            .map_err(|_| StatusCode::BAD_REQUEST);
+  };
+
+  let result = api_impl.as_ref().update_me(
+      &method,
+      &host,
+      &cookies,
+        &claims,
+        &body,
+  ).await;
+
+  let mut response = Response::builder();
+
+  let resp = match result {
+                                            Ok(rsp) => match rsp {
+                                                apis::skjera::UpdateMeResponse::Status200_TheUpdatedEmployee
                                                     (body)
                                                 => {
                                                   let mut response = response.status(200);
@@ -239,13 +718,22 @@ where
                                                   response.body(Body::from(body_content))
                                                 },
                                             },
-                                            Err(_) => {
+                                            Err(why) => {
                                                 // Application code returned an error. This should not happen, as the implementation should
                                                 // return a valid response.
-                                                response.status(500).body(Body::empty())
+                                                return api_impl.as_ref().handle_error(&method, &host, &cookies, why).await;
                                             },
                                         };
 
                                         resp.map_err(|e| { error!(error = ?e); StatusCode::INTERNAL_SERVER_ERROR })
 }
 
+
+#[allow(dead_code)]
+#[inline]
+fn response_with_status_code_only(code: StatusCode) -> Result<Response, StatusCode> {
+   Response::builder()
+          .status(code)
+          .body(Body::empty())
+          .map_err(|_| code)
+}